@@ -22,6 +22,8 @@ interface NFTAttrs {
 export async function uploadFileToPinata(file: File): Promise<string> {
   const formData = new FormData();
   formData.append('file', file);
+  // CIDv1 is what the proofs commit as `ciphertext_cid`; a CIDv0 hash of the same file differs
+  formData.append('pinataOptions', JSON.stringify({ cidVersion: 1 }));
 
   const res = await axios.post('https://api.pinata.cloud/pinning/pinFileToIPFS', formData, {
     maxContentLength: Infinity,
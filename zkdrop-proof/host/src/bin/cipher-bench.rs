@@ -0,0 +1,98 @@
+//! Compares guest cycles per KB of the dataset cipher verifiers.
+//!
+//! Executes (without proving) `aes_ctr_verifier` and `chacha_verifier` on
+//! plaintexts of increasing size and prints user cycles per KB, so sellers can
+//! pick the cheaper cipher for large datasets.
+//!
+//! ```bash
+//! cargo run --release --bin cipher-bench            # 1, 4, 16 and 64 KB
+//! cargo run --release --bin cipher-bench -- 256 512  # custom sizes in KB
+//! ```
+
+use anyhow::{bail, Result};
+use methods::{AES_CTR_VERIFIER_ELF, CHACHA_VERIFIER_ELF};
+use risc0_zkvm::{default_executor, ExecutorEnv};
+use serde::{de::DeserializeOwned, Serialize};
+use zkdrop_lib::aes_ctr::aes_ctr_encrypt;
+use zkdrop_lib::chacha::chacha_encrypt;
+use zkdrop_lib::types::{
+    AesCtrDecryptionProofInput, AesCtrDecryptionProofOutput, ChaChaDecryptionProofInput,
+    ChaChaDecryptionProofOutput, ChaChaVariant, Compression,
+};
+
+const DEFAULT_SIZES_KB: [usize; 4] = [1, 4, 16, 64];
+const KEY: [u8; 32] = [0x42; 32];
+const AES_IV: [u8; 16] = [0x01; 16];
+
+/// Deterministic printable plaintext of `len` bytes.
+fn plaintext(len: usize) -> String {
+    (0..len).map(|i| char::from(b'a' + (i * 7 % 26) as u8)).collect()
+}
+
+/// Runs `elf` on `input` in the executor and returns its journal and user cycle count.
+fn execute<T: Serialize, O: DeserializeOwned>(elf: &[u8], input: &T) -> Result<(O, u64)> {
+    let env = ExecutorEnv::builder().write(input)?.build()?;
+    let session = default_executor().execute(env, elf)?;
+    Ok((session.journal.decode()?, session.cycles()))
+}
+
+fn aes_ctr_cycles(plaintext: &str) -> Result<u64> {
+    let ciphertext = aes_ctr_encrypt(&KEY, &AES_IV, plaintext.as_bytes()).map_err(anyhow::Error::msg)?;
+    let input = AesCtrDecryptionProofInput {
+        aes_key_hex: hex::encode(KEY).into(),
+        iv_hex: hex::encode(AES_IV),
+        plaintext_utf8: plaintext.into(),
+        ciphertext_hex: hex::encode(ciphertext),
+        compression: Compression::None,
+    };
+    let (output, cycles): (AesCtrDecryptionProofOutput, u64) = execute(AES_CTR_VERIFIER_ELF, &input)?;
+    if !output.is_valid {
+        bail!("guest rejected benchmark input: {}", output.message);
+    }
+    Ok(cycles)
+}
+
+fn chacha_cycles(variant: ChaChaVariant, plaintext: &str) -> Result<u64> {
+    let nonce = vec![0x01; variant.nonce_len()];
+    let ciphertext =
+        chacha_encrypt(variant, &KEY, &nonce, plaintext.as_bytes(), &[]).map_err(anyhow::Error::msg)?;
+    let input = ChaChaDecryptionProofInput {
+        cipher: variant,
+        key_hex: hex::encode(KEY).into(),
+        nonce_hex: hex::encode(nonce),
+        plaintext_utf8: plaintext.into(),
+        ciphertext_hex: hex::encode(ciphertext),
+        aad_hex: None,
+    };
+    let (output, cycles): (ChaChaDecryptionProofOutput, u64) = execute(CHACHA_VERIFIER_ELF, &input)?;
+    if !output.is_valid {
+        bail!("guest rejected benchmark input: {}", output.message);
+    }
+    Ok(cycles)
+}
+
+fn main() -> Result<()> {
+    let sizes_kb = match std::env::args().skip(1).map(|a| a.parse()).collect::<Result<Vec<usize>, _>>() {
+        Ok(sizes) if sizes.contains(&0) => bail!("sizes must be at least 1 KB"),
+        Ok(sizes) if !sizes.is_empty() => sizes,
+        Ok(_) => DEFAULT_SIZES_KB.to_vec(),
+        Err(err) => bail!("sizes must be whole numbers of KB: {}", err),
+    };
+
+    println!("{:>8} | {:>20} | {:>20} | {:>20}", "size", "aes-ctr", "chacha20-poly1305", "xchacha20-poly1305");
+    println!("{:>8} | {:>20} | {:>20} | {:>20}", "(KB)", "cycles/KB", "cycles/KB", "cycles/KB");
+    for kb in sizes_kb {
+        let data = plaintext(kb * 1024);
+        let aes = aes_ctr_cycles(&data)?;
+        let chacha = chacha_cycles(ChaChaVariant::ChaCha20Poly1305, &data)?;
+        let xchacha = chacha_cycles(ChaChaVariant::XChaCha20Poly1305, &data)?;
+        println!(
+            "{:>8} | {:>20} | {:>20} | {:>20}",
+            kb,
+            aes / kb as u64,
+            chacha / kb as u64,
+            xchacha / kb as u64
+        );
+    }
+    Ok(())
+}
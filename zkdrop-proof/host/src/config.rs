@@ -1,6 +1,11 @@
 use std::env;
 use dotenvy::dotenv;
 
+use zeroize::Zeroizing;
+use zkdrop_lib::types::SecretString;
+
+use crate::vault::VaultSecret;
+
 const DEFAULT_PORT : u16 = 8080;
 
 pub fn load_env(){
@@ -19,3 +24,85 @@ pub fn get_address() -> String {
     let port = get_port().unwrap_or(DEFAULT_PORT);
     format!("0.0.0.0:{}", port)
 }
+
+const DEFAULT_WATCHER_POLL_SECS: u64 = 12;
+const DEFAULT_WATCHER_STATE_PATH: &str = "watcher-state.json";
+const DEFAULT_WATCHER_RECEIPTS_DIR: &str = "watcher-receipts";
+const DEFAULT_VAULT_PATH: &str = "dataset-vault.json";
+
+/// Settings for the Escrow event watcher.
+///
+/// The watcher is only started when `WATCHER_RPC_URL`, `ESCROW_ADDRESS` and
+/// `SELLER_PRIVATE_KEY` are all set.
+#[derive(Debug, Clone)]
+pub struct WatcherConfig {
+    /// JSON-RPC endpoint, e.g. `http://127.0.0.1:8545` for a local anvil/hardhat node
+    pub rpc_url: String,
+    /// Address of the deployed Escrow contract
+    pub escrow_address: String,
+    /// Hex-encoded private key of the seller account that submits `setEncryptedSecret`
+    pub seller_private_key: SecretString,
+    /// Block to start scanning from when no progress has been persisted yet
+    pub start_block: Option<u64>,
+    /// Seconds between two polls of the node
+    pub poll_interval_secs: u64,
+    /// File where processed blocks and per-purchase progress are persisted
+    pub state_path: String,
+    /// Directory where the base64 receipts of generated exchange proofs are stored
+    pub receipts_dir: String,
+    /// Proving backend used for the exchange proof (`local`, `bonsai`, `bonsai_snark`)
+    pub prove_mode: Option<String>,
+}
+
+pub fn get_watcher_config() -> Option<WatcherConfig> {
+    Some(WatcherConfig {
+        rpc_url: get_env_var("WATCHER_RPC_URL")?,
+        escrow_address: get_env_var("ESCROW_ADDRESS")?,
+        seller_private_key: SecretString::new(get_env_var("SELLER_PRIVATE_KEY")?),
+        start_block: get_env_var("WATCHER_START_BLOCK").and_then(|v| v.parse().ok()),
+        poll_interval_secs: get_env_var("WATCHER_POLL_INTERVAL_SECS")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_WATCHER_POLL_SECS),
+        state_path: get_env_var("WATCHER_STATE_PATH")
+            .unwrap_or_else(|| DEFAULT_WATCHER_STATE_PATH.to_string()),
+        receipts_dir: get_env_var("WATCHER_RECEIPTS_DIR")
+            .unwrap_or_else(|| DEFAULT_WATCHER_RECEIPTS_DIR.to_string()),
+        prove_mode: get_env_var("WATCHER_PROVE_MODE"),
+    })
+}
+
+pub fn get_vault_path() -> String {
+    get_env_var("VAULT_PATH").unwrap_or_else(|| DEFAULT_VAULT_PATH.to_string())
+}
+
+/// Secret that unlocks the dataset vault. `VAULT_KEY_FILE` takes precedence
+/// over `VAULT_PASSPHRASE`; without either the vault stays locked.
+pub fn get_vault_secret() -> Option<VaultSecret> {
+    if let Some(path) = get_env_var("VAULT_KEY_FILE") {
+        return Some(VaultSecret::KeyFile(path));
+    }
+    get_env_var("VAULT_PASSPHRASE").map(|passphrase| VaultSecret::Passphrase(Zeroizing::new(passphrase)))
+}
+
+/// Bearer token the seller sends as `Authorization: Bearer <token>` to manage
+/// the vault and reference datasets by ID. Without it those requests are rejected.
+pub fn get_seller_api_token() -> Option<SecretString> {
+    get_env_var("SELLER_API_TOKEN")
+        .filter(|token| !token.trim().is_empty())
+        .map(|token| SecretString::new(token.trim().to_string()))
+}
+
+const DEFAULT_IPFS_TIMEOUT_SECS: u64 = 30;
+
+/// Base URL of the IPFS gateway ciphertexts are fetched from by CID, e.g.
+/// `https://ipfs.io` or a local node's `http://127.0.0.1:8080`. Requests
+/// carrying a CID are rejected while it is unset.
+pub fn get_ipfs_gateway_url() -> Option<String> {
+    get_env_var("IPFS_GATEWAY_URL")
+}
+
+pub fn get_ipfs_timeout_secs() -> u64 {
+    get_env_var("IPFS_GATEWAY_TIMEOUT_SECS")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_IPFS_TIMEOUT_SECS)
+}
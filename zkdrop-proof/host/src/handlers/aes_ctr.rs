@@ -1,7 +1,83 @@
-use axum::{extract::Query, Json};
+use axum::{extract::{Query, State}, Json};
 use crate::zkvm;
-use zkdrop_lib::types::{AesCtrDecryptionProofInput, AesCtrDecryptionProofOutput};
-use super::{ProveParams, resolve_mode};
+use zkdrop_lib::types::{
+    AesCtrDecryptionProofInput, AesCtrDecryptionProofOutput, Compression, SecretString,
+};
+use zkdrop_lib::validation::MAX_PAYLOAD_LEN;
+use super::{
+    check_dataset_ciphertext, lookup_dataset, resolve_ciphertext_hex, ApiError, AppState,
+    ProveParams, SellerAuth, resolve_mode,
+};
+
+/// Request body of the AES-CTR decryption proof endpoint.
+///
+/// Either reference a dataset registered in the vault via `dataset_id`, or pass
+/// `aes_key_hex` and `iv_hex` directly. The ciphertext is sent as `ciphertext_hex`
+/// or fetched from the IPFS gateway by `ciphertext_cid`.
+#[derive(serde::Deserialize)]
+pub struct AesCtrDecryptionProofRequest {
+    pub dataset_id: Option<String>,
+    pub aes_key_hex: Option<SecretString>,
+    pub iv_hex: Option<String>,
+    pub plaintext_utf8: SecretString,
+    pub ciphertext_hex: Option<String>,
+    pub ciphertext_cid: Option<String>,
+    #[serde(default)]
+    pub compression: Compression,
+}
+
+impl AesCtrDecryptionProofRequest {
+    /// Builds the guest input, taking key and IV from the vault when `dataset_id` is set
+    /// by the seller. The ciphertext must then match the hash registered for the dataset.
+    pub(super) async fn into_input(
+        self,
+        state: &AppState,
+        seller: Option<&SellerAuth>,
+    ) -> Result<AesCtrDecryptionProofInput, ApiError> {
+        let ciphertext_hex =
+            resolve_ciphertext_hex(state, self.ciphertext_hex, self.ciphertext_cid, MAX_PAYLOAD_LEN)
+                .await?;
+        let (aes_key_hex, iv_hex) = resolve_aes_ctr_key(
+            state,
+            seller,
+            self.dataset_id,
+            self.aes_key_hex,
+            self.iv_hex,
+            &ciphertext_hex,
+        )?;
+
+        Ok(AesCtrDecryptionProofInput {
+            aes_key_hex,
+            iv_hex,
+            plaintext_utf8: self.plaintext_utf8,
+            ciphertext_hex,
+            compression: self.compression,
+        })
+    }
+}
+
+/// Resolves the AES-CTR key and IV, either from the vault via `dataset_id`
+/// (checking `ciphertext_hex` against the registered hash) or as given.
+pub(super) fn resolve_aes_ctr_key(
+    state: &AppState,
+    seller: Option<&SellerAuth>,
+    dataset_id: Option<String>,
+    aes_key_hex: Option<SecretString>,
+    iv_hex: Option<String>,
+    ciphertext_hex: &str,
+) -> Result<(SecretString, String), ApiError> {
+    match (dataset_id, aes_key_hex, iv_hex) {
+        (Some(id), None, None) => {
+            let dataset = lookup_dataset(state, seller, &id)?;
+            check_dataset_ciphertext(&dataset, &id, ciphertext_hex)?;
+            Ok((dataset.aes_key_hex, dataset.iv_hex))
+        }
+        (None, Some(key), Some(iv)) => Ok((key, iv)),
+        _ => Err(ApiError::bad_request(
+            "Provide either dataset_id or both aes_key_hex and iv_hex",
+        )),
+    }
+}
 
 /// Response structure returned by the AES-CTR decryption proof endpoint.
 ///
@@ -15,11 +91,17 @@ pub struct AesCtrDecryptionProofResponse {
 
 /// Handle AES-CTR decryption proof verification request.
 ///
-/// This handler receives a `AesCtrDecryptionProofInput` JSON body and an optional `prove_mode`
+/// This handler receives a `AesCtrDecryptionProofRequest` JSON body and an optional `prove_mode`
 /// query parameter to select proof generation backend (`local`, `bonsai`, or `bonsai-snark`).
 /// It runs the proof using the selected mode, decodes the result, and returns the output along
 /// with the serialized ZK proof receipt in base64 format.
 ///
+/// Instead of `aes_key_hex` and `iv_hex`, a `dataset_id` registered via `POST /datasets`
+/// may be passed. With `"compression": "gzip" | "zstd"` the ciphertext encrypts the
+/// compressed plaintext; the guest decompresses it and commits both content hashes.
+/// `ciphertext_hex` may be replaced by `ciphertext_cid`, the CIDv1 of the pinned
+/// ciphertext, which is fetched from `IPFS_GATEWAY_URL` and checked before proving.
+///
 /// ### Example request:
 /// `POST /aes-verify?prove_mode=local`
 ///
@@ -37,16 +119,25 @@ pub struct AesCtrDecryptionProofResponse {
 /// {
 ///   "output": {
 ///     "is_valid": true,
-///     "message": "AES decryption successful"
+///     "message": "✅ Ciphertext matches AES-CTR encryption",
+///     "compression": "none",
+///     "ciphertext_sha256_hex": "...",
+///     "ciphertext_cid": "bafkrei...",
+///     "compressed_sha256_hex": null,
+///     "plaintext_sha256_hex": "..."
 ///   },
 ///   "receipt_base64": "H4sIAAAAAAAA..."
 /// }
 /// ```
 pub async fn handle_verify(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
     Query(params): Query<ProveParams>,
-    Json(payload): Json<AesCtrDecryptionProofInput>,
-) -> Json<AesCtrDecryptionProofResponse> {
+    Json(payload): Json<AesCtrDecryptionProofRequest>,
+) -> Result<Json<AesCtrDecryptionProofResponse>, ApiError> {
     println!("[AES-Verify] Received request ");
+    let payload = payload.into_input(&state, seller.as_ref()).await?;
+    payload.validate().map_err(ApiError::validation)?;
 
     let mode = resolve_mode(params.prove_mode.as_deref());
     println!("[AES-Verify] Resolved proving mode: {:?}", mode);
@@ -61,5 +152,5 @@ pub async fn handle_verify(
     let receipt_base64 = zkdrop_lib::utils::receipt_to_base64(&receipt);
     println!("[AES-Verify] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
 
-    Json(AesCtrDecryptionProofResponse { output, receipt_base64 })
+    Ok(Json(AesCtrDecryptionProofResponse { output, receipt_base64 }))
 }
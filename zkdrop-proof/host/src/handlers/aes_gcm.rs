@@ -0,0 +1,152 @@
+use axum::{extract::{Query, State}, Json};
+use crate::zkvm;
+use zkdrop_lib::aes_gcm::{AES_GCM_NONCE_LEN, AES_GCM_TAG_LEN};
+use zkdrop_lib::types::{AesGcmEncryptionProofInput, AesGcmEncryptionProofOutput, SecretString};
+use zkdrop_lib::validation::MAX_PAYLOAD_LEN;
+use super::{
+    check_dataset_ciphertext, fetch_ipfs_file, lookup_dataset, ApiError, AppState, ProveParams,
+    SellerAuth, resolve_mode,
+};
+
+/// Request body of the AES-GCM encryption proof endpoint.
+///
+/// The key comes either from a dataset registered in the vault (`dataset_id`)
+/// or from `aes_key_hex`. The nonce is public: either pass `nonce_hex` and
+/// `ciphertext_hex`, or `file_cid`, the CIDv1 of the `nonce || ciphertext || tag`
+/// file pinned by the frontend, which is fetched and split by the host.
+#[derive(serde::Deserialize)]
+pub struct AesGcmEncryptionProofRequest {
+    pub dataset_id: Option<String>,
+    pub aes_key_hex: Option<SecretString>,
+    pub nonce_hex: Option<String>,
+    pub plaintext_utf8: SecretString,
+    pub ciphertext_hex: Option<String>,
+    pub file_cid: Option<String>,
+    pub aad_hex: Option<String>,
+}
+
+impl AesGcmEncryptionProofRequest {
+    /// Builds the guest input, taking the key from the vault when `dataset_id` is set.
+    /// The ciphertext (including the tag) must then match the hash registered for the dataset.
+    async fn into_input(
+        self,
+        state: &AppState,
+        seller: Option<&SellerAuth>,
+    ) -> Result<AesGcmEncryptionProofInput, ApiError> {
+        let (nonce_hex, ciphertext_hex) =
+            match (self.nonce_hex, self.ciphertext_hex, self.file_cid) {
+                (Some(nonce_hex), Some(ciphertext_hex), None) => (nonce_hex, ciphertext_hex),
+                (None, None, Some(cid)) => {
+                    let max_len = AES_GCM_NONCE_LEN + MAX_PAYLOAD_LEN + AES_GCM_TAG_LEN;
+                    let file = fetch_ipfs_file(state, &cid, max_len).await?;
+                    if file.len() < AES_GCM_NONCE_LEN + AES_GCM_TAG_LEN {
+                        return Err(ApiError::bad_request(format!(
+                            "File {} is too short to hold a nonce and tag",
+                            cid
+                        )));
+                    }
+                    let (nonce, ciphertext) = file.split_at(AES_GCM_NONCE_LEN);
+                    (hex::encode(nonce), hex::encode(ciphertext))
+                }
+                _ => {
+                    return Err(ApiError::bad_request(
+                        "Provide either nonce_hex and ciphertext_hex, or file_cid",
+                    ))
+                }
+            };
+        let aes_key_hex = match (self.dataset_id, self.aes_key_hex) {
+            (Some(id), None) => {
+                let dataset = lookup_dataset(state, seller, &id)?;
+                check_dataset_ciphertext(&dataset, &id, &ciphertext_hex)?;
+                dataset.aes_key_hex
+            }
+            (None, Some(key)) => key,
+            _ => {
+                return Err(ApiError::bad_request(
+                    "Exactly one of dataset_id or aes_key_hex must be provided",
+                ))
+            }
+        };
+
+        Ok(AesGcmEncryptionProofInput {
+            aes_key_hex,
+            nonce_hex,
+            plaintext_utf8: self.plaintext_utf8,
+            ciphertext_hex,
+            aad_hex: self.aad_hex,
+        })
+    }
+}
+
+/// Response structure returned by the AES-GCM encryption proof endpoint.
+///
+/// - `output`: verdict plus the public nonce/AAD and SHA-256 commitments to key,
+///   plaintext and ciphertext.
+/// - `receipt_base64`: The serialized ZK proof receipt in base64 encoding.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct AesGcmEncryptionProofResponse {
+    pub output: AesGcmEncryptionProofOutput,
+    pub receipt_base64: String,
+}
+
+/// POST /aes-gcm-verify?prove_mode=local|bonsai|bonsai_snark
+///
+/// Proves that `ciphertext_hex` (ciphertext followed by the 16-byte tag) is the
+/// AES-256-GCM encryption of `plaintext_utf8` under the key and nonce, with the
+/// optional `aad_hex` authenticated. Files produced by the frontend are laid out
+/// as `nonce (12) || ciphertext || tag (16)`; split off the first 12 bytes as
+/// `nonce_hex` and pass the rest as `ciphertext_hex`, or pass the pinned file's
+/// CIDv1 as `file_cid` and let the host fetch and split it.
+///
+/// ### Example request:
+/// ```json
+/// {
+///   "aes_key_hex": "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d",
+///   "nonce_hex": "0102030405060708090a0b0c",
+///   "plaintext_utf8": "example fileeee ! ",
+///   "ciphertext_hex": "...",
+///   "aad_hex": "7a6b64726f70"
+/// }
+/// ```
+///
+/// ### Example response:
+/// ```json
+/// {
+///   "output": {
+///     "is_valid": true,
+///     "message": "✅ Ciphertext and tag match AES-GCM encryption",
+///     "nonce_hex": "0102030405060708090a0b0c",
+///     "aad_hex": "7a6b64726f70",
+///     "key_sha256_hex": "...",
+///     "plaintext_sha256_hex": "...",
+///     "ciphertext_sha256_hex": "...",
+///     "file_cid": "bafkrei..."
+///   },
+///   "receipt_base64": "..."
+/// }
+/// ```
+pub async fn handle_verify(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<AesGcmEncryptionProofRequest>,
+) -> Result<Json<AesGcmEncryptionProofResponse>, ApiError> {
+    println!("[AES-GCM-Verify] Received request");
+    let payload = payload.into_input(&state, seller.as_ref()).await?;
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[AES-GCM-Verify] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_aes_gcm_verify(payload, mode).unwrap();
+    println!("[AES-GCM-Verify] Proof successfully generated.");
+
+    let output: AesGcmEncryptionProofOutput = receipt.journal.decode().unwrap();
+    println!("[AES-GCM-Verify] Output decoded: is_valid = {}, message = {}",
+             output.is_valid, output.message);
+
+    let receipt_base64 = zkdrop_lib::utils::receipt_to_base64(&receipt);
+    println!("[AES-GCM-Verify] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(AesGcmEncryptionProofResponse { output, receipt_base64 }))
+}
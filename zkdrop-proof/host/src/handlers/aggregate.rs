@@ -0,0 +1,186 @@
+use axum::{extract::Query, Json};
+use base64::{engine::general_purpose, Engine as _};
+use crate::zkvm::{self, AGGREGATABLE_GUESTS};
+use risc0_zkvm::sha::Digestible;
+use risc0_zkvm::{Digest, InnerReceipt, Receipt};
+use zkdrop_lib::aggregate::{image_id_bytes, receipt_leaf};
+use zkdrop_lib::merkle::inclusion_proof;
+use zkdrop_lib::types::{AggregatedReceipt, AggregationInput, AggregationOutput};
+use zkdrop_lib::utils::{receipt_to_base64, sha256_hex};
+use zkdrop_lib::validation::{FieldError, MAX_AGGREGATED_RECEIPTS};
+
+use super::{ApiError, ProveParams, resolve_mode};
+
+/// Request body of `/aggregate`: `receipt_base64` values returned by other
+/// endpoints in `local` or `bonsai` mode, in the order their leaves should take.
+#[derive(serde::Deserialize)]
+pub struct AggregationRequest {
+    pub receipts_base64: Vec<String>,
+}
+
+/// One aggregated purchase proof and what a contract needs to settle it
+/// against `merkle_root_hex`.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct AggregatedLeaf {
+    /// Guest that produced the sub-receipt, e.g. `rsa_encrypter`
+    pub guest: String,
+    pub image_id_hex: String,
+    pub journal_sha256_hex: String,
+    pub leaf_hex: String,
+    /// Sibling hashes from the leaf up to the root, bottom first
+    pub proof_hex: Vec<String>,
+}
+
+/// Response returned from the aggregation endpoint
+/// - `output`: receipt count and Merkle root of the sub-journals
+/// - `leaves`: per sub-receipt leaf and inclusion proof, in request order
+/// - `receipt_base64`: base64-encoded receipt for verification
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct AggregationResponse {
+    pub output: AggregationOutput,
+    pub leaves: Vec<AggregatedLeaf>,
+    pub receipt_base64: String,
+}
+
+/// Decodes one sub-receipt, checks it comes from a zkdrop guest and verifies it.
+/// Returns the guest's name and image ID along with the receipt.
+fn decode_receipt(encoded: &str) -> Result<(&'static (&'static str, [u32; 8]), Receipt), String> {
+    let bytes = general_purpose::STANDARD.decode(encoded).map_err(|_| "Invalid base64")?;
+    let receipt: Receipt = bincode::deserialize(&bytes).map_err(|_| "Not a serialized receipt")?;
+    if matches!(receipt.inner, InnerReceipt::Groth16(_)) {
+        return Err(String::from(
+            "SNARK receipts cannot be aggregated, use a local or bonsai mode receipt",
+        ));
+    }
+
+    let image_id = receipt
+        .claim()
+        .ok()
+        .and_then(|claim| claim.as_value().ok().map(|claim| claim.pre.digest()))
+        .ok_or("Receipt claim is pruned")?;
+    let guest = AGGREGATABLE_GUESTS
+        .iter()
+        .find(|(_, id)| Digest::from(*id) == image_id)
+        .ok_or_else(|| format!("Image ID {} is not a zkdrop guest", image_id))?;
+    receipt.verify(guest.1).map_err(|err| format!("Receipt does not verify: {}", err))?;
+    Ok((guest, receipt))
+}
+
+/// POST /aggregate?prove_mode=local|bonsai|bonsai_snark
+///
+/// Folds many purchase proofs (AES, RSA, ECIES, hash-lock, ...) into one
+/// receipt so a batch of purchases settles with a single on-chain
+/// verification. Each sub-receipt is checked on the host, passed to the prover
+/// as an assumption and verified in the guest with `env::verify` over its image
+/// ID and journal. The journal commits the Merkle root over the leaves
+/// `SHA-256(0x00 || image_id || SHA-256(journal))`; the response returns each
+/// leaf's inclusion proof, so a contract can settle a purchase from its
+/// journal, the proof and the verified root.
+///
+/// ### Example Request Body:
+/// ```json
+/// {
+///   "receipts_base64": ["AgAAAAAAAAA...", "AgAAAAAAAAA..."]
+/// }
+/// ```
+///
+/// ### Example response:
+/// ```json
+/// {
+///   "output": {
+///     "is_valid": true,
+///     "message": "✅ Aggregated 2 receipts",
+///     "receipt_count": 2,
+///     "merkle_root_hex": "..."
+///   },
+///   "leaves": [
+///     {
+///       "guest": "rsa_encrypter",
+///       "image_id_hex": "...",
+///       "journal_sha256_hex": "...",
+///       "leaf_hex": "...",
+///       "proof_hex": ["..."]
+///     }
+///   ],
+///   "receipt_base64": "..."
+/// }
+/// ```
+pub async fn handle_aggregate(
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<AggregationRequest>,
+) -> Result<Json<AggregationResponse>, ApiError> {
+    println!("[Aggregate] Received request with {} receipts", payload.receipts_base64.len());
+    let count = payload.receipts_base64.len();
+    if !(1..=MAX_AGGREGATED_RECEIPTS).contains(&count) {
+        // Checked before any receipt is decoded and verified
+        return Err(ApiError::validation(vec![FieldError {
+            field: String::from("receipts_base64"),
+            message: format!(
+                "must contain between 1 and {} receipts, got {}",
+                MAX_AGGREGATED_RECEIPTS, count
+            ),
+        }]));
+    }
+
+    let mut errors = Vec::new();
+    let mut guests = Vec::new();
+    let mut receipts = Vec::new();
+    for (i, encoded) in payload.receipts_base64.iter().enumerate() {
+        match decode_receipt(encoded) {
+            Ok((guest, receipt)) => {
+                guests.push(guest);
+                receipts.push(receipt);
+            }
+            Err(message) => errors.push(FieldError {
+                field: format!("receipts_base64[{}]", i),
+                message,
+            }),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::validation(errors));
+    }
+
+    let input = AggregationInput {
+        receipts: receipts
+            .iter()
+            .zip(&guests)
+            .map(|(receipt, (_, image_id))| AggregatedReceipt {
+                image_id: *image_id,
+                journal: receipt.journal.bytes.clone(),
+            })
+            .collect(),
+    };
+    input.validate().map_err(ApiError::validation)?;
+    let leaf_hashes: Vec<[u8; 32]> = input.receipts.iter().map(receipt_leaf).collect();
+    let journal_hashes: Vec<String> =
+        input.receipts.iter().map(|sub| sha256_hex(&sub.journal)).collect();
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[Aggregate] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_aggregate(input, &receipts, mode).unwrap();
+    println!("[Aggregate] Proof generated successfully.");
+
+    let output: AggregationOutput = receipt.journal.decode().unwrap();
+    println!("[Aggregate] Output decoded: is_valid = {}, message = {}",
+             output.is_valid, output.message);
+
+    let leaves = guests
+        .into_iter()
+        .zip(journal_hashes)
+        .enumerate()
+        .map(|(i, ((guest, image_id), journal_sha256_hex))| AggregatedLeaf {
+            guest: String::from(*guest),
+            image_id_hex: hex::encode(image_id_bytes(image_id)),
+            journal_sha256_hex,
+            leaf_hex: hex::encode(leaf_hashes[i]),
+            proof_hex: inclusion_proof(&leaf_hashes, i).iter().map(hex::encode).collect(),
+        })
+        .collect();
+
+    let receipt_base64 = receipt_to_base64(&receipt);
+    println!("[Aggregate] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(AggregationResponse { output, leaves, receipt_base64 }))
+}
@@ -0,0 +1,55 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header::AUTHORIZATION, request::Parts, StatusCode},
+};
+use zkdrop_lib::utils::sha256_hex;
+
+use super::{ApiError, AppState};
+
+/// Proof that a request carried the seller's bearer token
+/// (`Authorization: Bearer <SELLER_API_TOKEN>`).
+///
+/// Required by `/datasets` and `/hashlock-reveal`, and by every proof request
+/// that references a vault entry through `dataset_id`. Proof handlers take it as
+/// `Option<SellerAuth>`, so requests carrying their own key stay unauthenticated.
+pub struct SellerAuth(());
+
+#[async_trait]
+impl FromRequestParts<AppState> for SellerAuth {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        let expected = state.seller_token.as_ref().ok_or_else(|| {
+            ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Seller authentication is disabled (set SELLER_API_TOKEN)",
+            )
+        })?;
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| ApiError::new(StatusCode::UNAUTHORIZED, "Missing seller bearer token"))?;
+
+        // Compare digests so the comparison time does not depend on a common prefix
+        if sha256_hex(token.trim().as_bytes()) != sha256_hex(expected.expose_secret().as_bytes()) {
+            return Err(ApiError::new(StatusCode::UNAUTHORIZED, "Invalid seller bearer token"));
+        }
+        Ok(Self(()))
+    }
+}
+
+/// Returns the seller authentication, or `401` naming the field that needs it.
+pub fn require_seller<'a>(
+    seller: Option<&'a SellerAuth>,
+    field: &str,
+) -> Result<&'a SellerAuth, ApiError> {
+    seller.ok_or_else(|| {
+        ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            format!("{} requires the seller bearer token (Authorization: Bearer <SELLER_API_TOKEN>)", field),
+        )
+    })
+}
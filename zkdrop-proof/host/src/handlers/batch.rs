@@ -0,0 +1,132 @@
+use axum::{extract::{Query, State}, Json};
+use crate::rsa_keys::{random_wrap_seed, RsaPublicKeyParam};
+use crate::zkvm;
+use zkdrop_lib::types::{
+    BatchEncryptAesKeyInput, BatchEncryptAesKeyOutput, BatchRecipient, OaepParams, SecretString
+};
+use zkdrop_lib::utils::receipt_to_base64;
+use zkdrop_lib::validation::FieldError;
+
+use super::{resolve_aes_key_hex, ApiError, AppState, ProveParams, SellerAuth, resolve_mode};
+
+/// One buyer of a batch: either an RSA key (any encoding accepted by
+/// `/rsa-encrypt`, also as `rsa_pubkey_base64`) or an X25519 key for ECIES.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+pub enum BatchRecipientRequest {
+    Rsa {
+        #[serde(alias = "rsa_pubkey_base64")]
+        rsa_pubkey: RsaPublicKeyParam,
+    },
+    Ecies { ecies_pubkey_hex: String },
+}
+
+impl BatchRecipientRequest {
+    /// Normalises an RSA key to base64 SPKI DER; errors are reported under `field`.
+    pub(super) fn into_recipient(self, field: &str) -> Result<BatchRecipient, FieldError> {
+        match self {
+            BatchRecipientRequest::Rsa { rsa_pubkey } => rsa_pubkey
+                .to_spki_base64(&format!("{}.rsa_pubkey", field))
+                .map(BatchRecipient::Rsa),
+            BatchRecipientRequest::Ecies { ecies_pubkey_hex } => {
+                Ok(BatchRecipient::Ecies(ecies_pubkey_hex))
+            }
+        }
+    }
+}
+
+/// Request body of `/rsa-encrypt-batch`: the AES key is taken either from the
+/// vault (`dataset_id`) or from `aes_key_hex`.
+#[derive(serde::Deserialize)]
+pub struct BatchEncryptAesKeyRequest {
+    pub dataset_id: Option<String>,
+    pub aes_key_hex: Option<SecretString>,
+    pub recipients: Vec<BatchRecipientRequest>,
+    #[serde(default)]
+    pub oaep: OaepParams,
+}
+
+/// Response returned from the batch wrapping endpoint
+/// - `output`: AES key commitment and one wrapped key per recipient, in request order
+/// - `receipt_base64`: base64-encoded receipt for verification
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct BatchEncryptAesKeyResponse {
+    pub output: BatchEncryptAesKeyOutput,
+    pub receipt_base64: String,
+}
+
+/// POST /rsa-encrypt-batch?prove_mode=local|bonsai|bonsai_snark
+///
+/// Wraps one AES key to many buyers in a single proof, amortising proving cost
+/// across the licences of a listing. RSA and ECIES recipients may be mixed; the
+/// journal lists `(pubkey_sha256_hex, enc_aes_key_hex)` per recipient and commits
+/// the AES key once as `aes_key_sha256_hex`.
+///
+/// ### Example Request Body:
+/// ```json
+/// {
+///   "dataset_id": "1",
+///   "recipients": [
+///     { "rsa_pubkey": "MIIBIjANBgkqhkiG9..." },
+///     { "rsa_pubkey": "-----BEGIN PUBLIC KEY-----\n..." },
+///     { "ecies_pubkey_hex": "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a" }
+///   ]
+/// }
+/// ```
+///
+/// ### Example response:
+/// ```json
+/// {
+///   "output": {
+///     "is_valid": true,
+///     "message": "✅ AES key wrapped for 3 recipients",
+///     "aes_key_sha256_hex": "...",
+///     "wrapped": [
+///       { "pubkey_sha256_hex": "...", "enc_aes_key_hex": "..." }
+///     ],
+///     "oaep": { "digest": "sha256", "mgf_digest": "sha256", "label_hex": null }
+///   },
+///   "receipt_base64": "..."
+/// }
+/// ```
+pub async fn handle_rsa_encrypt_batch(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<BatchEncryptAesKeyRequest>,
+) -> Result<Json<BatchEncryptAesKeyResponse>, ApiError> {
+    println!("[Batch-Encrypt] Received request for {} recipients", payload.recipients.len());
+    let mut recipients = Vec::with_capacity(payload.recipients.len());
+    let mut errors = Vec::new();
+    for (i, recipient) in payload.recipients.into_iter().enumerate() {
+        match recipient.into_recipient(&format!("recipients[{}]", i)) {
+            Ok(recipient) => recipients.push(recipient),
+            Err(err) => errors.push(err),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::validation(errors));
+    }
+
+    let payload = BatchEncryptAesKeyInput {
+        aes_key_hex: resolve_aes_key_hex(&state, seller.as_ref(), payload.dataset_id.as_deref(), payload.aes_key_hex)?,
+        recipients,
+        oaep: payload.oaep,
+        wrap_seed_hex: random_wrap_seed(),
+    };
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[Batch-Encrypt] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_batch_encrypt(payload, mode).unwrap();
+    println!("[Batch-Encrypt] Proof generated successfully.");
+
+    let output: BatchEncryptAesKeyOutput = receipt.journal.decode().unwrap();
+    println!("[Batch-Encrypt] Output decoded from journal. Message : {}", output.message);
+
+    let receipt_base64 = receipt_to_base64(&receipt);
+    println!("[Batch-Encrypt] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(BatchEncryptAesKeyResponse { output, receipt_base64 }))
+}
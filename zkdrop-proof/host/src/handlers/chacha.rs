@@ -0,0 +1,127 @@
+use axum::{extract::{Query, State}, Json};
+use crate::zkvm;
+use zkdrop_lib::types::{ChaChaDecryptionProofInput, ChaChaDecryptionProofOutput, ChaChaVariant, SecretString};
+use super::{
+    check_dataset_ciphertext, lookup_dataset, ApiError, AppState, ProveParams, SellerAuth,
+    resolve_mode,
+};
+
+/// Request body of the ChaCha20-Poly1305 decryption proof endpoint.
+///
+/// The key comes either from a dataset registered in the vault (`dataset_id`)
+/// or from `key_hex`. The nonce is public and always passed explicitly.
+#[derive(serde::Deserialize)]
+pub struct ChaChaDecryptionProofRequest {
+    #[serde(default)]
+    pub cipher: ChaChaVariant,
+    pub dataset_id: Option<String>,
+    pub key_hex: Option<SecretString>,
+    pub nonce_hex: String,
+    pub plaintext_utf8: SecretString,
+    pub ciphertext_hex: String,
+    pub aad_hex: Option<String>,
+}
+
+impl ChaChaDecryptionProofRequest {
+    /// Builds the guest input, taking the key from the vault when `dataset_id` is set.
+    /// The ciphertext (including the tag) must then match the hash registered for the dataset.
+    fn into_input(
+        self,
+        state: &AppState,
+        seller: Option<&SellerAuth>,
+    ) -> Result<ChaChaDecryptionProofInput, ApiError> {
+        let key_hex = match (self.dataset_id, self.key_hex) {
+            (Some(id), None) => {
+                let dataset = lookup_dataset(state, seller, &id)?;
+                check_dataset_ciphertext(&dataset, &id, &self.ciphertext_hex)?;
+                dataset.aes_key_hex
+            }
+            (None, Some(key)) => key,
+            _ => {
+                return Err(ApiError::bad_request(
+                    "Exactly one of dataset_id or key_hex must be provided",
+                ))
+            }
+        };
+
+        Ok(ChaChaDecryptionProofInput {
+            cipher: self.cipher,
+            key_hex,
+            nonce_hex: self.nonce_hex,
+            plaintext_utf8: self.plaintext_utf8,
+            ciphertext_hex: self.ciphertext_hex,
+            aad_hex: self.aad_hex,
+        })
+    }
+}
+
+/// Response structure returned by the ChaCha20-Poly1305 decryption proof endpoint.
+///
+/// - `output`: The decoded result of the verification.
+/// - `receipt_base64`: The serialized ZK proof receipt in base64 encoding.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct ChaChaDecryptionProofResponse {
+    pub output: ChaChaDecryptionProofOutput,
+    pub receipt_base64: String,
+}
+
+/// POST /chacha-verify?prove_mode=local|bonsai|bonsai_snark
+///
+/// Proves that `ciphertext_hex` (ciphertext followed by the 16-byte tag) is the
+/// ChaCha20-Poly1305 encryption of `plaintext_utf8`. Set `"cipher": "xchacha20-poly1305"`
+/// for the 24-byte nonce variant. Needs far fewer guest cycles than `/aes-verify`
+/// for large datasets (see `cargo run --release --bin cipher-bench`).
+///
+/// ### Example request:
+/// ```json
+/// {
+///   "cipher": "chacha20-poly1305",
+///   "key_hex": "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d",
+///   "nonce_hex": "010101010101010101010101",
+///   "plaintext_utf8": "example fileeee ! ",
+///   "ciphertext_hex": "894ddbc2b6927493b44b17580b8b5c0410e17e4cbb13df0beef98b1ded1b01ce4be8"
+/// }
+/// ```
+///
+/// ### Example response:
+/// ```json
+/// {
+///   "output": {
+///     "is_valid": true,
+///     "message": "✅ Ciphertext and tag match ChaCha20-Poly1305 encryption",
+///     "cipher": "chacha20-poly1305",
+///     "nonce_hex": "010101010101010101010101",
+///     "aad_hex": null,
+///     "key_sha256_hex": "...",
+///     "plaintext_sha256_hex": "...",
+///     "ciphertext_sha256_hex": "...",
+///     "ciphertext_cid": "bafkrei..."
+///   },
+///   "receipt_base64": "..."
+/// }
+/// ```
+pub async fn handle_verify(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<ChaChaDecryptionProofRequest>,
+) -> Result<Json<ChaChaDecryptionProofResponse>, ApiError> {
+    println!("[ChaCha-Verify] Received request");
+    let payload = payload.into_input(&state, seller.as_ref())?;
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[ChaCha-Verify] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_chacha_verify(payload, mode).unwrap();
+    println!("[ChaCha-Verify] Proof successfully generated.");
+
+    let output: ChaChaDecryptionProofOutput = receipt.journal.decode().unwrap();
+    println!("[ChaCha-Verify] Output decoded: is_valid = {}, message = {}",
+             output.is_valid, output.message);
+
+    let receipt_base64 = zkdrop_lib::utils::receipt_to_base64(&receipt);
+    println!("[ChaCha-Verify] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(ChaChaDecryptionProofResponse { output, receipt_base64 }))
+}
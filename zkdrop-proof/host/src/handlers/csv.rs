@@ -0,0 +1,99 @@
+use axum::{extract::{Query, State}, Json};
+use crate::zkvm;
+use zkdrop_lib::types::{CsvPropertiesInput, CsvPropertiesOutput, CsvSchema};
+
+use super::aes_ctr::AesCtrDecryptionProofRequest;
+use super::{ApiError, AppState, ProveParams, SellerAuth, resolve_mode};
+
+/// Request body of `/csv-properties`: an `/aes-verify` request plus the
+/// declared CSV schema.
+#[derive(serde::Deserialize)]
+pub struct CsvPropertiesRequest {
+    #[serde(flatten)]
+    pub decryption: AesCtrDecryptionProofRequest,
+    pub schema: CsvSchema,
+}
+
+/// Response structure returned by the CSV property proof endpoint.
+///
+/// - `output`: row count and per-column statistics with the dataset commitments.
+/// - `receipt_base64`: The serialized ZK proof receipt in base64 encoding.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct CsvPropertiesResponse {
+    pub output: CsvPropertiesOutput,
+    pub receipt_base64: String,
+}
+
+/// POST /csv-properties?prove_mode=local|bonsai|bonsai_snark
+///
+/// Proves what an AES-CTR encrypted CSV dataset contains without revealing it:
+/// the header matches `schema.columns`, every value parses as its declared
+/// type (`string`, `integer`, `number` or `boolean`; empty fields are nulls),
+/// and the journal commits the row count, per-column null counts and numeric
+/// min/max/mean with `ciphertext_sha256_hex`. Accepts the same fields as
+/// `/aes-verify`.
+///
+/// ### Example request:
+/// ```json
+/// {
+///   "dataset_id": "1",
+///   "plaintext_utf8": "city,population\nParis,2102650\nLyon,\n",
+///   "ciphertext_hex": "...",
+///   "schema": {
+///     "delimiter": ",",
+///     "columns": [
+///       { "name": "city", "type": "string" },
+///       { "name": "population", "type": "integer" }
+///     ]
+///   }
+/// }
+/// ```
+///
+/// ### Example response:
+/// ```json
+/// {
+///   "output": {
+///     "is_valid": true,
+///     "message": "✅ CSV matches the declared schema",
+///     "iv_hex": "...",
+///     "key_sha256_hex": "...",
+///     "ciphertext_sha256_hex": "...",
+///     "compression": "none",
+///     "delimiter": ",",
+///     "row_count": 2,
+///     "columns": [
+///       { "name": "city", "type": "string", "null_count": 0, "min": null, "max": null, "mean": null },
+///       { "name": "population", "type": "integer", "null_count": 1, "min": 2102650.0, "max": 2102650.0, "mean": 2102650.0 }
+///     ]
+///   },
+///   "receipt_base64": "..."
+/// }
+/// ```
+pub async fn handle_csv_properties(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<CsvPropertiesRequest>,
+) -> Result<Json<CsvPropertiesResponse>, ApiError> {
+    println!("[CSV-Properties] Received request");
+    let payload = CsvPropertiesInput {
+        decryption: payload.decryption.into_input(&state, seller.as_ref()).await?,
+        schema: payload.schema,
+    };
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[CSV-Properties] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_csv_prove(payload, mode).unwrap();
+    println!("[CSV-Properties] Proof successfully generated.");
+
+    let output: CsvPropertiesOutput = receipt.journal.decode().unwrap();
+    println!("[CSV-Properties] Output decoded: is_valid = {}, rows = {}",
+             output.is_valid, output.row_count);
+
+    let receipt_base64 = zkdrop_lib::utils::receipt_to_base64(&receipt);
+    println!("[CSV-Properties] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(CsvPropertiesResponse { output, receipt_base64 }))
+}
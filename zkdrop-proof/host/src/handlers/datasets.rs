@@ -0,0 +1,99 @@
+use axum::{extract::State, http::StatusCode, Json};
+use crate::vault::DatasetKeyMaterial;
+use zkdrop_lib::types::SecretString;
+use zkdrop_lib::validation::{FieldError, Validator, AES_CTR_IV_LEN, AES_KEY_LEN, DIGEST_LEN};
+
+use super::{unlocked_vault, ApiError, AppState, SellerAuth};
+
+/// Longest dataset ID accepted (listing token IDs are decimal `uint256`s)
+const MAX_DATASET_ID_LEN: usize = 78;
+
+/// Request body for registering a dataset in the vault.
+///
+/// - `dataset_id`: listing token ID of the dataset NFT
+/// - remaining fields: see [`DatasetKeyMaterial`]
+#[derive(serde::Deserialize)]
+pub struct RegisterDatasetRequest {
+    pub dataset_id: String,
+    pub aes_key_hex: SecretString,
+    pub iv_hex: String,
+    pub ciphertext_sha256_hex: String,
+}
+
+impl RegisterDatasetRequest {
+    /// Checks the ID and the key, IV and hash lengths before anything is stored.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        if self.dataset_id.is_empty() {
+            v.error("dataset_id", String::from("must not be empty"));
+        } else if !self
+            .dataset_id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        {
+            v.error("dataset_id", String::from("must only contain letters, digits, '-' and '_'"));
+        } else {
+            v.max_len("dataset_id", self.dataset_id.len(), MAX_DATASET_ID_LEN);
+        }
+        v.hex_exact("aes_key_hex", self.aes_key_hex.expose_secret(), AES_KEY_LEN);
+        v.hex_exact("iv_hex", &self.iv_hex, AES_CTR_IV_LEN);
+        v.hex_exact("ciphertext_sha256_hex", &self.ciphertext_sha256_hex, DIGEST_LEN);
+        v.finish()
+    }
+}
+
+/// Response listing the registered dataset IDs (never any key material).
+#[derive(serde::Serialize)]
+pub struct DatasetListResponse {
+    pub dataset_ids: Vec<String>,
+}
+
+/// POST /datasets
+///
+/// Registers the AES key, IV and ciphertext hash of a listed dataset once, so later
+/// proof requests can pass `"dataset_id"` instead of `"aes_key_hex"`. Requires the
+/// seller bearer token, as does every request referencing a `dataset_id`.
+///
+/// ### Example Request Body:
+/// ```json
+/// {
+///   "dataset_id": "1",
+///   "aes_key_hex": "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d",
+///   "iv_hex": "01020300000000000000000000000000",
+///   "ciphertext_sha256_hex": "9dc42e9c375a1a5c762ab05ccba4763fb07e08e414673b0f11208a79e8925381"
+/// }
+/// ```
+///
+/// Responds with `201 Created` and the list of registered dataset IDs.
+pub async fn handle_register(
+    State(state): State<AppState>,
+    _seller: SellerAuth,
+    Json(payload): Json<RegisterDatasetRequest>,
+) -> Result<(StatusCode, Json<DatasetListResponse>), ApiError> {
+    payload.validate().map_err(ApiError::validation)?;
+    println!("[Datasets] Registering dataset {}", payload.dataset_id);
+
+    let vault = unlocked_vault(&state)?;
+
+    let material = DatasetKeyMaterial {
+        aes_key_hex: payload.aes_key_hex,
+        iv_hex: payload.iv_hex.to_lowercase(),
+        ciphertext_sha256_hex: payload.ciphertext_sha256_hex.to_lowercase(),
+    };
+    vault
+        .register(&payload.dataset_id, material)
+        .map_err(ApiError::internal)?;
+
+    Ok((StatusCode::CREATED, Json(DatasetListResponse { dataset_ids: vault.dataset_ids() })))
+}
+
+/// GET /datasets
+///
+/// Lists the IDs of all datasets registered in the vault. Requires the seller bearer token.
+pub async fn handle_list(
+    State(state): State<AppState>,
+    _seller: SellerAuth,
+) -> Json<DatasetListResponse> {
+    let dataset_ids = state.vault.as_ref().map(|vault| vault.dataset_ids()).unwrap_or_default();
+    Json(DatasetListResponse { dataset_ids })
+}
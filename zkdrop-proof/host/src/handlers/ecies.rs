@@ -0,0 +1,135 @@
+use axum::{extract::{Query, State}, Json};
+use crate::zkvm;
+use zkdrop_lib::types::{
+    EciesEncryptAesKeyInput, EciesEncryptedAesKeyInput,
+    EciesEncryptAesKeyOutput, EciesEncryptedAesKeyOutput, SecretString
+};
+use zkdrop_lib::utils::receipt_to_base64;
+
+use super::{resolve_aes_key_hex, ApiError, AppState, ProveParams, SellerAuth, resolve_mode};
+
+/// Request body of `/ecies-encrypt`: the AES key is taken either from the vault
+/// (`dataset_id`) or from `aes_key_hex`.
+#[derive(serde::Deserialize)]
+pub struct EciesEncryptAesKeyRequest {
+    pub dataset_id: Option<String>,
+    pub aes_key_hex: Option<SecretString>,
+    pub ecies_pubkey_hex: String,
+}
+
+/// Request body of `/ecies-verify`: the AES key is taken either from the vault
+/// (`dataset_id`) or from `aes_key_hex`.
+#[derive(serde::Deserialize)]
+pub struct EciesEncryptedAesKeyRequest {
+    pub dataset_id: Option<String>,
+    pub aes_key_hex: Option<SecretString>,
+    pub ecies_pubkey_hex: String,
+    pub enc_aes_key_hex: String,
+}
+
+/// Response returned from ECIES encryption endpoint
+/// - `output`: the ECIES envelope of the AES key, the recipient key hash and the AES key hash
+/// - `receipt_base64`: base64-encoded receipt for verification
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct EciesEncryptAesKeyResponse {
+    pub output: EciesEncryptAesKeyOutput,
+    pub receipt_base64: String,
+}
+
+/// Response returned from ECIES verification endpoint
+/// - `output`: validity status, the recipient key hash, the envelope and the AES key hash
+/// - `receipt_base64`: base64-encoded receipt for verification
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct EciesEncryptedAesKeyResponse {
+    pub output: EciesEncryptedAesKeyOutput,
+    pub receipt_base64: String,
+}
+
+/// POST /ecies-encrypt?prove_mode=local|bonsai|bonsai_snark
+///
+/// Wraps the AES key to the buyer's X25519 public key (see `zkdrop_lib::ecies`)
+/// and proves the wrapping was done correctly.
+///
+/// ### Example Request Body:
+/// ```json
+/// {
+///   "aes_key_hex": "09c40804a785de29d9e199df192549069fced35ab05058332da2c51318a034d0",
+///   "ecies_pubkey_hex": "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"
+/// }
+/// ```
+///
+/// ### Example `curl`:
+/// ```bash
+/// curl -X POST "http://localhost:8081/ecies-encrypt?prove_mode=local" \
+///      -H "Content-Type: application/json" \
+///      -d '{"aes_key_hex":"...", "ecies_pubkey_hex":"..."}'
+/// ```
+pub async fn handle_encrypt(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<EciesEncryptAesKeyRequest>,
+) -> Result<Json<EciesEncryptAesKeyResponse>, ApiError> {
+    println!("[ECIES-Encrypt] Received request");
+    let payload = EciesEncryptAesKeyInput {
+        aes_key_hex: resolve_aes_key_hex(&state, seller.as_ref(), payload.dataset_id.as_deref(), payload.aes_key_hex)?,
+        ecies_pubkey_hex: payload.ecies_pubkey_hex,
+    };
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[ECIES-Encrypt] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_ecies_encrypt(payload, mode).unwrap();
+    println!("[ECIES-Encrypt] Proof generated successfully.");
+
+    let output: EciesEncryptAesKeyOutput = receipt.journal.decode().unwrap();
+    println!("[ECIES-Encrypt] Output decoded from journal. Encrypted AES key: {}", output.enc_aes_key_hex);
+
+    let receipt_base64 = receipt_to_base64(&receipt);
+    println!("[ECIES-Encrypt] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(EciesEncryptAesKeyResponse { output, receipt_base64 }))
+}
+
+/// POST /ecies-verify?prove_mode=local|bonsai|bonsai_snark
+///
+/// Proves that `enc_aes_key_hex` is the ECIES envelope of the AES key for the
+/// given X25519 public key. `"dataset_id"` may replace `"aes_key_hex"`.
+///
+/// ### Example Request Body:
+/// ```json
+/// {
+///   "aes_key_hex": "09c40804a785de29d9e199df192549069fced35ab05058332da2c51318a034d0",
+///   "ecies_pubkey_hex": "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a",
+///   "enc_aes_key_hex": "..."
+/// }
+/// ```
+pub async fn handle_verify(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<EciesEncryptedAesKeyRequest>,
+) -> Result<Json<EciesEncryptedAesKeyResponse>, ApiError> {
+    println!("[ECIES-Verify] Received request");
+    let payload = EciesEncryptedAesKeyInput {
+        aes_key_hex: resolve_aes_key_hex(&state, seller.as_ref(), payload.dataset_id.as_deref(), payload.aes_key_hex)?,
+        ecies_pubkey_hex: payload.ecies_pubkey_hex,
+        enc_aes_key_hex: payload.enc_aes_key_hex,
+    };
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[ECIES-Verify] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_ecies_verify(payload, mode).unwrap();
+    println!("[ECIES-Verify] Proof generated successfully.");
+
+    let output: EciesEncryptedAesKeyOutput = receipt.journal.decode().unwrap();
+    println!("[ECIES-Verify] Output decoded from journal. Message : {}", output.message);
+
+    let receipt_base64 = receipt_to_base64(&receipt);
+    println!("[ECIES-Verify] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(EciesEncryptedAesKeyResponse { output, receipt_base64 }))
+}
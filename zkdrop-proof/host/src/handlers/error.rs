@@ -0,0 +1,52 @@
+use axum::{http::StatusCode, response::{IntoResponse, Response}, Json};
+use zkdrop_lib::validation::FieldError;
+
+/// Error returned by handlers, rendered as JSON:
+///
+/// ```json
+/// {
+///   "message": "Invalid input",
+///   "errors": [{ "field": "iv_hex", "message": "must be 16 bytes, got 12" }]
+/// }
+/// ```
+///
+/// `errors` is only present for validation failures.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+    pub errors: Vec<FieldError>,
+}
+
+#[derive(serde::Serialize)]
+struct ApiErrorBody {
+    message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<FieldError>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self { status, message: message.into(), errors: Vec::new() }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    /// `400 Bad Request` carrying the field-level errors of an input's `validate()`.
+    pub fn validation(errors: Vec<FieldError>) -> Self {
+        Self { status: StatusCode::BAD_REQUEST, message: String::from("Invalid input"), errors }
+    }
+
+    pub fn internal(err: anyhow::Error) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", err))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ApiErrorBody { message: self.message, errors: self.errors };
+        (self.status, Json(body)).into_response()
+    }
+}
@@ -0,0 +1,169 @@
+use axum::{extract::{Query, State}, Json};
+use crate::zkvm;
+use zkdrop_lib::types::{
+    EthEncryptAesKeyInput, EthEncryptedAesKeyInput, EthEncryptedData,
+    EthEncryptAesKeyOutput, EthEncryptedAesKeyOutput, SecretString
+};
+use zkdrop_lib::utils::receipt_to_base64;
+
+use super::{resolve_aes_key_hex, ApiError, AppState, ProveParams, SellerAuth, resolve_mode};
+
+/// Request body of `/eth-encrypt`: the AES key is taken either from the vault
+/// (`dataset_id`) or from `aes_key_hex`.
+#[derive(serde::Deserialize)]
+pub struct EthEncryptAesKeyRequest {
+    pub dataset_id: Option<String>,
+    pub aes_key_hex: Option<SecretString>,
+    pub eth_pubkey_base64: String,
+}
+
+/// Request body of `/eth-verify`: the AES key is taken either from the vault
+/// (`dataset_id`) or from `aes_key_hex`. `ephemeral_secret_hex` is needed for
+/// envelopes this host did not seal.
+#[derive(serde::Deserialize)]
+pub struct EthEncryptedAesKeyRequest {
+    pub dataset_id: Option<String>,
+    pub aes_key_hex: Option<SecretString>,
+    pub eth_pubkey_base64: String,
+    pub encrypted: EthEncryptedData,
+    pub ephemeral_secret_hex: Option<SecretString>,
+}
+
+/// Response returned from the wallet encryption endpoint
+/// - `output`: the eth-sig-util envelope of the AES key, the wallet key and the AES key hash
+/// - `eth_decrypt_payload`: the envelope as `0x`-prefixed hex of its JSON, the form
+///   `eth_decrypt` expects and the value to store via `setEncryptedSecret`
+/// - `receipt_base64`: base64-encoded receipt for verification
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct EthEncryptAesKeyResponse {
+    pub output: EthEncryptAesKeyOutput,
+    pub eth_decrypt_payload: String,
+    pub receipt_base64: String,
+}
+
+/// Response returned from the wallet encryption verification endpoint
+/// - `output`: validity status, the wallet key, the envelope and the AES key hash
+/// - `receipt_base64`: base64-encoded receipt for verification
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct EthEncryptedAesKeyResponse {
+    pub output: EthEncryptedAesKeyOutput,
+    pub receipt_base64: String,
+}
+
+/// POST /eth-encrypt?prove_mode=local|bonsai|bonsai_snark
+///
+/// Seals the AES key to the buyer's wallet encryption key (from
+/// `eth_getEncryptionPublicKey`) in the `x25519-xsalsa20-poly1305` format, so the
+/// buyer recovers it with `eth_decrypt` instead of a separate RSA keypair.
+/// The decrypted message is the AES key as lowercase hex.
+///
+/// ### Example Request Body:
+/// ```json
+/// {
+///   "aes_key_hex": "09c40804a785de29d9e199df192549069fced35ab05058332da2c51318a034d0",
+///   "eth_pubkey_base64": "mtrHOOhJ0+9u+xq5MVr2GqCgLCKk9fl7nBDxLXg/0Fw="
+/// }
+/// ```
+///
+/// ### Example response:
+/// ```json
+/// {
+///   "output": {
+///     "is_valid": true,
+///     "message": "✅ x25519-xsalsa20-poly1305 encryption successful",
+///     "encrypted": {
+///       "version": "x25519-xsalsa20-poly1305",
+///       "nonce": "...",
+///       "ephemPublicKey": "...",
+///       "ciphertext": "..."
+///     },
+///     "eth_pubkey_base64": "mtrHOOhJ0+9u+xq5MVr2GqCgLCKk9fl7nBDxLXg/0Fw=",
+///     "aes_key_sha256_hex": "..."
+///   },
+///   "eth_decrypt_payload": "0x7b2276657273696f6e223a...",
+///   "receipt_base64": "..."
+/// }
+/// ```
+pub async fn handle_encrypt(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<EthEncryptAesKeyRequest>,
+) -> Result<Json<EthEncryptAesKeyResponse>, ApiError> {
+    println!("[ETH-Encrypt] Received request");
+    let payload = EthEncryptAesKeyInput {
+        aes_key_hex: resolve_aes_key_hex(&state, seller.as_ref(), payload.dataset_id.as_deref(), payload.aes_key_hex)?,
+        eth_pubkey_base64: payload.eth_pubkey_base64,
+    };
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[ETH-Encrypt] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_eth_encrypt(payload, mode).unwrap();
+    println!("[ETH-Encrypt] Proof generated successfully.");
+
+    let output: EthEncryptAesKeyOutput = receipt.journal.decode().unwrap();
+    println!("[ETH-Encrypt] Output decoded from journal. Message : {}", output.message);
+
+    let eth_decrypt_payload = format!(
+        "0x{}",
+        hex::encode(serde_json::to_vec(&output.encrypted).unwrap())
+    );
+
+    let receipt_base64 = receipt_to_base64(&receipt);
+    println!("[ETH-Encrypt] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(EthEncryptAesKeyResponse { output, eth_decrypt_payload, receipt_base64 }))
+}
+
+/// POST /eth-verify?prove_mode=local|bonsai|bonsai_snark
+///
+/// Proves that `encrypted` seals the AES key to the given wallet encryption key.
+/// `"dataset_id"` may replace `"aes_key_hex"`. Envelopes from `/eth-encrypt`
+/// verify as they are. Envelopes sealed elsewhere, e.g. by `eth-sig-util`
+/// `encrypt` with a random sender key, also need `ephemeral_secret_hex`, the
+/// sender's ephemeral X25519 secret, as a private witness.
+///
+/// ### Example Request Body:
+/// ```json
+/// {
+///   "aes_key_hex": "09c40804a785de29d9e199df192549069fced35ab05058332da2c51318a034d0",
+///   "eth_pubkey_base64": "mtrHOOhJ0+9u+xq5MVr2GqCgLCKk9fl7nBDxLXg/0Fw=",
+///   "encrypted": {
+///     "version": "x25519-xsalsa20-poly1305",
+///     "nonce": "...",
+///     "ephemPublicKey": "...",
+///     "ciphertext": "..."
+///   }
+/// }
+/// ```
+pub async fn handle_verify(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<EthEncryptedAesKeyRequest>,
+) -> Result<Json<EthEncryptedAesKeyResponse>, ApiError> {
+    println!("[ETH-Verify] Received request");
+    let payload = EthEncryptedAesKeyInput {
+        aes_key_hex: resolve_aes_key_hex(&state, seller.as_ref(), payload.dataset_id.as_deref(), payload.aes_key_hex)?,
+        eth_pubkey_base64: payload.eth_pubkey_base64,
+        encrypted: payload.encrypted,
+        ephemeral_secret_hex: payload.ephemeral_secret_hex,
+    };
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[ETH-Verify] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_eth_verify(payload, mode).unwrap();
+    println!("[ETH-Verify] Proof generated successfully.");
+
+    let output: EthEncryptedAesKeyOutput = receipt.journal.decode().unwrap();
+    println!("[ETH-Verify] Output decoded from journal. Message : {}", output.message);
+
+    let receipt_base64 = receipt_to_base64(&receipt);
+    println!("[ETH-Verify] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(EthEncryptedAesKeyResponse { output, receipt_base64 }))
+}
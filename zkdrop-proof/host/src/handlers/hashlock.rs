@@ -0,0 +1,141 @@
+use axum::{extract::{Query, State}, Json};
+use crate::zkvm;
+use zkdrop_lib::hashlock::key_hash_hex;
+use zkdrop_lib::types::{HashLockDigest, HashLockProofInput, HashLockProofOutput, SecretString};
+use zkdrop_lib::validation::{Validator, AES_KEY_LEN};
+use zeroize::Zeroizing;
+
+use super::aes_ctr::AesCtrDecryptionProofRequest;
+use super::{resolve_aes_key_hex, ApiError, AppState, ProveParams, SellerAuth, resolve_mode};
+
+/// Request body of `/hashlock-verify`: an `/aes-verify` request plus the hash
+/// the key is locked under.
+#[derive(serde::Deserialize)]
+pub struct HashLockProofRequest {
+    #[serde(flatten)]
+    pub decryption: AesCtrDecryptionProofRequest,
+    #[serde(default)]
+    pub digest: HashLockDigest,
+}
+
+/// Response structure returned by the hash-lock proof endpoint.
+///
+/// - `output`: decryption verdict and the lock `key_hash_hex`.
+/// - `receipt_base64`: The serialized ZK proof receipt in base64 encoding.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct HashLockProofResponse {
+    pub output: HashLockProofOutput,
+    pub receipt_base64: String,
+}
+
+/// Request body of `/hashlock-reveal`: the key is taken either from the vault
+/// (`dataset_id`) or from `aes_key_hex`.
+#[derive(serde::Deserialize)]
+pub struct HashLockRevealRequest {
+    pub dataset_id: Option<String>,
+    pub aes_key_hex: Option<SecretString>,
+    #[serde(default)]
+    pub digest: HashLockDigest,
+}
+
+/// Payload the seller submits on-chain to claim a hash-locked payment. Both
+/// values are `0x`-prefixed `bytes32`; the contract checks `H(key) == key_hash`.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct HashLockReveal {
+    pub digest: HashLockDigest,
+    pub key_hash: String,
+    pub key: SecretString,
+}
+
+/// Builds the reveal payload for `aes_key_hex`, whose hash matches the
+/// `key_hash_hex` committed by `/hashlock-verify` for the same key and digest.
+pub fn reveal_payload(aes_key_hex: &SecretString, digest: HashLockDigest) -> Result<HashLockReveal, ApiError> {
+    let mut v = Validator::new();
+    v.hex_exact("aes_key_hex", aes_key_hex.expose_secret(), AES_KEY_LEN);
+    v.finish().map_err(ApiError::validation)?;
+
+    let key = Zeroizing::new(
+        hex::decode(aes_key_hex.expose_secret()).map_err(|_| ApiError::bad_request("Invalid AES key hex"))?,
+    );
+    Ok(HashLockReveal {
+        digest,
+        key_hash: format!("0x{}", key_hash_hex(digest, &key)),
+        key: SecretString::new(format!("0x{}", hex::encode(key.as_slice()))),
+    })
+}
+
+/// POST /hashlock-verify?prove_mode=local|bonsai|bonsai_snark
+///
+/// Classic ZKCP: proves that the ciphertext decrypts under the AES key and
+/// commits `key_hash_hex = H(key)` (`"digest": "sha256"` or `"keccak256"`) with
+/// the ciphertext hash and CID and the plaintext hash. The buyer locks payment against `key_hash_hex`; no buyer
+/// key pair is involved. Accepts the same fields as `/aes-verify`.
+///
+/// ### Example request:
+/// ```json
+/// {
+///   "aes_key_hex": "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d",
+///   "iv_hex": "01020300000000000000000000000000",
+///   "plaintext_utf8": "example fileeee ! ",
+///   "ciphertext_hex": "ef8d7b4abcaea121953432bd58aa69589312",
+///   "digest": "keccak256"
+/// }
+/// ```
+pub async fn handle_verify(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<HashLockProofRequest>,
+) -> Result<Json<HashLockProofResponse>, ApiError> {
+    println!("[HashLock-Verify] Received request");
+    let payload = HashLockProofInput {
+        decryption: payload.decryption.into_input(&state, seller.as_ref()).await?,
+        digest: payload.digest,
+    };
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[HashLock-Verify] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_hashlock_verify(payload, mode).unwrap();
+    println!("[HashLock-Verify] Proof successfully generated.");
+
+    let output: HashLockProofOutput = receipt.journal.decode().unwrap();
+    println!("[HashLock-Verify] Output decoded: is_valid = {}, key hash = {}",
+             output.is_valid, output.key_hash_hex);
+
+    let receipt_base64 = zkdrop_lib::utils::receipt_to_base64(&receipt);
+    println!("[HashLock-Verify] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(HashLockProofResponse { output, receipt_base64 }))
+}
+
+/// POST /hashlock-reveal
+///
+/// Returns the payload that releases a hash-locked payment: the AES key and
+/// its hash as `bytes32`. Only call this once the buyer has locked payment.
+/// Since it hands out the key, it always requires the seller bearer token,
+/// whether the key comes from the vault or from the request.
+///
+/// ### Example request:
+/// ```json
+/// { "dataset_id": "1", "digest": "keccak256" }
+/// ```
+///
+/// ### Example response:
+/// ```json
+/// {
+///   "digest": "keccak256",
+///   "key_hash": "0x...",
+///   "key": "0xde15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d"
+/// }
+/// ```
+pub async fn handle_reveal(
+    State(state): State<AppState>,
+    seller: SellerAuth,
+    Json(payload): Json<HashLockRevealRequest>,
+) -> Result<Json<HashLockReveal>, ApiError> {
+    println!("[HashLock-Reveal] Received request");
+    let aes_key_hex = resolve_aes_key_hex(&state, Some(&seller), payload.dataset_id.as_deref(), payload.aes_key_hex)?;
+    Ok(Json(reveal_payload(&aes_key_hex, payload.digest)?))
+}
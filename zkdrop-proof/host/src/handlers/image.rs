@@ -0,0 +1,112 @@
+use axum::{extract::{Query, State}, Json};
+use crate::zkvm;
+use zkdrop_lib::types::{ImageContainer, ImageMetadataInput, ImageMetadataOutput, SecretString};
+use zkdrop_lib::validation::MAX_PAYLOAD_LEN;
+
+use super::aes_ctr::resolve_aes_ctr_key;
+use super::{resolve_ciphertext_hex, ApiError, AppState, ProveParams, SellerAuth, resolve_mode};
+
+/// Request body of `/image-metadata`. Either reference a dataset registered in
+/// the vault via `dataset_id`, or pass `aes_key_hex` and `iv_hex` directly. The
+/// archive is sent as `ciphertext_hex` or fetched by `ciphertext_cid`.
+#[derive(serde::Deserialize)]
+pub struct ImageMetadataRequest {
+    pub dataset_id: Option<String>,
+    pub aes_key_hex: Option<SecretString>,
+    pub iv_hex: Option<String>,
+    pub ciphertext_hex: Option<String>,
+    pub ciphertext_cid: Option<String>,
+    pub container: ImageContainer,
+}
+
+/// Response structure returned by the image metadata proof endpoint.
+///
+/// - `output`: image count, format histogram and dimension ranges with the dataset commitments.
+/// - `receipt_base64`: The serialized ZK proof receipt in base64 encoding.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct ImageMetadataResponse {
+    pub output: ImageMetadataOutput,
+    pub receipt_base64: String,
+}
+
+/// POST /image-metadata?prove_mode=local|bonsai|bonsai_snark
+///
+/// Proves what an AES-CTR encrypted image archive (`zip` or `tar`) contains:
+/// the guest decrypts the ciphertext, unpacks the archive and reads the PNG,
+/// JPEG and GIF headers, committing the image count, a per-format histogram and the
+/// width/height ranges with `ciphertext_sha256_hex`. No plaintext is sent;
+/// other files are only counted. Like `/aes-verify`, `ciphertext_cid` may
+/// replace `ciphertext_hex`.
+///
+/// ### Example request:
+/// ```json
+/// {
+///   "dataset_id": "1",
+///   "ciphertext_hex": "...",
+///   "container": "zip"
+/// }
+/// ```
+///
+/// ### Example response:
+/// ```json
+/// {
+///   "output": {
+///     "is_valid": true,
+///     "message": "✅ Image metadata read from the AES-CTR encrypted archive",
+///     "iv_hex": "...",
+///     "key_sha256_hex": "...",
+///     "ciphertext_sha256_hex": "...",
+///     "container": "zip",
+///     "image_count": 3,
+///     "other_file_count": 1,
+///     "formats": [{ "format": "png", "count": 2 }, { "format": "jpeg", "count": 1 }],
+///     "dimensions": { "min_width": 32, "max_width": 1920, "min_height": 480, "max_height": 1080 }
+///   },
+///   "receipt_base64": "..."
+/// }
+/// ```
+pub async fn handle_image_metadata(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<ImageMetadataRequest>,
+) -> Result<Json<ImageMetadataResponse>, ApiError> {
+    println!("[Image-Metadata] Received request");
+    let ciphertext_hex = resolve_ciphertext_hex(
+        &state,
+        payload.ciphertext_hex,
+        payload.ciphertext_cid,
+        MAX_PAYLOAD_LEN,
+    )
+    .await?;
+    let (aes_key_hex, iv_hex) = resolve_aes_ctr_key(
+        &state,
+        seller.as_ref(),
+        payload.dataset_id,
+        payload.aes_key_hex,
+        payload.iv_hex,
+        &ciphertext_hex,
+    )?;
+    let payload = ImageMetadataInput {
+        aes_key_hex,
+        iv_hex,
+        ciphertext_hex,
+        container: payload.container,
+    };
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[Image-Metadata] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_image_prove(payload, mode).map_err(ApiError::internal)?;
+    println!("[Image-Metadata] Proof successfully generated.");
+
+    let output: ImageMetadataOutput = receipt.journal.decode().map_err(|err| ApiError::internal(err.into()))?;
+    println!("[Image-Metadata] Output decoded: is_valid = {}, images = {}",
+             output.is_valid, output.image_count);
+
+    let receipt_base64 = zkdrop_lib::utils::receipt_to_base64(&receipt);
+    println!("[Image-Metadata] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(ImageMetadataResponse { output, receipt_base64 }))
+}
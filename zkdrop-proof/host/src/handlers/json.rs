@@ -0,0 +1,116 @@
+use axum::{extract::{Query, State}, Json};
+use crate::zkvm;
+use zkdrop_lib::types::{JsonConformanceInput, JsonConformanceOutput, JsonFormat};
+
+use super::aes_ctr::AesCtrDecryptionProofRequest;
+use super::{ApiError, AppState, ProveParams, SellerAuth, resolve_mode};
+
+/// Request body of `/json-conformance`: an `/aes-verify` request plus the
+/// dataset format and the JSON Schema, given as a JSON value.
+#[derive(serde::Deserialize)]
+pub struct JsonConformanceRequest {
+    #[serde(flatten)]
+    pub decryption: AesCtrDecryptionProofRequest,
+    #[serde(default)]
+    pub format: JsonFormat,
+    pub schema: serde_json::Value,
+}
+
+/// Response structure returned by the JSON Schema conformance endpoint.
+///
+/// - `output`: verdict, record count, committed schema and dataset commitments.
+/// - `receipt_base64`: The serialized ZK proof receipt in base64 encoding.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct JsonConformanceResponse {
+    pub output: JsonConformanceOutput,
+    pub receipt_base64: String,
+}
+
+/// POST /json-conformance?prove_mode=local|bonsai|bonsai_snark
+///
+/// Proves that every record of an AES-CTR encrypted JSON (`"format": "json"`,
+/// records are the elements of a top-level array) or JSON Lines (`"jsonl"`)
+/// dataset validates against `schema`, and commits the record count. The
+/// schema is committed as compact JSON with sorted keys (`schema_json`).
+/// Supported keywords: `type`, `enum`, `const`, `minimum`, `maximum`,
+/// `exclusiveMinimum`, `exclusiveMaximum`, `minLength`, `maxLength`, `items`,
+/// `minItems`, `maxItems`, `properties`, `required`, `additionalProperties`
+/// (boolean). Accepts the same fields as `/aes-verify`.
+///
+/// ### Example request:
+/// ```json
+/// {
+///   "dataset_id": "1",
+///   "plaintext_utf8": "{\"city\":\"Paris\",\"population\":2102650}\n",
+///   "ciphertext_hex": "...",
+///   "format": "jsonl",
+///   "schema": {
+///     "type": "object",
+///     "required": ["city", "population"],
+///     "properties": {
+///       "city": { "type": "string", "minLength": 1 },
+///       "population": { "type": "integer", "minimum": 0 }
+///     },
+///     "additionalProperties": false
+///   }
+/// }
+/// ```
+///
+/// ### Example response:
+/// ```json
+/// {
+///   "output": {
+///     "is_valid": true,
+///     "message": "✅ All 1 records conform to the JSON Schema",
+///     "iv_hex": "...",
+///     "key_sha256_hex": "...",
+///     "ciphertext_sha256_hex": "...",
+///     "compression": "none",
+///     "format": "jsonl",
+///     "schema_json": "{\"additionalProperties\":false,...}",
+///     "record_count": 1
+///   },
+///   "receipt_base64": "..."
+/// }
+/// ```
+pub async fn handle_json_conformance(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<JsonConformanceRequest>,
+) -> Result<Json<JsonConformanceResponse>, ApiError> {
+    println!("[JSON-Conformance] Received request");
+    let payload = JsonConformanceInput {
+        decryption: payload.decryption.into_input(&state, seller.as_ref()).await?,
+        format: payload.format,
+        schema_json: payload.schema.to_string(),
+    };
+    payload.validate().map_err(|errors| {
+        // The request carries the schema as `schema`, the guest input as `schema_json`
+        let errors = errors
+            .into_iter()
+            .map(|mut err| {
+                if err.field == "schema_json" {
+                    err.field = String::from("schema");
+                }
+                err
+            })
+            .collect();
+        ApiError::validation(errors)
+    })?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[JSON-Conformance] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_json_prove(payload, mode).unwrap();
+    println!("[JSON-Conformance] Proof successfully generated.");
+
+    let output: JsonConformanceOutput = receipt.journal.decode().unwrap();
+    println!("[JSON-Conformance] Output decoded: is_valid = {}, records = {}",
+             output.is_valid, output.record_count);
+
+    let receipt_base64 = zkdrop_lib::utils::receipt_to_base64(&receipt);
+    println!("[JSON-Conformance] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(JsonConformanceResponse { output, receipt_base64 }))
+}
@@ -0,0 +1,88 @@
+use axum::{extract::{Query, State}, Json};
+use crate::zkvm;
+use zkdrop_lib::types::{LineageProofInput, LineageProofOutput, LineageRelation};
+
+use super::aes_ctr::AesCtrDecryptionProofRequest;
+use super::{ApiError, AppState, ProveParams, SellerAuth, resolve_mode};
+
+/// Request body of `/lineage-proof`: one `/aes-verify` request per version.
+#[derive(serde::Deserialize)]
+pub struct LineageProofRequest {
+    pub previous: AesCtrDecryptionProofRequest,
+    pub current: AesCtrDecryptionProofRequest,
+    #[serde(default)]
+    pub relation: LineageRelation,
+}
+
+/// Response structure returned by the version lineage proof endpoint.
+///
+/// - `output`: commitments to both versions and whether the relation holds.
+/// - `receipt_base64`: The serialized ZK proof receipt in base64 encoding.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct LineageProofResponse {
+    pub output: LineageProofOutput,
+    pub receipt_base64: String,
+}
+
+/// POST /lineage-proof?prove_mode=local|bonsai|bonsai_snark
+///
+/// Proves that a new version of an AES-CTR encrypted dataset extends the
+/// previous one without altering its records, so a listing can show a
+/// verifiable version history. With `relation` `prefix` (default) the previous
+/// plaintext must be a prefix of the current one ending with a line break
+/// (append-only); with `row_subset` every line of the previous version must
+/// appear in the current one, in any order. `previous` and `current` each take
+/// the fields of `/aes-verify`, so registered versions can be referenced by
+/// `dataset_id`; keys may differ between versions.
+///
+/// ### Example request:
+/// ```json
+/// {
+///   "previous": { "dataset_id": "1", "plaintext_utf8": "t,v\n1,10\n2,20\n", "ciphertext_hex": "..." },
+///   "current": { "dataset_id": "2", "plaintext_utf8": "t,v\n1,10\n2,20\n3,30\n", "ciphertext_hex": "..." },
+///   "relation": "prefix"
+/// }
+/// ```
+///
+/// ### Example response:
+/// ```json
+/// {
+///   "output": {
+///     "is_valid": true,
+///     "message": "✅ Current version appends to the previous version",
+///     "relation": "prefix",
+///     "previous": { "iv_hex": "...", "key_sha256_hex": "...", "ciphertext_sha256_hex": "...", "compression": "none", "record_count": 3 },
+///     "current": { "iv_hex": "...", "key_sha256_hex": "...", "ciphertext_sha256_hex": "...", "compression": "none", "record_count": 4 }
+///   },
+///   "receipt_base64": "..."
+/// }
+/// ```
+pub async fn handle_lineage_proof(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<LineageProofRequest>,
+) -> Result<Json<LineageProofResponse>, ApiError> {
+    println!("[Lineage] Received request");
+    let payload = LineageProofInput {
+        previous: payload.previous.into_input(&state, seller.as_ref()).await?,
+        current: payload.current.into_input(&state, seller.as_ref()).await?,
+        relation: payload.relation,
+    };
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[Lineage] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_lineage_prove(payload, mode).unwrap();
+    println!("[Lineage] Proof successfully generated.");
+
+    let output: LineageProofOutput = receipt.journal.decode().unwrap();
+    println!("[Lineage] Output decoded: is_valid = {}, message = {}",
+             output.is_valid, output.message);
+
+    let receipt_base64 = zkdrop_lib::utils::receipt_to_base64(&receipt);
+    println!("[Lineage] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(LineageProofResponse { output, receipt_base64 }))
+}
@@ -1,11 +1,200 @@
 /// AES-CTR proof module handlers
 pub mod aes_ctr;
 
+/// AES-GCM proof module handlers
+pub mod aes_gcm;
+
+/// ChaCha20-Poly1305 proof module handlers
+pub mod chacha;
+
 /// RSA key encryption/decryption proof module handlers
 pub mod rsa;
 
+/// Batch (one AES key, many buyers) key wrapping proof module handlers
+pub mod batch;
+
+/// Licence transfer (key re-wrapping) proof handlers
+pub mod rewrap;
+
+/// ECIES (X25519) key wrapping proof module handlers
+pub mod ecies;
+
+/// Wallet encryption key (x25519-xsalsa20-poly1305) proof module handlers
+pub mod eth_encryption;
+
+/// Hash-locked key release (classic ZKCP) handlers
+pub mod hashlock;
+
+/// CSV dataset property proof handlers
+pub mod csv;
+
+/// JSON Schema conformance proof handlers
+pub mod json;
+
+/// Image archive metadata proof handlers
+pub mod image;
+
+/// Regex/keyword predicate proof handlers
+pub mod predicate;
+
+/// PII-absence attestation handlers
+pub mod pii;
+
+/// Dataset version lineage proof handlers
+pub mod lineage;
+
+/// Verifiable sample preview handlers
+pub mod preview;
+
+/// Buyer dispute (seller misbehaviour) proof handlers
+pub mod dispute;
+
+/// Proof aggregation (many purchases, one receipt) handlers
+pub mod aggregate;
+
+/// Dataset vault registration handlers
+pub mod datasets;
+
+/// Published receipts of the Escrow watcher's exchange proofs
+pub mod purchases;
+
+/// Seller bearer-token authentication for vault-backed requests
+pub mod auth;
+
+/// JSON error responses shared by all handlers
+pub mod error;
+
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use crate::ipfs::IpfsGateway;
+use crate::vault::{DatasetKeyMaterial, Vault};
 use crate::zkvm::ProveMode;
 use serde::Deserialize;
+use zkdrop_lib::types::SecretString;
+use zkdrop_lib::utils::sha256_hex;
+
+pub use auth::{require_seller, SellerAuth};
+pub use error::ApiError;
+
+/// Shared state handed to every handler.
+#[derive(Clone)]
+pub struct AppState {
+    /// Unlocked dataset vault, `None` when no vault secret is configured
+    pub vault: Option<Arc<Vault>>,
+    /// Gateway ciphertexts are fetched from by CID, `None` without `IPFS_GATEWAY_URL`
+    pub ipfs: Option<Arc<IpfsGateway>>,
+    /// Bearer token of the seller, `None` without `SELLER_API_TOKEN`
+    pub seller_token: Option<Arc<SecretString>>,
+    /// Where the Escrow watcher stores receipts, `None` when it is not configured
+    pub watcher_receipts_dir: Option<String>,
+}
+
+/// Returns the unlocked vault or `503` if no vault secret is configured.
+pub fn unlocked_vault(state: &AppState) -> Result<&Arc<Vault>, ApiError> {
+    state.vault.as_ref().ok_or_else(|| {
+        ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Dataset vault is locked (set VAULT_PASSPHRASE or VAULT_KEY_FILE)",
+        )
+    })
+}
+
+/// Fetches the key material registered under `dataset_id`. Only the
+/// authenticated seller may reference vault entries.
+pub fn lookup_dataset(
+    state: &AppState,
+    seller: Option<&SellerAuth>,
+    dataset_id: &str,
+) -> Result<DatasetKeyMaterial, ApiError> {
+    require_seller(seller, "dataset_id")?;
+    unlocked_vault(state)?.get(dataset_id).ok_or_else(|| {
+        ApiError::new(StatusCode::NOT_FOUND, format!("Dataset {} is not registered", dataset_id))
+    })
+}
+
+/// Rejects a ciphertext that differs from the one registered for `dataset_id`.
+pub fn check_dataset_ciphertext(
+    dataset: &DatasetKeyMaterial,
+    dataset_id: &str,
+    ciphertext_hex: &str,
+) -> Result<(), ApiError> {
+    let ciphertext = hex::decode(ciphertext_hex)
+        .map_err(|_| ApiError::bad_request("Invalid ciphertext hex"))?;
+    if sha256_hex(&ciphertext) != dataset.ciphertext_sha256_hex {
+        return Err(ApiError::bad_request(format!(
+            "Ciphertext does not match the one registered for dataset {}",
+            dataset_id
+        )));
+    }
+    Ok(())
+}
+
+/// Fetches the file stored under `cid` from the configured IPFS gateway and
+/// checks it against the CID. Only base32 CIDv1 (`bafk…`/`bafy…`) can be
+/// recomputed; an `ipfs://` prefix is accepted.
+pub async fn fetch_ipfs_file(
+    state: &AppState,
+    cid: &str,
+    max_len: usize,
+) -> Result<Vec<u8>, ApiError> {
+    let gateway = state.ipfs.as_ref().ok_or_else(|| {
+        ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "No IPFS gateway configured (set IPFS_GATEWAY_URL)",
+        )
+    })?;
+    let cid = cid.strip_prefix("ipfs://").unwrap_or(cid);
+    let is_cid_v1 = cid.starts_with('b')
+        && cid.bytes().all(|b| b.is_ascii_lowercase() || (b'2'..=b'7').contains(&b));
+    if !is_cid_v1 {
+        return Err(ApiError::bad_request(format!(
+            "{} is not a base32 CIDv1 (CIDv0 Qm… hashes cannot be verified)",
+            cid
+        )));
+    }
+    println!("[IPFS] Fetching {}", cid);
+    let data = gateway
+        .fetch(cid, max_len)
+        .await
+        .map_err(|err| ApiError::new(StatusCode::BAD_GATEWAY, format!("{:#}", err)))?;
+    println!("[IPFS] Fetched and verified {} ({} bytes)", cid, data.len());
+    Ok(data)
+}
+
+/// Takes the ciphertext from `ciphertext_hex` or fetches it by `ciphertext_cid`,
+/// exactly one of which must be set, and returns it hex-encoded.
+pub async fn resolve_ciphertext_hex(
+    state: &AppState,
+    ciphertext_hex: Option<String>,
+    ciphertext_cid: Option<String>,
+    max_len: usize,
+) -> Result<String, ApiError> {
+    match (ciphertext_hex, ciphertext_cid) {
+        (Some(ciphertext_hex), None) => Ok(ciphertext_hex),
+        (None, Some(cid)) => Ok(hex::encode(fetch_ipfs_file(state, &cid, max_len).await?)),
+        _ => Err(ApiError::bad_request(
+            "Exactly one of ciphertext_hex or ciphertext_cid must be provided",
+        )),
+    }
+}
+
+/// Picks the AES key either from a registered dataset (seller only) or from
+/// the request body.
+pub fn resolve_aes_key_hex(
+    state: &AppState,
+    seller: Option<&SellerAuth>,
+    dataset_id: Option<&str>,
+    aes_key_hex: Option<SecretString>,
+) -> Result<SecretString, ApiError> {
+    match (dataset_id, aes_key_hex) {
+        (Some(id), None) => Ok(lookup_dataset(state, seller, id)?.aes_key_hex),
+        (None, Some(key)) => Ok(key),
+        _ => Err(ApiError::bad_request(
+            "Exactly one of dataset_id or aes_key_hex must be provided",
+        )),
+    }
+}
 
 /// Query parameters used for selecting the proving mode.
 /// Supported values:
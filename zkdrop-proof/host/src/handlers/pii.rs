@@ -0,0 +1,95 @@
+use axum::{extract::{Query, State}, Json};
+use crate::zkvm;
+use zkdrop_lib::pii::PII_DETECTORS;
+use zkdrop_lib::types::{PiiAttestationInput, PiiAttestationOutput, PiiDetector};
+
+use super::aes_ctr::AesCtrDecryptionProofRequest;
+use super::{ApiError, AppState, ProveParams, SellerAuth, resolve_mode};
+
+/// Request body of `/pii-attestation`: an `/aes-verify` request plus the
+/// detectors to run (all built-in detectors when omitted).
+#[derive(serde::Deserialize)]
+pub struct PiiAttestationRequest {
+    #[serde(flatten)]
+    pub decryption: AesCtrDecryptionProofRequest,
+    pub detectors: Option<Vec<PiiDetector>>,
+}
+
+/// Response structure returned by the PII attestation endpoint.
+///
+/// - `output`: per-detector hit counts and the `pii_free` badge with the dataset commitments.
+/// - `receipt_base64`: The serialized ZK proof receipt in base64 encoding.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct PiiAttestationResponse {
+    pub output: PiiAttestationOutput,
+    pub receipt_base64: String,
+}
+
+/// POST /pii-attestation?prove_mode=local|bonsai|bonsai_snark
+///
+/// Runs the built-in PII detectors (`email`, `phone`, `iban`, `credit_card`,
+/// `ipv4`, `eth_address`) over the private plaintext of an AES-CTR encrypted
+/// dataset and commits the hit count of each, with `pii_free` set when none
+/// fired. Listings can show the result as a verifiable "no PII detected" badge.
+/// Accepts the same fields as `/aes-verify`.
+///
+/// ### Example request:
+/// ```json
+/// {
+///   "dataset_id": "1",
+///   "plaintext_utf8": "city,population\nParis,2102650\nLyon,522250\n",
+///   "ciphertext_hex": "..."
+/// }
+/// ```
+///
+/// ### Example response:
+/// ```json
+/// {
+///   "output": {
+///     "is_valid": true,
+///     "message": "✅ No PII detected in the AES-CTR encrypted dataset",
+///     "iv_hex": "...",
+///     "key_sha256_hex": "...",
+///     "ciphertext_sha256_hex": "...",
+///     "compression": "none",
+///     "pii_free": true,
+///     "hits": [
+///       { "detector": "email", "count": 0 },
+///       { "detector": "phone", "count": 0 },
+///       { "detector": "iban", "count": 0 },
+///       { "detector": "credit_card", "count": 0 },
+///       { "detector": "ipv4", "count": 0 },
+///       { "detector": "eth_address", "count": 0 }
+///     ]
+///   },
+///   "receipt_base64": "..."
+/// }
+/// ```
+pub async fn handle_pii_attestation(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<PiiAttestationRequest>,
+) -> Result<Json<PiiAttestationResponse>, ApiError> {
+    println!("[PII-Attestation] Received request");
+    let payload = PiiAttestationInput {
+        decryption: payload.decryption.into_input(&state, seller.as_ref()).await?,
+        detectors: payload.detectors.unwrap_or_else(|| PII_DETECTORS.to_vec()),
+    };
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[PII-Attestation] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_pii_prove(payload, mode).unwrap();
+    println!("[PII-Attestation] Proof successfully generated.");
+
+    let output: PiiAttestationOutput = receipt.journal.decode().unwrap();
+    println!("[PII-Attestation] Output decoded: is_valid = {}, pii_free = {}",
+             output.is_valid, output.pii_free);
+
+    let receipt_base64 = zkdrop_lib::utils::receipt_to_base64(&receipt);
+    println!("[PII-Attestation] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(PiiAttestationResponse { output, receipt_base64 }))
+}
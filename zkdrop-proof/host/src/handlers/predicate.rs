@@ -0,0 +1,94 @@
+use axum::{extract::{Query, State}, Json};
+use crate::zkvm;
+use zkdrop_lib::types::{Predicate, PredicateProofInput, PredicateProofOutput};
+
+use super::aes_ctr::AesCtrDecryptionProofRequest;
+use super::{ApiError, AppState, ProveParams, SellerAuth, resolve_mode};
+
+/// Request body of `/predicate-proof`: an `/aes-verify` request plus the
+/// public patterns to evaluate.
+#[derive(serde::Deserialize)]
+pub struct PredicateProofRequest {
+    #[serde(flatten)]
+    pub decryption: AesCtrDecryptionProofRequest,
+    pub predicates: Vec<Predicate>,
+}
+
+/// Response structure returned by the predicate proof endpoint.
+///
+/// - `output`: per-pattern results with the dataset commitments.
+/// - `receipt_base64`: The serialized ZK proof receipt in base64 encoding.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct PredicateProofResponse {
+    pub output: PredicateProofOutput,
+    pub receipt_base64: String,
+}
+
+/// POST /predicate-proof?prove_mode=local|bonsai|bonsai_snark
+///
+/// Evaluates public regular expressions (or keywords, with `"literal": true`)
+/// over the private plaintext of an AES-CTR encrypted dataset. In `count` mode
+/// (default) the journal commits the number of non-overlapping matches, in
+/// `presence` mode only whether the pattern occurs, e.g. to attest that a
+/// dataset contains no email addresses. Patterns that match the empty string
+/// are rejected. Accepts the same fields as `/aes-verify`.
+///
+/// ### Example request:
+/// ```json
+/// {
+///   "dataset_id": "1",
+///   "plaintext_utf8": "name,email\nAlice,alice@example.com\n",
+///   "ciphertext_hex": "...",
+///   "predicates": [
+///     { "pattern": "[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\\.[A-Za-z]{2,}" },
+///     { "pattern": "SSN", "literal": true, "mode": "presence" }
+///   ]
+/// }
+/// ```
+///
+/// ### Example response:
+/// ```json
+/// {
+///   "output": {
+///     "is_valid": true,
+///     "message": "✅ Predicates evaluated over the AES-CTR encrypted dataset",
+///     "iv_hex": "...",
+///     "key_sha256_hex": "...",
+///     "ciphertext_sha256_hex": "...",
+///     "compression": "none",
+///     "results": [
+///       { "pattern": "[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\\.[A-Za-z]{2,}", "literal": false, "mode": "count", "matched": true, "match_count": 1 },
+///       { "pattern": "SSN", "literal": true, "mode": "presence", "matched": false, "match_count": null }
+///     ]
+///   },
+///   "receipt_base64": "..."
+/// }
+/// ```
+pub async fn handle_predicate_proof(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<PredicateProofRequest>,
+) -> Result<Json<PredicateProofResponse>, ApiError> {
+    println!("[Predicate] Received request");
+    let payload = PredicateProofInput {
+        decryption: payload.decryption.into_input(&state, seller.as_ref()).await?,
+        predicates: payload.predicates,
+    };
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[Predicate] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_predicate_prove(payload, mode).unwrap();
+    println!("[Predicate] Proof successfully generated.");
+
+    let output: PredicateProofOutput = receipt.journal.decode().unwrap();
+    println!("[Predicate] Output decoded: is_valid = {}, {} results",
+             output.is_valid, output.results.len());
+
+    let receipt_base64 = zkdrop_lib::utils::receipt_to_base64(&receipt);
+    println!("[Predicate] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(PredicateProofResponse { output, receipt_base64 }))
+}
@@ -0,0 +1,113 @@
+use axum::{extract::{Query, State}, Json};
+use crate::zkvm;
+use zkdrop_lib::preview::PREVIEW_DEFAULT_CHUNK_LEN;
+use zkdrop_lib::types::{PreviewSelection, SamplePreviewInput, SamplePreviewOutput};
+
+use super::aes_ctr::AesCtrDecryptionProofRequest;
+use super::{ApiError, AppState, ProveParams, SellerAuth, resolve_mode};
+
+fn default_chunk_len() -> u32 {
+    PREVIEW_DEFAULT_CHUNK_LEN
+}
+
+/// Request body of `/sample-preview`: an `/aes-verify` request plus the excerpt
+/// to reveal and the Merkle chunk length.
+#[derive(serde::Deserialize)]
+pub struct SamplePreviewRequest {
+    #[serde(flatten)]
+    pub decryption: AesCtrDecryptionProofRequest,
+    #[serde(default = "default_chunk_len")]
+    pub chunk_len: u32,
+    pub selection: PreviewSelection,
+}
+
+/// Response structure returned by the sample preview endpoint.
+///
+/// - `output`: journal with the commitments and the revealed excerpts.
+/// - `excerpts_utf8`: the excerpts decoded as (lossy) UTF-8, for display.
+/// - `receipt_base64`: The serialized ZK proof receipt in base64 encoding.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct SamplePreviewResponse {
+    pub output: SamplePreviewOutput,
+    pub excerpts_utf8: Vec<String>,
+    pub receipt_base64: String,
+}
+
+/// POST /sample-preview?prove_mode=local|bonsai|bonsai_snark
+///
+/// Proves that the revealed excerpts come from the AES-CTR encrypted dataset,
+/// so a listing can show an authentic preview before purchase. `selection` is
+/// either `{"range": {"offset", "len"}}` or `{"chunks": {"indices": [..]}}`
+/// (chunks of `chunk_len` bytes, default 1024). The journal commits the excerpts
+/// with the ciphertext SHA-256, its Merkle root and the key hash. Accepts the
+/// same fields as `/aes-verify`.
+///
+/// ### Example request:
+/// ```json
+/// {
+///   "aes_key_hex": "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d",
+///   "iv_hex": "01020300000000000000000000000000",
+///   "plaintext_utf8": "example fileeee ! ",
+///   "ciphertext_hex": "ef8d7b4abcaea121953432bd58aa69589312",
+///   "chunk_len": 8,
+///   "selection": { "range": { "offset": 0, "len": 7 } }
+/// }
+/// ```
+///
+/// ### Example response:
+/// ```json
+/// {
+///   "output": {
+///     "is_valid": true,
+///     "message": "✅ Preview excerpts come from the AES-CTR encrypted dataset",
+///     "iv_hex": "01020300000000000000000000000000",
+///     "key_sha256_hex": "...",
+///     "ciphertext_sha256_hex": "...",
+///     "compression": "none",
+///     "ciphertext_merkle_root_hex": "...",
+///     "chunk_len": 8,
+///     "plaintext_len": 18,
+///     "revealed": [{ "offset": 0, "bytes_hex": "6578616d706c65" }]
+///   },
+///   "excerpts_utf8": ["example"],
+///   "receipt_base64": "..."
+/// }
+/// ```
+pub async fn handle_preview(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
+    Query(params): Query<ProveParams>,
+    Json(payload): Json<SamplePreviewRequest>,
+) -> Result<Json<SamplePreviewResponse>, ApiError> {
+    println!("[Sample-Preview] Received request");
+    let payload = SamplePreviewInput {
+        decryption: payload.decryption.into_input(&state, seller.as_ref()).await?,
+        chunk_len: payload.chunk_len,
+        selection: payload.selection,
+    };
+    payload.validate().map_err(ApiError::validation)?;
+
+    let mode = resolve_mode(params.prove_mode.as_deref());
+    println!("[Sample-Preview] Resolved proving mode: {:?}", mode);
+
+    let receipt = zkvm::run_preview_prove(payload, mode).unwrap();
+    println!("[Sample-Preview] Proof successfully generated.");
+
+    let output: SamplePreviewOutput = receipt.journal.decode().unwrap();
+    println!("[Sample-Preview] Output decoded: is_valid = {}, {} excerpts",
+             output.is_valid, output.revealed.len());
+
+    let excerpts_utf8 = output
+        .revealed
+        .iter()
+        .map(|excerpt| {
+            let bytes = hex::decode(&excerpt.bytes_hex).unwrap_or_default();
+            String::from_utf8_lossy(&bytes).into_owned()
+        })
+        .collect();
+
+    let receipt_base64 = zkdrop_lib::utils::receipt_to_base64(&receipt);
+    println!("[Sample-Preview] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
+
+    Ok(Json(SamplePreviewResponse { output, excerpts_utf8, receipt_base64 }))
+}
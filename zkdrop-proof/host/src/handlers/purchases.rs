@@ -0,0 +1,48 @@
+use std::fs;
+
+use alloy::primitives::U256;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::watcher::receipt_path;
+
+use super::{ApiError, AppState};
+
+/// Exchange proof the Escrow watcher generated for a purchase.
+///
+/// - `receipt_base64`: `rsa_encrypter` receipt; its journal holds the wrapped key
+///   submitted with `setEncryptedSecret` and the buyer key hash
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct PurchaseReceiptResponse {
+    pub purchase_id: String,
+    pub receipt_base64: String,
+}
+
+/// GET /purchases/{purchase_id}/receipt
+///
+/// Publishes the receipt of the exchange proof behind the `encryptedSecret`
+/// the watcher delivered, so the buyer can verify it before accepting the
+/// dataset. Responds with `404` until the purchase has been proved.
+pub async fn handle_receipt(
+    State(state): State<AppState>,
+    Path(purchase_id): Path<String>,
+) -> Result<Json<PurchaseReceiptResponse>, ApiError> {
+    let receipts_dir = state.watcher_receipts_dir.as_deref().ok_or_else(|| {
+        ApiError::new(StatusCode::SERVICE_UNAVAILABLE, "Escrow watcher is not configured")
+    })?;
+    let purchase_id: U256 = purchase_id
+        .parse()
+        .map_err(|_| ApiError::bad_request("purchase_id must be a decimal uint256"))?;
+
+    let receipt_base64 = fs::read_to_string(receipt_path(receipts_dir, purchase_id)).map_err(|_| {
+        ApiError::new(
+            StatusCode::NOT_FOUND,
+            format!("No receipt for purchase {} yet", purchase_id),
+        )
+    })?;
+
+    Ok(Json(PurchaseReceiptResponse { purchase_id: purchase_id.to_string(), receipt_base64 }))
+}
@@ -1,13 +1,42 @@
-use axum::{extract::Query, Json};
+use axum::{extract::{Query, State}, Json};
+use crate::rsa_keys::{private_key_to_pkcs8_base64, random_wrap_seed, RsaPublicKeyParam};
 use crate::zkvm;
 use zkdrop_lib::types::{
     RsaEncryptAesKeyInput, RsaEncryptedAesKeyInput,
-    RsaEncryptAesKeyOutput, RsaEncryptedAesKeyOutput
+    OaepParams, RsaEncryptAesKeyOutput, RsaEncryptedAesKeyOutput, RsaWrapWitness, SecretString
 };
 use zkdrop_lib::utils::receipt_to_base64;
-use risc0_zkvm::Receipt;
 
-use super::{ProveParams, resolve_mode};
+use super::{resolve_aes_key_hex, ApiError, AppState, ProveParams, SellerAuth, resolve_mode};
+
+/// Request body of `/rsa-encrypt`: the AES key is taken either from the vault
+/// (`dataset_id`) or from `aes_key_hex`. `rsa_pubkey` takes any encoding listed
+/// in [`RsaPublicKeyParam`]; the former name `rsa_pubkey_base64` is still accepted.
+#[derive(serde::Deserialize)]
+pub struct RsaEncryptAesKeyRequest {
+    pub dataset_id: Option<String>,
+    pub aes_key_hex: Option<SecretString>,
+    #[serde(alias = "rsa_pubkey_base64")]
+    pub rsa_pubkey: RsaPublicKeyParam,
+    #[serde(default)]
+    pub oaep: OaepParams,
+}
+
+/// Request body of `/rsa-verify`: the AES key is taken either from the vault
+/// (`dataset_id`) or from `aes_key_hex`. `rsa_pubkey` takes any encoding listed
+/// in [`RsaPublicKeyParam`]; the former name `rsa_pubkey_base64` is still accepted.
+/// `witness` is the OAEP seed or the recipient private key (PEM or base64 DER).
+#[derive(serde::Deserialize)]
+pub struct RsaEncryptedAesKeyRequest {
+    pub dataset_id: Option<String>,
+    pub aes_key_hex: Option<SecretString>,
+    #[serde(alias = "rsa_pubkey_base64")]
+    pub rsa_pubkey: RsaPublicKeyParam,
+    pub enc_aes_key_hex: String,
+    #[serde(default)]
+    pub oaep: OaepParams,
+    pub witness: RsaWrapWitness,
+}
 
 /// Response returned from RSA encryption endpoint
 /// - `output`: contains the result of AES key encryption
@@ -33,7 +62,25 @@ pub struct RsaEncryptedAesKeyResponse {
 /// ```json
 /// {
 ///   "aes_key_hex": "09c40804a785de29d9e199df192549069fced35ab05058332da2c51318a034d0",
-///   "rsa_pubkey_base64": "MIIBIjANBgkqhkiG9..."
+///   "rsa_pubkey": "MIIBIjANBgkqhkiG9..."
+/// }
+/// ```
+///
+/// The key may also be PEM (SPKI or PKCS#1), base64 PKCS#1 DER or a JWK, e.g.
+/// `"rsa_pubkey": {"kty": "RSA", "n": "0z0Nf8Aa...", "e": "AQAB"}`. It is
+/// normalised to SPKI DER, whose SHA-256 the journal commits as `rsa_pubkey_sha256_hex`.
+///
+/// OAEP defaults to SHA-256 with no label. Clients using other parameters pass
+/// e.g. `"oaep": {"digest": "sha1", "mgf_digest": "sha1", "label_hex": "7075726368617365"}`
+/// (digests: `sha1`, `sha256`, `sha384`, `sha512`; the label as raw bytes in hex);
+/// the journal commits them. The OAEP seed comes from a private seed the host
+/// draws from the OS RNG for every proof, so it is never committed.
+///
+/// or, for a dataset registered via `POST /datasets`:
+/// ```json
+/// {
+///   "dataset_id": "1",
+///   "rsa_pubkey": "MIIBIjANBgkqhkiG9..."
 /// }
 /// ```
 ///
@@ -48,13 +95,25 @@ pub struct RsaEncryptedAesKeyResponse {
 /// ```bash
 /// curl -X POST "http://localhost:8081/rsa-encrypt?prove_mode=local" \
 ///      -H "Content-Type: application/json" \
-///      -d '{"aes_key_hex":"...", "rsa_pubkey_base64":"..."}'
+///      -d '{"aes_key_hex":"...", "rsa_pubkey":"..."}'
 /// ```
 pub async fn handle_encrypt(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
     Query(params): Query<ProveParams>,
-    Json(payload): Json<RsaEncryptAesKeyInput>,
-) -> Json<RsaEncryptAesKeyResponse> {
+    Json(payload): Json<RsaEncryptAesKeyRequest>,
+) -> Result<Json<RsaEncryptAesKeyResponse>, ApiError> {
     println!("[RSA-Encrypt] Received request");
+    let payload = RsaEncryptAesKeyInput {
+        aes_key_hex: resolve_aes_key_hex(&state, seller.as_ref(), payload.dataset_id.as_deref(), payload.aes_key_hex)?,
+        rsa_pubkey_base64: payload
+            .rsa_pubkey
+            .to_spki_base64("rsa_pubkey")
+            .map_err(|err| ApiError::validation(vec![err]))?,
+        oaep: payload.oaep,
+        wrap_seed_hex: random_wrap_seed(),
+    };
+    payload.validate().map_err(ApiError::validation)?;
 
     let mode = resolve_mode(params.prove_mode.as_deref());
     println!("[RSA-Encrypt] Resolved proving mode: {:?}", mode);
@@ -68,7 +127,7 @@ pub async fn handle_encrypt(
     let receipt_base64 = receipt_to_base64(&receipt);
     println!("[RSA-Encrypt] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
 
-    Json(RsaEncryptAesKeyResponse { output, receipt_base64 })
+    Ok(Json(RsaEncryptAesKeyResponse { output, receipt_base64 }))
 }
 
 /// POST /rsa-verify?prove_mode=local|bonsai|bonsai_snark
@@ -77,11 +136,21 @@ pub async fn handle_encrypt(
 /// ```json
 /// {
 ///     "aes_key_hex": "32ec9a3cfee00897de3704677830710ae8d9074b0d88851b3d656435cb6db2b0",
-///     "rsa_pubkey_base64": "MIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEA0z0Nf8AagvQqkT/baHDHYnt0frz/wnWIWDPTJGY2NXrnIDMuBHdBOFjVM3soPfr1nI8ZKjF0KOcGsCqn31gLcTblkQlzIfg7FfO+QVAywoY8wgvt2BKreQ+Fq8gzjhV/EAsJbqWnBoQj+fD0TXy+EZdP378UdePMxEPMWT3Ivao/2Myz2mmnoyVU2AGAhCeouGlgsIhcFfdQhqSF/DlkFlDvo7UKA8cDGZjHxGAzcajQqnAkBu2Wb2eOQXMeIKB3oKqI3AvpqNFpz5tq+y6y3vBhmRqZ+oUFnfQx9wG3nWtACgCksxNh0XZTs8ZSAXKwjKRAniW4EkTSt9AhV3ef4wIDAQAB",
-///     "enc_aes_key_hex": "8655273e91ecb30a5d15cc3267df665c7e639941e25a2b4658ffba8f71dd388083df1f62b9ed5687d40c98485c9abded97f80fea456b67a5d4577268bb65c3038d0662c879465c32573790e462b44e3670fd578259d380b606067e0e55ce3dba3cdee6ba9156e4c9945f3bd9ad7ed9ce505ad87e87c5e808d4cbd21aeaea6f6f787c093424a5c913efbcde17d82ceeb850394b2744535df5e1eac9d7bfec6b0f3e685c866a6237c86892dc1335491e9de984af56b58e4ef118758451953e37f0b404cdd06b77f68fa669556864104f1c52ef9a322df3e25f19b655adbcb153815621244a0b14671f12a8b272b90dbaefd1e5a35bbb14be776562170c5d560942"
+///     "rsa_pubkey": "MIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEA0z0Nf8AagvQqkT/baHDHYnt0frz/wnWIWDPTJGY2NXrnIDMuBHdBOFjVM3soPfr1nI8ZKjF0KOcGsCqn31gLcTblkQlzIfg7FfO+QVAywoY8wgvt2BKreQ+Fq8gzjhV/EAsJbqWnBoQj+fD0TXy+EZdP378UdePMxEPMWT3Ivao/2Myz2mmnoyVU2AGAhCeouGlgsIhcFfdQhqSF/DlkFlDvo7UKA8cDGZjHxGAzcajQqnAkBu2Wb2eOQXMeIKB3oKqI3AvpqNFpz5tq+y6y3vBhmRqZ+oUFnfQx9wG3nWtACgCksxNh0XZTs8ZSAXKwjKRAniW4EkTSt9AhV3ef4wIDAQAB",
+///     "enc_aes_key_hex": "8655273e91ecb30a5d15cc3267df665c7e639941e25a2b4658ffba8f71dd388083df1f62b9ed5687d40c98485c9abded97f80fea456b67a5d4577268bb65c3038d0662c879465c32573790e462b44e3670fd578259d380b606067e0e55ce3dba3cdee6ba9156e4c9945f3bd9ad7ed9ce505ad87e87c5e808d4cbd21aeaea6f6f787c093424a5c913efbcde17d82ceeb850394b2744535df5e1eac9d7bfec6b0f3e685c866a6237c86892dc1335491e9de984af56b58e4ef118758451953e37f0b404cdd06b77f68fa669556864104f1c52ef9a322df3e25f19b655adbcb153815621244a0b14671f12a8b272b90dbaefd1e5a35bbb14be776562170c5d560942",
+///     "witness": { "oaep_seed_hex": "5f1c0b..." }
 /// }
 /// ```
 ///
+/// OAEP is randomised, so the public key alone cannot show what was wrapped.
+/// `witness` proves it either with the OAEP seed the wrapper drew
+/// (`{"oaep_seed_hex": "..."}`, as long as the OAEP digest) or with the recipient
+/// private key (`{"rsa_private_key_base64": "..."}`, PKCS#8 or PKCS#1, PEM or
+/// base64 DER), which must decrypt `enc_aes_key_hex` to the AES key. Keys wrapped
+/// by WebCrypto, which does not expose the seed, are proven with the private key.
+///
+/// As for `/rsa-encrypt`, `"dataset_id"` may replace `"aes_key_hex"`.
+///
 /// ### Example Logs:
 /// ```text
 /// [INFO] Verifying proof using Bonsai
@@ -92,13 +161,32 @@ pub async fn handle_encrypt(
 /// ```bash
 /// curl -X POST "http://localhost:8081/rsa-verify?prove_mode=local" \
 ///      -H "Content-Type: application/json" \
-///      -d '{"aes_key_hex":"...", "rsa_pubkey_base64":"...", "enc_aes_key_hex":"..."}'
+///      -d '{"aes_key_hex":"...", "rsa_pubkey":"...", "enc_aes_key_hex":"...", "witness":{"rsa_private_key_base64":"..."}}'
 /// ```
 pub async fn handle_verify(
+    State(state): State<AppState>,
+    seller: Option<SellerAuth>,
     Query(params): Query<ProveParams>,
-    Json(payload): Json<RsaEncryptedAesKeyInput>,
-) -> Json<RsaEncryptedAesKeyResponse> {
+    Json(payload): Json<RsaEncryptedAesKeyRequest>,
+) -> Result<Json<RsaEncryptedAesKeyResponse>, ApiError> {
     println!("[RSA-Verify] Received request");
+    let payload = RsaEncryptedAesKeyInput {
+        aes_key_hex: resolve_aes_key_hex(&state, seller.as_ref(), payload.dataset_id.as_deref(), payload.aes_key_hex)?,
+        rsa_pubkey_base64: payload
+            .rsa_pubkey
+            .to_spki_base64("rsa_pubkey")
+            .map_err(|err| ApiError::validation(vec![err]))?,
+        enc_aes_key_hex: payload.enc_aes_key_hex,
+        oaep: payload.oaep,
+        witness: match payload.witness {
+            RsaWrapWitness::RsaPrivateKeyBase64(private_key) => RsaWrapWitness::RsaPrivateKeyBase64(
+                private_key_to_pkcs8_base64(&private_key, "witness.rsa_private_key_base64")
+                    .map_err(|err| ApiError::validation(vec![err]))?,
+            ),
+            witness => witness,
+        },
+    };
+    payload.validate().map_err(ApiError::validation)?;
 
     let mode = resolve_mode(params.prove_mode.as_deref());
     println!("[RSA-Verify] Resolved proving mode: {:?}", mode);
@@ -112,5 +200,5 @@ pub async fn handle_verify(
     let receipt_base64 = receipt_to_base64(&receipt);
     println!("[RSA-Verify] Receipt serialized to base64 ({} bytes).", receipt_base64.len());
 
-    Json(RsaEncryptedAesKeyResponse { output, receipt_base64 })
+    Ok(Json(RsaEncryptedAesKeyResponse { output, receipt_base64 }))
 }
\ No newline at end of file
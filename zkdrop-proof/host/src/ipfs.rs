@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use zkdrop_lib::ipfs::ipfs_cid;
+
+/// Client for an IPFS HTTP gateway (a public gateway, a dedicated Pinata
+/// gateway or the gateway of a local Kubo node), used to fetch ciphertexts by
+/// CID instead of receiving them in the request body.
+///
+/// Gateways are not trusted: the fetched bytes are re-hashed into a CID with
+/// the same layout the guest commits and rejected unless it matches.
+pub struct IpfsGateway {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl IpfsGateway {
+    /// Creates a client for the gateway at `base_url`, e.g. `https://ipfs.io`
+    /// or `http://127.0.0.1:8080`. Files are read from `{base_url}/ipfs/{cid}`.
+    pub fn new(base_url: &str, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("failed to build IPFS gateway client")?;
+        Ok(Self { base_url: base_url.trim_end_matches('/').to_string(), client })
+    }
+
+    /// Fetches the file stored under `cid` and checks that it hashes to that
+    /// CID. Files larger than `max_len` bytes are rejected without being read
+    /// in full.
+    pub async fn fetch(&self, cid: &str, max_len: usize) -> Result<Vec<u8>> {
+        let url = format!("{}/ipfs/{}", self.base_url, cid);
+        let mut response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("failed to fetch {}", url))?
+            .error_for_status()?;
+
+        let too_large = || format!("file {} exceeds the {}-byte limit", cid, max_len);
+        if response.content_length().is_some_and(|len| len > max_len as u64) {
+            bail!(too_large());
+        }
+        let mut data = Vec::new();
+        while let Some(chunk) = response.chunk().await.context("failed to read gateway response")? {
+            if data.len() + chunk.len() > max_len {
+                bail!(too_large());
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        let computed = ipfs_cid(&data);
+        if computed != cid {
+            bail!(
+                "content fetched for {} hashes to {} (was it pinned as CIDv0 or with \
+                 non-default chunking?)",
+                cid,
+                computed
+            );
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::routing::get;
+    use axum::Router;
+    use futures_util::stream;
+    use std::convert::Infallible;
+
+    const MAX_LEN: usize = 1024;
+
+    /// Serves `body()` for every `/ipfs/{cid}` path on a local port and
+    /// returns a client for it.
+    async fn gateway(body: fn() -> Body) -> IpfsGateway {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/ipfs/:cid", get(move || async move { body() }));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        IpfsGateway::new(&format!("http://{}/", addr), Duration::from_secs(5)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn fetch_returns_content_matching_cid() {
+        let gateway = gateway(|| Body::from(vec![7u8; 100])).await;
+        let data = gateway.fetch(&ipfs_cid(&[7u8; 100]), MAX_LEN).await.unwrap();
+        assert_eq!(data, vec![7u8; 100]);
+    }
+
+    #[tokio::test]
+    async fn fetch_rejects_content_of_another_cid() {
+        let gateway = gateway(|| Body::from(vec![7u8; 100])).await;
+        let err = gateway.fetch(&ipfs_cid(&[8u8; 100]), MAX_LEN).await.unwrap_err();
+        assert!(err.to_string().contains("hashes to"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn fetch_rejects_oversize_content_length() {
+        let gateway = gateway(|| Body::from(vec![7u8; MAX_LEN + 1])).await;
+        let err = gateway.fetch(&ipfs_cid(&[7u8; MAX_LEN + 1]), MAX_LEN).await.unwrap_err();
+        assert!(err.to_string().contains("exceeds the 1024-byte limit"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn fetch_rejects_oversize_streamed_body() {
+        // Chunked transfer: no Content-Length, so the limit applies while reading
+        let gateway = gateway(|| {
+            let chunks = (0..4).map(|_| Ok::<_, Infallible>(vec![7u8; MAX_LEN / 2]));
+            Body::from_stream(stream::iter(chunks))
+        })
+        .await;
+        let err = gateway.fetch(&ipfs_cid(&[7u8; 2 * MAX_LEN]), MAX_LEN).await.unwrap_err();
+        assert!(err.to_string().contains("exceeds the 1024-byte limit"), "{err}");
+    }
+}
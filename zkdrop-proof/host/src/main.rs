@@ -1,9 +1,17 @@
-use axum;
+use std::sync::Arc;
+use std::time::Duration;
+
 use tracing_subscriber::FmtSubscriber;
 mod routes;
 mod zkvm;
 mod handlers;
 mod config;
+mod ipfs;
+mod rsa_keys;
+mod vault;
+mod watcher;
+#[cfg(test)]
+mod redaction;
 
 #[tokio::main]
 async fn main() {
@@ -13,8 +21,47 @@ async fn main() {
     // Setup logging
     FmtSubscriber::builder().init();
 
+    // Unlock the dataset vault if a passphrase or key file is configured
+    let vault = match config::get_vault_secret() {
+        Some(secret) => match vault::Vault::open(&config::get_vault_path(), secret) {
+            Ok(vault) => Some(Arc::new(vault)),
+            Err(err) => {
+                eprintln!("🔒 Dataset vault stays locked, failed to unlock it: {:#}", err);
+                None
+            }
+        },
+        None => {
+            println!("🔒 Dataset vault locked (no VAULT_PASSPHRASE or VAULT_KEY_FILE)");
+            None
+        }
+    };
+
+    // Fetch ciphertexts by CID if an IPFS gateway is configured
+    let ipfs = config::get_ipfs_gateway_url().map(|url| {
+        let timeout = Duration::from_secs(config::get_ipfs_timeout_secs());
+        match ipfs::IpfsGateway::new(&url, timeout) {
+            Ok(gateway) => Arc::new(gateway),
+            Err(err) => panic!("Failed to set up IPFS gateway: {:#}", err),
+        }
+    });
+
+    // Deliver encrypted keys for new Escrow purchases in the background
+    watcher::spawn_if_configured(vault.clone());
+
+    // Vault-backed requests need the seller's bearer token
+    let seller_token = config::get_seller_api_token().map(Arc::new);
+    if seller_token.is_none() {
+        println!("🔒 Seller authentication disabled (no SELLER_API_TOKEN), dataset_id requests are rejected");
+    }
+
     // Build app with routes
-    let app = routes::build_router();
+    let watcher_receipts_dir = config::get_watcher_config().map(|config| config.receipts_dir);
+    let app = routes::build_router(handlers::AppState {
+        vault,
+        ipfs,
+        seller_token,
+        watcher_receipts_dir,
+    });
 
     // Run server
     let listener = tokio::net::TcpListener::bind(config::get_address()).await.unwrap();
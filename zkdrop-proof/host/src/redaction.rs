@@ -0,0 +1,194 @@
+//! Tests that secret fields never reach tracing or `println!` output, whatever
+//! the type that carries them is logged as, and that secret buffers are wiped.
+
+use std::io::{self, Write};
+use std::mem::MaybeUninit;
+use std::sync::{Arc, Mutex};
+
+use serde::de::DeserializeOwned;
+use zkdrop_lib::types::{
+    AesCtrDecryptionProofInput, ChaChaDecryptionProofInput, DisputeProofInput, RewrapInput,
+    RsaEncryptAesKeyInput, SecretString,
+};
+
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::config::WatcherConfig;
+use crate::vault::DatasetKeyMaterial;
+
+const AES_KEY_HEX: &str = "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d";
+const PLAINTEXT: &str = "patient-record-4711";
+const WRAP_SEED_HEX: &str = "5eed5eed5eed5eed5eed5eed5eed5eed5eed5eed5eed5eed5eed5eed5eed5eed";
+const PRIVATE_KEY: &str = "MIIEvQIBADANBgkqhkiG9w0BAQEFAASCBKcwggSjAgEAAoIBAQC7";
+const SELLER_PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// Writer appending everything the subscriber formats to a shared buffer.
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Runs `log` under a fmt subscriber and returns what it printed.
+fn capture_logs(log: impl FnOnce()) -> String {
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+    tracing::subscriber::with_default(subscriber, log);
+    let bytes = logs.0.lock().unwrap().clone();
+    String::from_utf8(bytes).unwrap()
+}
+
+/// Runs `print` against a writer and returns what it wrote. `println!` is
+/// `writeln!` on stdout, so this is the host's console output path.
+fn capture_prints(print: impl FnOnce(&mut CapturedLogs) -> io::Result<()>) -> String {
+    let mut logs = CapturedLogs::default();
+    print(&mut logs).unwrap();
+    let bytes = logs.0.lock().unwrap().clone();
+    String::from_utf8(bytes).unwrap()
+}
+
+fn assert_redacted(logs: &str, secrets: &[&str]) {
+    assert!(logs.contains("[REDACTED]"), "nothing was logged: {}", logs);
+    for secret in secrets {
+        assert!(!logs.contains(secret), "secret leaked into logs: {}", logs);
+    }
+}
+
+fn from_json<T: DeserializeOwned>(json: serde_json::Value) -> T {
+    serde_json::from_value(json).unwrap()
+}
+
+#[test]
+fn proof_inputs_are_redacted() {
+    let aes_ctr: AesCtrDecryptionProofInput = from_json(serde_json::json!({
+        "aes_key_hex": AES_KEY_HEX,
+        "iv_hex": "01020300000000000000000000000000",
+        "plaintext_utf8": PLAINTEXT,
+        "ciphertext_hex": "ef8d7b4abcaea121953432bd58aa69589312",
+    }));
+    let chacha: ChaChaDecryptionProofInput = from_json(serde_json::json!({
+        "key_hex": AES_KEY_HEX,
+        "nonce_hex": "000000000000000000000000",
+        "plaintext_utf8": PLAINTEXT,
+        "ciphertext_hex": "00",
+    }));
+    let rsa: RsaEncryptAesKeyInput = from_json(serde_json::json!({
+        "aes_key_hex": AES_KEY_HEX,
+        "rsa_pubkey_base64": "MIIBIjANBgkqhkiG9w0BAQEFAAOCAQ8AMIIBCgKCAQEA0z0Nf8Aa",
+        "wrap_seed_hex": WRAP_SEED_HEX,
+    }));
+
+    let logs = capture_logs(|| {
+        tracing::info!(?aes_ctr, "AES-CTR input");
+        tracing::info!(?chacha, "ChaCha input");
+        tracing::info!(?rsa, "RSA input");
+        tracing::info!("{:?} {:#?}", aes_ctr, rsa);
+    });
+    assert_redacted(&logs, &[AES_KEY_HEX, PLAINTEXT, WRAP_SEED_HEX]);
+    assert!(logs.contains("01020300000000000000000000000000"), "public IV should stay visible");
+}
+
+#[test]
+fn private_key_witnesses_are_redacted() {
+    let dispute: DisputeProofInput = from_json(serde_json::json!({
+        "rsa_private_key_base64": PRIVATE_KEY,
+        "encrypted_secret_hex": "5f1c",
+        "key_commitment": { "digest": "sha256", "key_hash_hex": "00" },
+    }));
+    let rewrap: RewrapInput = from_json(serde_json::json!({
+        "source": { "owner": {
+            "rsa_private_key_base64": PRIVATE_KEY,
+            "enc_aes_key_hex": "5f1c",
+            "oaep": {},
+        } },
+        "recipient": { "ecies_pubkey_hex": "00" },
+        "wrap_seed_hex": WRAP_SEED_HEX,
+    }));
+
+    let logs = capture_logs(|| {
+        tracing::warn!(?dispute, ?rewrap, "witnesses");
+    });
+    assert_redacted(&logs, &[PRIVATE_KEY, WRAP_SEED_HEX]);
+}
+
+#[test]
+fn host_key_material_is_redacted() {
+    let dataset = DatasetKeyMaterial {
+        aes_key_hex: SecretString::from(AES_KEY_HEX),
+        iv_hex: String::from("01020300000000000000000000000000"),
+        ciphertext_sha256_hex: String::from("9dc42e9c"),
+    };
+    let watcher = WatcherConfig {
+        rpc_url: String::from("http://127.0.0.1:8545"),
+        escrow_address: String::from("0x5FbDB2315678afecb367f032d93F642f64180aa3"),
+        seller_private_key: SecretString::from(SELLER_PRIVATE_KEY),
+        start_block: None,
+        poll_interval_secs: 12,
+        state_path: String::from("watcher-state.json"),
+        receipts_dir: String::from("watcher-receipts"),
+        prove_mode: None,
+    };
+
+    let logs = capture_logs(|| {
+        tracing::info!(?dataset, "dataset");
+        tracing::error!("watcher config: {:?}", watcher);
+    });
+    assert_redacted(&logs, &[AES_KEY_HEX, SELLER_PRIVATE_KEY, &SELLER_PRIVATE_KEY[2..]]);
+}
+
+#[test]
+fn printed_inputs_are_redacted() {
+    let aes_ctr: AesCtrDecryptionProofInput = from_json(serde_json::json!({
+        "aes_key_hex": AES_KEY_HEX,
+        "iv_hex": "01020300000000000000000000000000",
+        "plaintext_utf8": PLAINTEXT,
+        "ciphertext_hex": "ef8d7b4abcaea121953432bd58aa69589312",
+    }));
+    let dataset = DatasetKeyMaterial {
+        aes_key_hex: SecretString::from(AES_KEY_HEX),
+        iv_hex: String::from("01020300000000000000000000000000"),
+        ciphertext_sha256_hex: String::from("9dc42e9c"),
+    };
+
+    let logs = capture_prints(|out| {
+        writeln!(out, "[AES-Verify] Payload: {:?}", aes_ctr)?;
+        writeln!(out, "[Vault] Dataset: {:#?}", dataset)?;
+        writeln!(out, "{:?}", aes_ctr.aes_key_hex)
+    });
+    assert_redacted(&logs, &[AES_KEY_HEX, PLAINTEXT]);
+}
+
+#[test]
+fn secret_string_wipes_its_buffer() {
+    assert_zeroize_on_drop::<SecretString>();
+    let mut secret = SecretString::from(AES_KEY_HEX);
+    let (ptr, len) = (secret.expose_secret().as_ptr(), secret.expose_secret().len());
+    secret.zeroize();
+    assert!(secret.expose_secret().is_empty());
+    // Zeroizing a String keeps its allocation, so the old bytes are still readable
+    let buffer = unsafe { std::slice::from_raw_parts(ptr, len) };
+    assert!(buffer.iter().all(|&b| b == 0), "buffer not wiped: {:?}", buffer);
+}
+
+#[test]
+fn zeroizing_wipes_on_drop() {
+    let mut slot = MaybeUninit::new(Zeroizing::new([0x5a_u8; 32]));
+    unsafe { slot.assume_init_drop() };
+    // Dropping does not free the slot, and any byte pattern is a valid [u8; 32]
+    let bytes = unsafe { slot.as_ptr().cast::<[u8; 32]>().read() };
+    assert_eq!(bytes, [0u8; 32]);
+}
+
+fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
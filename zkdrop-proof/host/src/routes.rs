@@ -1,9 +1,37 @@
 use axum::{Router};
-use crate::handlers::{aes_ctr, rsa};
+use crate::handlers::{
+    aes_ctr, aes_gcm, aggregate, batch, chacha, csv, datasets, dispute, ecies, eth_encryption, hashlock, image,
+    json, lineage, pii, predicate, preview, purchases, rewrap, rsa, AppState,
+};
 
-pub fn build_router() -> Router {
+pub fn build_router(state: AppState) -> Router {
     Router::new()
         .route("/aes-verify", axum::routing::post(aes_ctr::handle_verify))
+        .route("/aes-gcm-verify", axum::routing::post(aes_gcm::handle_verify))
+        .route("/chacha-verify", axum::routing::post(chacha::handle_verify))
         .route("/rsa-encrypt", axum::routing::post(rsa::handle_encrypt))
         .route("/rsa-verify", axum::routing::post(rsa::handle_verify))
+        .route("/rsa-encrypt-batch", axum::routing::post(batch::handle_rsa_encrypt_batch))
+        .route("/rewrap", axum::routing::post(rewrap::handle_rewrap))
+        .route("/ecies-encrypt", axum::routing::post(ecies::handle_encrypt))
+        .route("/ecies-verify", axum::routing::post(ecies::handle_verify))
+        .route("/eth-encrypt", axum::routing::post(eth_encryption::handle_encrypt))
+        .route("/eth-verify", axum::routing::post(eth_encryption::handle_verify))
+        .route("/hashlock-verify", axum::routing::post(hashlock::handle_verify))
+        .route("/hashlock-reveal", axum::routing::post(hashlock::handle_reveal))
+        .route("/csv-properties", axum::routing::post(csv::handle_csv_properties))
+        .route("/json-conformance", axum::routing::post(json::handle_json_conformance))
+        .route("/image-metadata", axum::routing::post(image::handle_image_metadata))
+        .route("/predicate-proof", axum::routing::post(predicate::handle_predicate_proof))
+        .route("/pii-attestation", axum::routing::post(pii::handle_pii_attestation))
+        .route("/lineage-proof", axum::routing::post(lineage::handle_lineage_proof))
+        .route("/sample-preview", axum::routing::post(preview::handle_preview))
+        .route("/dispute-proof", axum::routing::post(dispute::handle_dispute_proof))
+        .route("/aggregate", axum::routing::post(aggregate::handle_aggregate))
+        .route("/purchases/:purchase_id/receipt", axum::routing::get(purchases::handle_receipt))
+        .route(
+            "/datasets",
+            axum::routing::get(datasets::handle_list).post(datasets::handle_register),
+        )
+        .with_state(state)
 }
@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail, Context, Result};
+use argon2::Argon2;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+use zkdrop_lib::types::SecretString;
+
+const VAULT_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Key material a seller registered for one listed dataset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetKeyMaterial {
+    /// AES-256 key, hex-encoded (64 hex chars → 32 bytes)
+    pub aes_key_hex: SecretString,
+
+    /// AES-CTR IV / counter, hex-encoded (32 hex chars → 16 bytes)
+    pub iv_hex: String,
+
+    /// SHA-256 of the uploaded ciphertext, hex-encoded
+    pub ciphertext_sha256_hex: String,
+}
+
+/// Secret used to unlock the vault.
+pub enum VaultSecret {
+    /// Passphrase stretched with Argon2id and the salt stored in the vault file
+    Passphrase(Zeroizing<String>),
+    /// 32-byte key read from a file (raw or hex-encoded)
+    KeyFile(String),
+}
+
+/// On-disk layout of the vault. The dataset map is serialized to JSON and
+/// sealed with AES-256-GCM; only the KDF parameters are stored in clear.
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    kdf: String,
+    salt_hex: String,
+    nonce_hex: String,
+    ciphertext_hex: String,
+}
+
+/// File-based encrypted keystore mapping dataset IDs (listing token IDs) to
+/// their key material.
+///
+/// Sellers register a dataset once; proof requests and the Escrow watcher
+/// then reference it by ID instead of carrying the AES key around.
+pub struct Vault {
+    path: String,
+    kdf: String,
+    salt: Vec<u8>,
+    key: Zeroizing<[u8; 32]>,
+    datasets: RwLock<BTreeMap<String, DatasetKeyMaterial>>,
+}
+
+impl Vault {
+    /// Unlocks the vault at `path`, creating an empty one if the file does not exist.
+    pub fn open(path: &str, secret: VaultSecret) -> Result<Self> {
+        let existing = if Path::new(path).exists() {
+            let raw = fs::read_to_string(path)
+                .with_context(|| format!("failed to read vault {}", path))?;
+            let file: VaultFile = serde_json::from_str(&raw)
+                .with_context(|| format!("failed to parse vault {}", path))?;
+            if file.version != VAULT_VERSION {
+                bail!("unsupported vault version {}", file.version);
+            }
+            Some(file)
+        } else {
+            None
+        };
+
+        let salt = match &existing {
+            Some(file) => hex::decode(&file.salt_hex).context("invalid vault salt")?,
+            None => {
+                let mut salt = vec![0u8; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+                salt
+            }
+        };
+        let (kdf, key) = derive_key(secret, &salt)?;
+
+        if let Some(file) = &existing {
+            if file.kdf != kdf {
+                bail!("vault was created with {} but unlocked with {}", file.kdf, kdf);
+            }
+        }
+
+        let vault = Self {
+            path: path.to_string(),
+            kdf,
+            salt,
+            key,
+            datasets: RwLock::new(BTreeMap::new()),
+        };
+
+        match existing {
+            Some(file) => {
+                let datasets = vault.decrypt(&file)?;
+                *vault.datasets.write().unwrap() = datasets;
+            }
+            None => vault.save(&BTreeMap::new())?,
+        }
+
+        Ok(vault)
+    }
+
+    /// Registers (or replaces) the key material of a dataset and persists the vault.
+    pub fn register(&self, dataset_id: &str, material: DatasetKeyMaterial) -> Result<()> {
+        let mut datasets = self.datasets.write().unwrap();
+        let mut updated = datasets.clone();
+        updated.insert(dataset_id.to_string(), material);
+        self.save(&updated)?;
+        *datasets = updated;
+        Ok(())
+    }
+
+    pub fn get(&self, dataset_id: &str) -> Option<DatasetKeyMaterial> {
+        self.datasets.read().unwrap().get(dataset_id).cloned()
+    }
+
+    /// IDs of all registered datasets; never exposes key material.
+    pub fn dataset_ids(&self) -> Vec<String> {
+        self.datasets.read().unwrap().keys().cloned().collect()
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new_from_slice(self.key.as_slice()).expect("vault key is 32 bytes")
+    }
+
+    fn decrypt(&self, file: &VaultFile) -> Result<BTreeMap<String, DatasetKeyMaterial>> {
+        let nonce = hex::decode(&file.nonce_hex).context("invalid vault nonce")?;
+        if nonce.len() != NONCE_LEN {
+            bail!("invalid vault nonce length");
+        }
+        let ciphertext = hex::decode(&file.ciphertext_hex).context("invalid vault ciphertext")?;
+        let plaintext = self
+            .cipher()
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map(Zeroizing::new)
+            .map_err(|_| anyhow!("vault could not be unlocked (wrong passphrase or key file?)"))?;
+        serde_json::from_slice(&plaintext).context("vault contents are corrupted")
+    }
+
+    /// Re-encrypts the whole map under a fresh nonce and atomically replaces the file.
+    fn save(&self, datasets: &BTreeMap<String, DatasetKeyMaterial>) -> Result<()> {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+
+        let plaintext = Zeroizing::new(serde_json::to_vec(datasets)?);
+        let ciphertext = self
+            .cipher()
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_ref())
+            .map_err(|_| anyhow!("failed to encrypt vault"))?;
+
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            kdf: self.kdf.clone(),
+            salt_hex: hex::encode(&self.salt),
+            nonce_hex: hex::encode(nonce),
+            ciphertext_hex: hex::encode(ciphertext),
+        };
+
+        let tmp_path = format!("{}.tmp", self.path);
+        fs::write(&tmp_path, serde_json::to_vec_pretty(&file)?)
+            .with_context(|| format!("failed to write vault {}", tmp_path))?;
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("failed to move vault to {}", self.path))?;
+        Ok(())
+    }
+}
+
+fn derive_key(secret: VaultSecret, salt: &[u8]) -> Result<(String, Zeroizing<[u8; 32]>)> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    match secret {
+        VaultSecret::Passphrase(passphrase) => {
+            Argon2::default()
+                .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+                .map_err(|err| anyhow!("failed to derive vault key: {}", err))?;
+            Ok((String::from("argon2id"), key))
+        }
+        VaultSecret::KeyFile(path) => {
+            let raw = Zeroizing::new(
+                fs::read(&path).with_context(|| format!("failed to read vault key file {}", path))?,
+            );
+            let bytes = match std::str::from_utf8(&raw).ok().map(str::trim) {
+                Some(text) if text.len() == 64 => Zeroizing::new(
+                    hex::decode(text).context("invalid hex in vault key file")?,
+                ),
+                _ => raw,
+            };
+            if bytes.len() != 32 {
+                bail!("vault key file must contain 32 raw bytes or 64 hex chars");
+            }
+            key.copy_from_slice(&bytes);
+            Ok((String::from("key-file"), key))
+        }
+    }
+}
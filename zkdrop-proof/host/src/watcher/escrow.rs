@@ -0,0 +1,22 @@
+use alloy::sol;
+
+// Subset of `contracts/Escrow.sol` used by the watcher.
+sol! {
+    #[sol(rpc)]
+    contract Escrow {
+        event PurchaseSubmitted(uint256 purchaseId, address buyer, address seller, uint256 amount);
+
+        function getPurchase(uint256 purchaseId) external view returns (
+            address buyer,
+            address seller,
+            uint256 amount,
+            uint256 listingTokenId,
+            bool isComplete,
+            string memory datasetInfo,
+            string memory buyerPublicKey,
+            string memory encryptedSecret
+        );
+
+        function setEncryptedSecret(uint256 purchaseId, string memory encryptedSecret) external;
+    }
+}
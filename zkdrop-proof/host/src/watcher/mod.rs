@@ -0,0 +1,447 @@
+/// Escrow contract bindings
+mod escrow;
+
+/// Persisted watcher progress
+mod state;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy::primitives::{Address, U256};
+use alloy::providers::{DynProvider, Provider, ProviderBuilder};
+use alloy::rpc::types::Filter;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::sol_types::SolEvent;
+use anyhow::{anyhow, Context, Result};
+use zkdrop_lib::types::{OaepParams, RsaEncryptAesKeyInput, RsaEncryptAesKeyOutput};
+use zkdrop_lib::utils::receipt_to_base64;
+
+use crate::config::{self, WatcherConfig};
+use crate::handlers::resolve_mode;
+use crate::rsa_keys::{random_wrap_seed, RsaPublicKeyParam};
+use crate::vault::Vault;
+use crate::zkvm::{self, ProveMode};
+use escrow::Escrow;
+use state::{PurchaseProgress, WatcherState};
+
+/// Maximum number of blocks requested in a single `eth_getLogs` call.
+const LOG_BLOCK_RANGE: u64 = 1_000;
+
+/// Polls a purchase is retried on before it is marked `Failed`.
+const MAX_PURCHASE_ATTEMPTS: u32 = 5;
+
+/// Watches the Escrow contract for `PurchaseSubmitted` events addressed to the
+/// configured seller and delivers the encrypted AES key for each of them:
+///
+/// 1. reads `buyerPublicKey` and `listingTokenId` via `getPurchase`
+/// 2. looks up the AES key registered for the listing in the dataset vault
+/// 3. proves the RSA-OAEP wrapping of the AES key (`rsa_encrypter` guest)
+/// 4. stores the receipt and submits `setEncryptedSecret`
+///
+/// Every step is persisted to `WATCHER_STATE_PATH`, so a restarted watcher
+/// neither re-proves nor re-submits purchases it already handled. A purchase
+/// that fails is recorded on its own and retried on later polls, so it never
+/// holds up the purchases after it. The receipt is published by the host at
+/// `GET /purchases/{purchase_id}/receipt`.
+pub struct Watcher {
+    config: WatcherConfig,
+    provider: DynProvider,
+    escrow: Escrow::EscrowInstance<DynProvider>,
+    seller: Address,
+    mode: ProveMode,
+    vault: Arc<Vault>,
+}
+
+impl Watcher {
+    pub fn new(config: WatcherConfig, vault: Arc<Vault>) -> Result<Self> {
+        let signer: PrivateKeySigner = config
+            .seller_private_key
+            .expose_secret()
+            .parse()
+            .context("invalid SELLER_PRIVATE_KEY")?;
+        let seller = signer.address();
+
+        let escrow_address: Address = config
+            .escrow_address
+            .parse()
+            .context("invalid ESCROW_ADDRESS")?;
+
+        let provider = ProviderBuilder::new()
+            .wallet(signer)
+            .connect_http(config.rpc_url.parse().context("invalid WATCHER_RPC_URL")?)
+            .erased();
+        let escrow = Escrow::new(escrow_address, provider.clone());
+        let mode = resolve_mode(config.prove_mode.as_deref());
+
+        Ok(Self { config, provider, escrow, seller, mode, vault })
+    }
+
+    /// Polls the node forever. Errors reading logs are logged and the failed
+    /// block range is retried on the next tick.
+    pub async fn run(self) {
+        let mut state = match WatcherState::load(&self.config.state_path) {
+            Ok(state) => state,
+            Err(err) => {
+                println!("[Watcher] Could not load state, watcher disabled: {:#}", err);
+                return;
+            }
+        };
+
+        println!(
+            "[Watcher] Watching Escrow {} for purchases of seller {}",
+            self.escrow.address(),
+            self.seller
+        );
+
+        loop {
+            if let Err(err) = self.poll(&mut state).await {
+                println!("[Watcher] Poll failed, retrying: {:#}", err);
+            }
+            tokio::time::sleep(Duration::from_secs(self.config.poll_interval_secs)).await;
+        }
+    }
+
+    async fn poll(&self, state: &mut WatcherState) -> Result<()> {
+        for key in state.unfinished_purchases() {
+            let purchase_id: U256 = key.parse().context("invalid purchase ID in watcher state")?;
+            self.try_purchase(state, purchase_id).await?;
+        }
+
+        let latest = self.provider.get_block_number().await?;
+        let mut from = state
+            .last_processed_block
+            .map(|block| block + 1)
+            .or(self.config.start_block)
+            .unwrap_or(latest);
+
+        while from <= latest {
+            let to = latest.min(from + LOG_BLOCK_RANGE - 1);
+            let filter = Filter::new()
+                .address(*self.escrow.address())
+                .event_signature(Escrow::PurchaseSubmitted::SIGNATURE_HASH)
+                .from_block(from)
+                .to_block(to);
+
+            for log in self.provider.get_logs(&filter).await? {
+                let event = log.log_decode::<Escrow::PurchaseSubmitted>()?.inner.data;
+                if event.seller != self.seller {
+                    continue;
+                }
+                if state.progress(&event.purchaseId.to_string()).is_none() {
+                    self.try_purchase(state, event.purchaseId).await?;
+                }
+            }
+
+            state.last_processed_block = Some(to);
+            state.save(&self.config.state_path)?;
+            from = to + 1;
+        }
+
+        Ok(())
+    }
+
+    /// Runs [`Self::handle_purchase`], recording a failure against the purchase
+    /// instead of aborting the poll. Only failing to persist the state is fatal.
+    async fn try_purchase(&self, state: &mut WatcherState, purchase_id: U256) -> Result<()> {
+        if let Err(err) = self.handle_purchase(state, purchase_id).await {
+            let key = purchase_id.to_string();
+            println!("[Watcher] Purchase {} failed, will retry: {:#}", key, err);
+            state.record_failure(&key, format!("{:#}", err), MAX_PURCHASE_ATTEMPTS);
+            state.save(&self.config.state_path)?;
+        }
+        Ok(())
+    }
+
+    /// Drives one purchase from its persisted progress to `Submitted` or `Failed`.
+    async fn handle_purchase(&self, state: &mut WatcherState, purchase_id: U256) -> Result<()> {
+        let key = purchase_id.to_string();
+
+        if matches!(state.progress(&key), None | Some(PurchaseProgress::Retrying { .. })) {
+            let progress = match self.prove_purchase(purchase_id).await? {
+                Ok(progress) => progress,
+                Err(reason) => {
+                    println!("[Watcher] Purchase {} skipped: {}", key, reason);
+                    PurchaseProgress::Failed { reason }
+                }
+            };
+            state.set_progress(&key, progress);
+            state.save(&self.config.state_path)?;
+        }
+
+        if let Some(PurchaseProgress::Proved { enc_aes_key_hex, receipt_path }) =
+            state.progress(&key).cloned()
+        {
+            let tx_hash = self.submit_secret(purchase_id, &enc_aes_key_hex).await?;
+            println!("[Watcher] Purchase {} delivered in tx {}", key, tx_hash);
+            state.set_progress(
+                &key,
+                PurchaseProgress::Submitted { enc_aes_key_hex, receipt_path, tx_hash },
+            );
+            state.save(&self.config.state_path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Generates the exchange proof for a purchase.
+    ///
+    /// The outer `Result` carries transient failures (RPC, prover) which are
+    /// retried; the inner one carries reasons why the purchase can never be served.
+    async fn prove_purchase(
+        &self,
+        purchase_id: U256,
+    ) -> Result<std::result::Result<PurchaseProgress, String>> {
+        let purchase = self.escrow.getPurchase(purchase_id).call().await?;
+
+        if !purchase.encryptedSecret.is_empty() {
+            return Ok(Err(String::from("encrypted secret already set on-chain")));
+        }
+
+        let listing_token_id = purchase.listingTokenId.to_string();
+        let dataset = match self.vault.get(&listing_token_id) {
+            Some(dataset) => dataset,
+            None => {
+                return Ok(Err(format!("no key material registered for listing {}", listing_token_id)))
+            }
+        };
+
+        let rsa_pubkey_base64 = match RsaPublicKeyParam::from(purchase.buyerPublicKey.clone())
+            .to_spki_base64("buyerPublicKey")
+        {
+            Ok(key) => key,
+            Err(err) => return Ok(Err(format!("invalid exchange input ({}: {})", err.field, err.message))),
+        };
+        let input = RsaEncryptAesKeyInput {
+            aes_key_hex: dataset.aes_key_hex,
+            rsa_pubkey_base64,
+            oaep: OaepParams::default(),
+            wrap_seed_hex: random_wrap_seed(),
+        };
+        if let Err(errors) = input.validate() {
+            let reasons: Vec<String> =
+                errors.iter().map(|e| format!("{}: {}", e.field, e.message)).collect();
+            return Ok(Err(format!("invalid exchange input ({})", reasons.join("; "))));
+        }
+
+        println!("[Watcher] Proving purchase {} (listing {})", purchase_id, listing_token_id);
+        let mode = self.mode;
+        let receipt = tokio::task::spawn_blocking(move || zkvm::run_rsa_encrypt(input, mode))
+            .await
+            .map_err(|err| anyhow!("prover task failed: {}", err))??;
+
+        let output: RsaEncryptAesKeyOutput = receipt.journal.decode()?;
+        if !output.is_valid {
+            return Ok(Err(output.message));
+        }
+
+        fs::create_dir_all(&self.config.receipts_dir)?;
+        let receipt_path = receipt_path(&self.config.receipts_dir, purchase_id)
+            .to_string_lossy()
+            .into_owned();
+        fs::write(&receipt_path, receipt_to_base64(&receipt))
+            .with_context(|| format!("failed to write receipt {}", receipt_path))?;
+
+        Ok(Ok(PurchaseProgress::Proved {
+            enc_aes_key_hex: output.enc_aes_key_hex,
+            receipt_path,
+        }))
+    }
+
+    async fn submit_secret(&self, purchase_id: U256, enc_aes_key_hex: &str) -> Result<String> {
+        let receipt = self
+            .escrow
+            .setEncryptedSecret(purchase_id, enc_aes_key_hex.to_string())
+            .send()
+            .await?
+            .get_receipt()
+            .await?;
+
+        if !receipt.status() {
+            return Err(anyhow!(
+                "setEncryptedSecret reverted in tx {}",
+                receipt.transaction_hash
+            ));
+        }
+        Ok(receipt.transaction_hash.to_string())
+    }
+}
+
+/// File the base64 receipt of `purchase_id`'s exchange proof is stored in.
+pub fn receipt_path(receipts_dir: &str, purchase_id: U256) -> PathBuf {
+    Path::new(receipts_dir).join(format!("purchase-{}.receipt", purchase_id))
+}
+
+/// Spawns the watcher if it is configured, see [`config::get_watcher_config`].
+/// The watcher needs an unlocked vault to look up dataset keys.
+pub fn spawn_if_configured(vault: Option<Arc<Vault>>) {
+    let Some(config) = config::get_watcher_config() else {
+        println!("[Watcher] Not configured, skipping");
+        return;
+    };
+    let Some(vault) = vault else {
+        println!("[Watcher] Dataset vault is locked, watcher disabled");
+        return;
+    };
+
+    match Watcher::new(config, vault) {
+        Ok(watcher) => {
+            tokio::spawn(watcher.run());
+        }
+        Err(err) => println!("[Watcher] Invalid configuration, watcher disabled: {:#}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! End-to-end test against a local node with the contracts deployed:
+    //!
+    //! ```bash
+    //! npx hardhat node
+    //! npx hardhat run scripts/deploy.ts --network localhost
+    //! WATCHER_TEST_ESCROW_ADDRESS=0x... WATCHER_TEST_DATASET_NFT_ADDRESS=0x... \
+    //!     RISC0_DEV_MODE=1 cargo test -p host watcher -- --ignored
+    //! ```
+    //!
+    //! `anvil` works as well after deploying the same contracts. The seller and
+    //! buyer are the first two default hardhat/anvil accounts.
+
+    use super::*;
+    use crate::vault::{DatasetKeyMaterial, VaultSecret};
+    use alloy::sol;
+    use rsa::pkcs8::EncodePublicKey;
+    use zeroize::Zeroizing;
+    use zkdrop_lib::types::SecretString;
+
+    const SELLER_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const BUYER_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    sol! {
+        #[sol(rpc)]
+        contract DatasetNFT {
+            function mintListingNFT(
+                address seller,
+                string memory metadataURI,
+                string memory category,
+                uint256 price
+            ) public returns (uint256);
+        }
+
+        #[sol(rpc)]
+        contract EscrowBuyer {
+            function submitPurchase(
+                address seller,
+                uint listingTokenId,
+                string memory datasetInfo,
+                string memory buyerPublicKey
+            ) public payable returns (uint256);
+        }
+    }
+
+    fn env_or(key: &str, default: &str) -> String {
+        std::env::var(key).unwrap_or_else(|_| default.to_string())
+    }
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("zkdrop-watcher-test-{}-{}", std::process::id(), name))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a local hardhat/anvil node with the contracts deployed"]
+    async fn delivers_wrapped_key_for_new_purchase() -> Result<()> {
+        let rpc_url = env_or("WATCHER_TEST_RPC_URL", "http://127.0.0.1:8545");
+        let escrow_address: Address = std::env::var("WATCHER_TEST_ESCROW_ADDRESS")?.parse()?;
+        let nft_address: Address = std::env::var("WATCHER_TEST_DATASET_NFT_ADDRESS")?.parse()?;
+
+        let seller_signer: PrivateKeySigner = SELLER_KEY.parse()?;
+        let seller = seller_signer.address();
+        let seller_provider = ProviderBuilder::new()
+            .wallet(seller_signer)
+            .connect_http(rpc_url.parse()?)
+            .erased();
+        let buyer_provider = ProviderBuilder::new()
+            .wallet(BUYER_KEY.parse::<PrivateKeySigner>()?)
+            .connect_http(rpc_url.parse()?)
+            .erased();
+        let start_block = seller_provider.get_block_number().await?;
+
+        // Seller lists a dataset and registers its key in a fresh vault
+        let nft = DatasetNFT::new(nft_address, seller_provider.clone());
+        let mint = nft.mintListingNFT(
+            seller,
+            String::from("ipfs://watcher-test"),
+            String::from("test"),
+            U256::from(1),
+        );
+        let listing_token_id = mint.call().await?;
+        mint.send().await?.get_receipt().await?;
+
+        let vault_path = temp_path("vault.json");
+        let secret = VaultSecret::Passphrase(Zeroizing::new(String::from("test")));
+        let vault = Arc::new(Vault::open(&vault_path, secret)?);
+        vault.register(
+            &listing_token_id.to_string(),
+            DatasetKeyMaterial {
+                aes_key_hex: SecretString::from(
+                    "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d",
+                ),
+                iv_hex: String::from("01020300000000000000000000000000"),
+                ciphertext_sha256_hex: String::from(
+                    "9dc42e9c375a1a5c762ab05ccba4763fb07e08e414673b0f11208a79e8925381",
+                ),
+            },
+        )?;
+
+        // Buyer submits a purchase carrying their RSA public key
+        let buyer_key = rsa::RsaPrivateKey::new(&mut rand::rngs::OsRng, 2048)?;
+        let buyer_pubkey = base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            buyer_key.to_public_key().to_public_key_der()?.as_bytes(),
+        );
+        let receipt = EscrowBuyer::new(escrow_address, buyer_provider)
+            .submitPurchase(seller, listing_token_id, String::from("watcher test"), buyer_pubkey)
+            .value(U256::from(1))
+            .send()
+            .await?
+            .get_receipt()
+            .await?;
+        let purchase_id = receipt
+            .inner
+            .logs()
+            .iter()
+            .find_map(|log| log.log_decode::<Escrow::PurchaseSubmitted>().ok())
+            .map(|log| log.inner.data.purchaseId)
+            .ok_or_else(|| anyhow!("no PurchaseSubmitted event"))?;
+
+        let config = WatcherConfig {
+            rpc_url,
+            escrow_address: escrow_address.to_string(),
+            seller_private_key: SecretString::from(SELLER_KEY),
+            start_block: Some(start_block),
+            poll_interval_secs: 1,
+            state_path: temp_path("state.json"),
+            receipts_dir: temp_path("receipts"),
+            prove_mode: Some(String::from("local")),
+        };
+        let watcher = Watcher::new(config, vault)?;
+        let mut state = WatcherState::default();
+        watcher.poll(&mut state).await?;
+
+        let Some(PurchaseProgress::Submitted { enc_aes_key_hex, receipt_path, .. }) =
+            state.progress(&purchase_id.to_string()).cloned()
+        else {
+            panic!("purchase not delivered: {:?}", state.progress(&purchase_id.to_string()));
+        };
+        assert!(Path::new(&receipt_path).exists());
+
+        let purchase = watcher.escrow.getPurchase(purchase_id).call().await?;
+        assert_eq!(purchase.encryptedSecret, enc_aes_key_hex);
+
+        let _ = fs::remove_file(vault_path);
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Progress of a single purchase handled by the watcher.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum PurchaseProgress {
+    /// Exchange proof generated, `setEncryptedSecret` not yet confirmed
+    Proved {
+        enc_aes_key_hex: String,
+        receipt_path: String,
+    },
+    /// `setEncryptedSecret` was mined
+    Submitted {
+        enc_aes_key_hex: String,
+        receipt_path: String,
+        tx_hash: String,
+    },
+    /// Proving or submitting failed transiently (RPC, prover); retried on the
+    /// next polls until it succeeds or runs out of attempts
+    Retrying { attempts: u32, last_error: String },
+    /// The purchase cannot be served (unknown dataset, bad public key, ...)
+    Failed { reason: String },
+}
+
+/// Persisted watcher progress, written after every change so the watcher
+/// resumes where it stopped after a restart.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WatcherState {
+    /// Last block whose `PurchaseSubmitted` logs were fully handled
+    pub last_processed_block: Option<u64>,
+
+    /// Progress per purchase ID
+    pub purchases: BTreeMap<String, PurchaseProgress>,
+}
+
+impl WatcherState {
+    pub fn load(path: &str) -> Result<Self> {
+        if !Path::new(path).exists() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed to read watcher state {}", path))?;
+        serde_json::from_str(&raw).with_context(|| format!("failed to parse watcher state {}", path))
+    }
+
+    /// Writes the state to a temporary file first and renames it, so a crash
+    /// mid-write never leaves a truncated state behind.
+    pub fn save(&self, path: &str) -> Result<()> {
+        let tmp_path = format!("{}.tmp", path);
+        fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("failed to write watcher state {}", tmp_path))?;
+        fs::rename(&tmp_path, path)
+            .with_context(|| format!("failed to move watcher state to {}", path))?;
+        Ok(())
+    }
+
+    pub fn progress(&self, purchase_id: &str) -> Option<&PurchaseProgress> {
+        self.purchases.get(purchase_id)
+    }
+
+    pub fn set_progress(&mut self, purchase_id: &str, progress: PurchaseProgress) {
+        self.purchases.insert(purchase_id.to_string(), progress);
+    }
+
+    /// Purchases whose key still has to be proved or submitted.
+    pub fn unfinished_purchases(&self) -> Vec<String> {
+        self.purchases
+            .iter()
+            .filter(|(_, progress)| {
+                matches!(progress, PurchaseProgress::Proved { .. } | PurchaseProgress::Retrying { .. })
+            })
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Records a transient failure of `purchase_id`. Unproved purchases give up
+    /// after `max_attempts`; proved ones keep their receipt and retry submission.
+    pub fn record_failure(&mut self, purchase_id: &str, error: String, max_attempts: u32) {
+        let attempts = match self.purchases.get(purchase_id) {
+            Some(PurchaseProgress::Proved { .. }) => return,
+            Some(PurchaseProgress::Retrying { attempts, .. }) => *attempts + 1,
+            _ => 1,
+        };
+        let progress = if attempts >= max_attempts {
+            PurchaseProgress::Failed {
+                reason: format!("gave up after {} attempts: {}", attempts, error),
+            }
+        } else {
+            PurchaseProgress::Retrying { attempts, last_error: error }
+        };
+        self.set_progress(purchase_id, progress);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_are_retried_then_given_up() {
+        let mut state = WatcherState::default();
+        state.record_failure("1", String::from("rpc down"), 3);
+        state.record_failure("1", String::from("rpc down"), 3);
+        assert!(matches!(
+            state.progress("1"),
+            Some(PurchaseProgress::Retrying { attempts: 2, .. })
+        ));
+        assert_eq!(state.unfinished_purchases(), vec![String::from("1")]);
+
+        state.record_failure("1", String::from("prover crashed"), 3);
+        match state.progress("1") {
+            Some(PurchaseProgress::Failed { reason }) => assert!(reason.contains("prover crashed")),
+            other => panic!("unexpected progress {:?}", other),
+        }
+        assert!(state.unfinished_purchases().is_empty());
+    }
+
+    #[test]
+    fn proved_purchases_keep_their_receipt() {
+        let mut state = WatcherState::default();
+        let proved = PurchaseProgress::Proved {
+            enc_aes_key_hex: String::from("ab"),
+            receipt_path: String::from("purchase-2.receipt"),
+        };
+        state.set_progress("2", proved);
+        state.record_failure("2", String::from("nonce too low"), 1);
+        assert!(matches!(state.progress("2"), Some(PurchaseProgress::Proved { .. })));
+        assert_eq!(state.unfinished_purchases(), vec![String::from("2")]);
+    }
+}
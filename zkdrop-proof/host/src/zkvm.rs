@@ -3,12 +3,26 @@ use std::time::Duration;
 use anyhow::Result;
 use bonsai_sdk::blocking::Client;
 use methods::{
-    AES_CTR_VERIFIER_ELF, AES_CTR_VERIFIER_ID, RSA_ENCRYPTER_ELF, RSA_ENCRYPTER_ID,
-    RSA_VERIFIER_ELF, RSA_VERIFIER_ID,
+    AES_CTR_VERIFIER_ELF, AES_CTR_VERIFIER_ID, AES_GCM_VERIFIER_ELF, AES_GCM_VERIFIER_ID,
+    AGGREGATOR_ELF, AGGREGATOR_ID, BATCH_ENCRYPTER_ELF, BATCH_ENCRYPTER_ID, CHACHA_VERIFIER_ELF,
+    CHACHA_VERIFIER_ID, CSV_PROVER_ELF, CSV_PROVER_ID, DISPUTE_PROVER_ELF, DISPUTE_PROVER_ID,
+    ECIES_ENCRYPTER_ELF, ECIES_ENCRYPTER_ID, ECIES_VERIFIER_ELF, ECIES_VERIFIER_ID,
+    ETH_ENCRYPTER_ELF, ETH_ENCRYPTER_ID, ETH_VERIFIER_ELF, ETH_VERIFIER_ID, HASHLOCK_VERIFIER_ELF,
+    HASHLOCK_VERIFIER_ID, IMAGE_PROVER_ELF, IMAGE_PROVER_ID, JSON_PROVER_ELF, JSON_PROVER_ID,
+    LINEAGE_PROVER_ELF, LINEAGE_PROVER_ID, PII_PROVER_ELF, PII_PROVER_ID, PREDICATE_PROVER_ELF,
+    PREDICATE_PROVER_ID, PREVIEW_PROVER_ELF, PREVIEW_PROVER_ID, REWRAP_PROVER_ELF, REWRAP_PROVER_ID,
+    RSA_ENCRYPTER_ELF, RSA_ENCRYPTER_ID, RSA_VERIFIER_ELF, RSA_VERIFIER_ID,
 };
 use risc0_zkvm::{compute_image_id, default_prover, Digest, ExecutorEnv, Receipt};
+use serde::Serialize;
 use zkdrop_lib::types::{
-    AesCtrDecryptionProofInput, RsaEncryptAesKeyInput, RsaEncryptedAesKeyInput,
+    AesCtrDecryptionProofInput, AesGcmEncryptionProofInput, AggregationInput,
+    BatchEncryptAesKeyInput,
+    ChaChaDecryptionProofInput, CsvPropertiesInput, DisputeProofInput, EciesEncryptAesKeyInput,
+    EciesEncryptedAesKeyInput, EthEncryptAesKeyInput, EthEncryptedAesKeyInput, HashLockProofInput,
+    ImageMetadataInput, JsonConformanceInput, LineageProofInput, PiiAttestationInput,
+    PredicateProofInput, RewrapInput, RsaEncryptAesKeyInput, RsaEncryptedAesKeyInput,
+    SamplePreviewInput,
 };
 
 use bincode::{deserialize, serialize};
@@ -33,6 +47,7 @@ fn run_local(env: ExecutorEnv, elf: &[u8], method_id: &[u32; 8]) -> Receipt {
 
 fn run_in_bonsai(
     input_data: Vec<u8>,
+    assumptions: &[Receipt],
     elf: &[u8],
     method_id: &[u32; 8],
     use_snark: bool,
@@ -44,7 +59,10 @@ fn run_in_bonsai(
 
     let input_id = client.upload_input(input_data)?;
 
-    let assumptions: Vec<String> = vec![];
+    let assumptions = assumptions
+        .iter()
+        .map(|receipt| Ok(client.upload_receipt(serialize(receipt)?)?))
+        .collect::<Result<Vec<String>>>()?;
     let execute_only = false;
 
     let session = client.create_session(image_id.clone(), input_id, assumptions, execute_only)?;
@@ -115,62 +133,186 @@ pub fn run_stark2snark(session_id: String) -> Result<Receipt> {
     }
 }
 
-pub fn run_aes_verify(input: AesCtrDecryptionProofInput, mode: ProveMode) -> Result<Receipt> {
+/// Proves `elf` on `input` with the selected backend and verifies the receipt
+/// against `method_id`.
+fn prove<T: Serialize>(input: &T, mode: ProveMode, elf: &[u8], method_id: &[u32; 8]) -> Result<Receipt> {
+    prove_with_assumptions(input, &[], mode, elf, method_id)
+}
+
+/// Like [`prove`], with receipts the guest may `env::verify` passed as
+/// assumptions (uploaded to Bonsai in the Bonsai modes).
+fn prove_with_assumptions<T: Serialize>(
+    input: &T,
+    assumptions: &[Receipt],
+    mode: ProveMode,
+    elf: &[u8],
+    method_id: &[u32; 8],
+) -> Result<Receipt> {
     match mode {
         ProveMode::Local => {
-            let env = ExecutorEnv::builder().write(&input)?.build()?;
-            Ok(run_local(env, AES_CTR_VERIFIER_ELF, &AES_CTR_VERIFIER_ID))
+            let mut builder = ExecutorEnv::builder();
+            for receipt in assumptions {
+                builder.add_assumption(receipt.clone());
+            }
+            let env = builder.write(input)?.build()?;
+            Ok(run_local(env, elf, method_id))
+        }
+        ProveMode::Bonsai => {
+            run_in_bonsai(serialize(input)?, assumptions, elf, method_id, false)
+        }
+        ProveMode::BonsaiWithSnark => {
+            run_in_bonsai(serialize(input)?, assumptions, elf, method_id, true)
         }
-        ProveMode::Bonsai => run_in_bonsai(
-            serialize(&input)?,
-            AES_CTR_VERIFIER_ELF,
-            &AES_CTR_VERIFIER_ID,
-            false,
-        ),
-        ProveMode::BonsaiWithSnark => run_in_bonsai(
-            serialize(&input)?,
-            AES_CTR_VERIFIER_ELF,
-            &AES_CTR_VERIFIER_ID,
-            true,
-        ),
     }
 }
 
+pub fn run_aes_verify(input: AesCtrDecryptionProofInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, AES_CTR_VERIFIER_ELF, &AES_CTR_VERIFIER_ID)
+}
+
+pub fn run_aes_gcm_verify(input: AesGcmEncryptionProofInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, AES_GCM_VERIFIER_ELF, &AES_GCM_VERIFIER_ID)
+}
+
+pub fn run_chacha_verify(input: ChaChaDecryptionProofInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, CHACHA_VERIFIER_ELF, &CHACHA_VERIFIER_ID)
+}
+
+pub fn run_csv_prove(input: CsvPropertiesInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, CSV_PROVER_ELF, &CSV_PROVER_ID)
+}
+
+pub fn run_hashlock_verify(input: HashLockProofInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, HASHLOCK_VERIFIER_ELF, &HASHLOCK_VERIFIER_ID)
+}
+
+pub fn run_image_prove(input: ImageMetadataInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, IMAGE_PROVER_ELF, &IMAGE_PROVER_ID)
+}
+
+pub fn run_json_prove(input: JsonConformanceInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, JSON_PROVER_ELF, &JSON_PROVER_ID)
+}
+
+pub fn run_lineage_prove(input: LineageProofInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, LINEAGE_PROVER_ELF, &LINEAGE_PROVER_ID)
+}
+
+pub fn run_pii_prove(input: PiiAttestationInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, PII_PROVER_ELF, &PII_PROVER_ID)
+}
+
+pub fn run_predicate_prove(input: PredicateProofInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, PREDICATE_PROVER_ELF, &PREDICATE_PROVER_ID)
+}
+
+pub fn run_preview_prove(input: SamplePreviewInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, PREVIEW_PROVER_ELF, &PREVIEW_PROVER_ID)
+}
+
 pub fn run_rsa_encrypt(input: RsaEncryptAesKeyInput, mode: ProveMode) -> Result<Receipt> {
-    match mode {
-        ProveMode::Local => {
-            let env = ExecutorEnv::builder().write(&input)?.build()?;
-            Ok(run_local(env, RSA_ENCRYPTER_ELF, &RSA_ENCRYPTER_ID))
-        }
-        ProveMode::Bonsai => run_in_bonsai(
-            serialize(&input)?,
-            RSA_ENCRYPTER_ELF,
-            &RSA_ENCRYPTER_ID,
-            false,
-        ),
-        ProveMode::BonsaiWithSnark => run_in_bonsai(
-            serialize(&input)?,
-            RSA_ENCRYPTER_ELF,
-            &RSA_ENCRYPTER_ID,
-            true,
-        ),
-    }
+    prove(&input, mode, RSA_ENCRYPTER_ELF, &RSA_ENCRYPTER_ID)
 }
 
 pub fn run_rsa_verify(input: RsaEncryptedAesKeyInput, mode: ProveMode) -> Result<Receipt> {
-    match mode {
-        ProveMode::Local => {
-            let env = ExecutorEnv::builder().write(&input)?.build()?;
-            Ok(run_local(env, RSA_VERIFIER_ELF, &RSA_VERIFIER_ID))
-        }
-        ProveMode::Bonsai => run_in_bonsai(
-            serialize(&input)?,
-            RSA_VERIFIER_ELF,
-            &RSA_VERIFIER_ID,
-            false,
-        ),
-        ProveMode::BonsaiWithSnark => {
-            run_in_bonsai(serialize(&input)?, RSA_VERIFIER_ELF, &RSA_VERIFIER_ID, true)
-        }
+    prove(&input, mode, RSA_VERIFIER_ELF, &RSA_VERIFIER_ID)
+}
+
+pub fn run_batch_encrypt(input: BatchEncryptAesKeyInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, BATCH_ENCRYPTER_ELF, &BATCH_ENCRYPTER_ID)
+}
+
+pub fn run_rewrap_prove(input: RewrapInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, REWRAP_PROVER_ELF, &REWRAP_PROVER_ID)
+}
+
+pub fn run_dispute_prove(input: DisputeProofInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, DISPUTE_PROVER_ELF, &DISPUTE_PROVER_ID)
+}
+
+pub fn run_ecies_encrypt(input: EciesEncryptAesKeyInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, ECIES_ENCRYPTER_ELF, &ECIES_ENCRYPTER_ID)
+}
+
+pub fn run_ecies_verify(input: EciesEncryptedAesKeyInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, ECIES_VERIFIER_ELF, &ECIES_VERIFIER_ID)
+}
+
+pub fn run_eth_encrypt(input: EthEncryptAesKeyInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, ETH_ENCRYPTER_ELF, &ETH_ENCRYPTER_ID)
+}
+
+pub fn run_eth_verify(input: EthEncryptedAesKeyInput, mode: ProveMode) -> Result<Receipt> {
+    prove(&input, mode, ETH_VERIFIER_ELF, &ETH_VERIFIER_ID)
+}
+
+pub fn run_aggregate(
+    input: AggregationInput,
+    receipts: &[Receipt],
+    mode: ProveMode,
+) -> Result<Receipt> {
+    prove_with_assumptions(&input, receipts, mode, AGGREGATOR_ELF, &AGGREGATOR_ID)
+}
+
+/// Guests whose receipts `/aggregate` accepts, by name and image ID
+pub const AGGREGATABLE_GUESTS: &[(&str, [u32; 8])] = &[
+    ("aes_ctr_verifier", AES_CTR_VERIFIER_ID),
+    ("aes_gcm_verifier", AES_GCM_VERIFIER_ID),
+    ("batch_encrypter", BATCH_ENCRYPTER_ID),
+    ("chacha_verifier", CHACHA_VERIFIER_ID),
+    ("csv_prover", CSV_PROVER_ID),
+    ("dispute_prover", DISPUTE_PROVER_ID),
+    ("ecies_encrypter", ECIES_ENCRYPTER_ID),
+    ("ecies_verifier", ECIES_VERIFIER_ID),
+    ("eth_encrypter", ETH_ENCRYPTER_ID),
+    ("eth_verifier", ETH_VERIFIER_ID),
+    ("hashlock_verifier", HASHLOCK_VERIFIER_ID),
+    ("image_prover", IMAGE_PROVER_ID),
+    ("json_prover", JSON_PROVER_ID),
+    ("lineage_prover", LINEAGE_PROVER_ID),
+    ("pii_prover", PII_PROVER_ID),
+    ("predicate_prover", PREDICATE_PROVER_ID),
+    ("preview_prover", PREVIEW_PROVER_ID),
+    ("rewrap_prover", REWRAP_PROVER_ID),
+    ("rsa_encrypter", RSA_ENCRYPTER_ID),
+    ("rsa_verifier", RSA_VERIFIER_ID),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose, Engine as _};
+    use rsa::pkcs8::EncodePublicKey;
+    use zkdrop_lib::types::{OaepParams, RsaEncryptAesKeyOutput, SecretString};
+
+    /// Wraps an AES key to a freshly generated `bits` key inside the
+    /// `rsa_encrypter` guest and checks the journal.
+    fn prove_rsa_encrypt(bits: usize) {
+        let key = rsa::RsaPrivateKey::new(&mut rand::rngs::OsRng, bits).unwrap().to_public_key();
+        let der = key.to_public_key_der().unwrap();
+        let input = RsaEncryptAesKeyInput {
+            aes_key_hex: SecretString::from(
+                "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d",
+            ),
+            rsa_pubkey_base64: general_purpose::STANDARD.encode(der.as_bytes()),
+            oaep: OaepParams::default(),
+            wrap_seed_hex: crate::rsa_keys::random_wrap_seed(),
+        };
+        let receipt = run_rsa_encrypt(input, ProveMode::Local).unwrap();
+        let output: RsaEncryptAesKeyOutput = receipt.journal.decode().unwrap();
+        assert!(output.is_valid, "{}", output.message);
+        assert_eq!(output.enc_aes_key_hex.len(), 2 * bits / 8);
+    }
+
+    #[test]
+    #[ignore = "needs the guests built with the RISC Zero toolchain; run with RISC0_DEV_MODE=1"]
+    fn rsa_encrypter_wraps_to_3072_bit_key() {
+        prove_rsa_encrypt(3072);
+    }
+
+    #[test]
+    #[ignore = "needs the guests built with the RISC Zero toolchain; run with RISC0_DEV_MODE=1"]
+    fn rsa_encrypter_wraps_to_4096_bit_key() {
+        prove_rsa_encrypt(4096);
     }
 }
@@ -3,7 +3,7 @@
 use risc0_zkvm::guest::env;
 
 use zkdrop_lib::types::AesCtrDecryptionProofInput;
-use zkdrop_lib::aes_ctr::aes_ctr_verify;
+use zkdrop_lib::aes_ctr::aes_ctr_prove;
 
 risc0_zkvm::guest::entry!(main);
 
@@ -11,8 +11,8 @@ pub fn main() {
     // Read input from host
     let input: AesCtrDecryptionProofInput = env::read();
 
-    // Run AES-CTR verification
-    let result = aes_ctr_verify(input);
+    // Run AES-CTR verification (decompressing first if requested)
+    let result = aes_ctr_prove(input);
 
     // Return result to host
     env::commit(&result);
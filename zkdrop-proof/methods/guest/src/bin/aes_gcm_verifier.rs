@@ -0,0 +1,20 @@
+#![no_main]
+
+
+use risc0_zkvm::guest::env;
+use zkdrop_lib::types::AesGcmEncryptionProofInput;
+use zkdrop_lib::aes_gcm::aes_gcm_verify;
+
+risc0_zkvm::guest::entry!(main);
+
+
+pub fn main() {
+    // Receive input from host
+    let input: AesGcmEncryptionProofInput = env::read();
+
+    // Verify the ciphertext and tag are the AES-256-GCM encryption of the plaintext
+    let verification_output = aes_gcm_verify(input);
+
+    // Return result to host
+    env::commit(&verification_output);
+}
@@ -0,0 +1,25 @@
+#![no_main]
+
+
+use risc0_zkvm::guest::env;
+use zkdrop_lib::types::AggregationInput;
+use zkdrop_lib::aggregate::aggregate;
+
+risc0_zkvm::guest::entry!(main);
+
+
+pub fn main() {
+    // Receive the sub-receipt claims; the receipts arrive as assumptions
+    let input: AggregationInput = env::read();
+
+    // Verify every purchase proof, resolved against its assumption receipt
+    for receipt in &input.receipts {
+        env::verify(receipt.image_id, &receipt.journal).unwrap();
+    }
+
+    // Commit the Merkle root of the verified sub-journals
+    let aggregation_output = aggregate(&input);
+
+    // Return result to host
+    env::commit(&aggregation_output);
+}
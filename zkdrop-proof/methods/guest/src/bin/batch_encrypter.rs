@@ -0,0 +1,20 @@
+#![no_main]
+
+
+use risc0_zkvm::guest::env;
+use zkdrop_lib::types::BatchEncryptAesKeyInput;
+use zkdrop_lib::batch::batch_encrypt;
+
+risc0_zkvm::guest::entry!(main);
+
+
+pub fn main() {
+    // Receive input from host
+    let input: BatchEncryptAesKeyInput = env::read();
+
+    // Wrap the AES key for every buyer
+    let encryption_output = batch_encrypt(input);
+
+    // Return result to host
+    env::commit(&encryption_output);
+}
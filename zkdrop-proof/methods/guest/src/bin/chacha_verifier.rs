@@ -0,0 +1,20 @@
+#![no_main]
+
+
+use risc0_zkvm::guest::env;
+use zkdrop_lib::types::ChaChaDecryptionProofInput;
+use zkdrop_lib::chacha::chacha_verify;
+
+risc0_zkvm::guest::entry!(main);
+
+
+pub fn main() {
+    // Receive input from host
+    let input: ChaChaDecryptionProofInput = env::read();
+
+    // Verify the ciphertext and tag are the ChaCha20-Poly1305 encryption of the plaintext
+    let verification_output = chacha_verify(input);
+
+    // Return result to host
+    env::commit(&verification_output);
+}
@@ -0,0 +1,19 @@
+#![no_main]
+
+use risc0_zkvm::guest::env;
+
+use zkdrop_lib::types::CsvPropertiesInput;
+use zkdrop_lib::csv::csv_prove;
+
+risc0_zkvm::guest::entry!(main);
+
+pub fn main() {
+    // Read input from host (key and CSV plaintext stay private)
+    let input: CsvPropertiesInput = env::read();
+
+    // Verify the encryption, check the schema and compute the statistics
+    let result = csv_prove(input);
+
+    // Return result to host
+    env::commit(&result);
+}
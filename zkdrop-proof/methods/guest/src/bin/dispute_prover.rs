@@ -0,0 +1,19 @@
+#![no_main]
+
+use risc0_zkvm::guest::env;
+
+use zkdrop_lib::types::DisputeProofInput;
+use zkdrop_lib::dispute::dispute_prove;
+
+risc0_zkvm::guest::entry!(main);
+
+pub fn main() {
+    // Read input from host (the buyer RSA private key stays a private witness)
+    let input: DisputeProofInput = env::read();
+
+    // Check the delivered key against the seller's commitments
+    let result = dispute_prove(input);
+
+    // Return result to host
+    env::commit(&result);
+}
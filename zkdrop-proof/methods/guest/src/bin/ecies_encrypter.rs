@@ -0,0 +1,19 @@
+#![no_main]
+
+
+use risc0_zkvm::guest::env;
+use zkdrop_lib::types::EciesEncryptAesKeyInput;
+use zkdrop_lib::ecies::ecies_encrypt;
+
+risc0_zkvm::guest::entry!(main);
+
+pub fn main() {
+    // Receive input from host
+    let input: EciesEncryptAesKeyInput = env::read();
+
+    // Wrap the AES key to the buyer's X25519 public key
+    let encryption_output = ecies_encrypt(input);
+
+    // Return result to host
+    env::commit(&encryption_output);
+}
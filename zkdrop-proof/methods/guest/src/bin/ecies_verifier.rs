@@ -0,0 +1,20 @@
+#![no_main]
+
+
+use risc0_zkvm::guest::env;
+use zkdrop_lib::types::EciesEncryptedAesKeyInput;
+use zkdrop_lib::ecies::ecies_verify;
+
+risc0_zkvm::guest::entry!(main);
+
+
+pub fn main() {
+    // Receive input from host
+    let input: EciesEncryptedAesKeyInput = env::read();
+
+    // Verify ECIES wrapping of AES key
+    let verification_output = ecies_verify(input);
+
+    // Return result to host
+    env::commit(&verification_output);
+}
@@ -0,0 +1,19 @@
+#![no_main]
+
+
+use risc0_zkvm::guest::env;
+use zkdrop_lib::types::EthEncryptAesKeyInput;
+use zkdrop_lib::eth_encryption::eth_encrypt;
+
+risc0_zkvm::guest::entry!(main);
+
+pub fn main() {
+    // Receive input from host
+    let input: EthEncryptAesKeyInput = env::read();
+
+    // Seal the AES key to the buyer's wallet encryption key
+    let encryption_output = eth_encrypt(input);
+
+    // Return result to host
+    env::commit(&encryption_output);
+}
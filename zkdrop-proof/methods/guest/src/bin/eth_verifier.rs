@@ -0,0 +1,20 @@
+#![no_main]
+
+
+use risc0_zkvm::guest::env;
+use zkdrop_lib::types::EthEncryptedAesKeyInput;
+use zkdrop_lib::eth_encryption::eth_verify;
+
+risc0_zkvm::guest::entry!(main);
+
+
+pub fn main() {
+    // Receive input from host
+    let input: EthEncryptedAesKeyInput = env::read();
+
+    // Verify the AES key was sealed to the wallet encryption key
+    let verification_output = eth_verify(input);
+
+    // Return result to host
+    env::commit(&verification_output);
+}
@@ -0,0 +1,20 @@
+#![no_main]
+
+
+use risc0_zkvm::guest::env;
+use zkdrop_lib::types::HashLockProofInput;
+use zkdrop_lib::hashlock::hashlock_verify;
+
+risc0_zkvm::guest::entry!(main);
+
+
+pub fn main() {
+    // Receive input from host
+    let input: HashLockProofInput = env::read();
+
+    // Verify the decryption and commit the hash of the key
+    let verification_output = hashlock_verify(input);
+
+    // Return result to host
+    env::commit(&verification_output);
+}
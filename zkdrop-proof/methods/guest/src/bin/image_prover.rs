@@ -0,0 +1,19 @@
+#![no_main]
+
+use risc0_zkvm::guest::env;
+
+use zkdrop_lib::types::ImageMetadataInput;
+use zkdrop_lib::image::image_prove;
+
+risc0_zkvm::guest::entry!(main);
+
+pub fn main() {
+    // Read input from host (key stays private)
+    let input: ImageMetadataInput = env::read();
+
+    // Decrypt the archive and read the image headers
+    let result = image_prove(input);
+
+    // Return result to host
+    env::commit(&result);
+}
@@ -0,0 +1,19 @@
+#![no_main]
+
+use risc0_zkvm::guest::env;
+
+use zkdrop_lib::types::JsonConformanceInput;
+use zkdrop_lib::json_schema::json_prove;
+
+risc0_zkvm::guest::entry!(main);
+
+pub fn main() {
+    // Read input from host (key and JSON plaintext stay private)
+    let input: JsonConformanceInput = env::read();
+
+    // Verify the encryption and validate every record against the schema
+    let result = json_prove(input);
+
+    // Return result to host
+    env::commit(&result);
+}
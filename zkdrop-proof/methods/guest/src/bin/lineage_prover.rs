@@ -0,0 +1,19 @@
+#![no_main]
+
+use risc0_zkvm::guest::env;
+
+use zkdrop_lib::types::LineageProofInput;
+use zkdrop_lib::lineage::lineage_prove;
+
+risc0_zkvm::guest::entry!(main);
+
+pub fn main() {
+    // Read input from host (keys and both plaintexts stay private)
+    let input: LineageProofInput = env::read();
+
+    // Verify both encryptions and check the new version extends the old one
+    let result = lineage_prove(input);
+
+    // Return result to host
+    env::commit(&result);
+}
@@ -0,0 +1,19 @@
+#![no_main]
+
+use risc0_zkvm::guest::env;
+
+use zkdrop_lib::types::PiiAttestationInput;
+use zkdrop_lib::pii::pii_prove;
+
+risc0_zkvm::guest::entry!(main);
+
+pub fn main() {
+    // Read input from host (key and plaintext stay private)
+    let input: PiiAttestationInput = env::read();
+
+    // Verify the encryption and run the PII detectors over the plaintext
+    let result = pii_prove(input);
+
+    // Return result to host
+    env::commit(&result);
+}
@@ -0,0 +1,19 @@
+#![no_main]
+
+use risc0_zkvm::guest::env;
+
+use zkdrop_lib::types::PredicateProofInput;
+use zkdrop_lib::predicate::predicate_prove;
+
+risc0_zkvm::guest::entry!(main);
+
+pub fn main() {
+    // Read input from host (key and plaintext stay private, patterns are public)
+    let input: PredicateProofInput = env::read();
+
+    // Verify the encryption and evaluate the patterns over the plaintext
+    let result = predicate_prove(input);
+
+    // Return result to host
+    env::commit(&result);
+}
@@ -0,0 +1,19 @@
+#![no_main]
+
+use risc0_zkvm::guest::env;
+
+use zkdrop_lib::types::SamplePreviewInput;
+use zkdrop_lib::preview::preview_prove;
+
+risc0_zkvm::guest::entry!(main);
+
+pub fn main() {
+    // Read input from host (key and full plaintext stay private)
+    let input: SamplePreviewInput = env::read();
+
+    // Verify the encryption and extract the selected excerpts
+    let result = preview_prove(input);
+
+    // Return result to host
+    env::commit(&result);
+}
@@ -0,0 +1,20 @@
+#![no_main]
+
+
+use risc0_zkvm::guest::env;
+use zkdrop_lib::types::RewrapInput;
+use zkdrop_lib::rewrap::rewrap_prove;
+
+risc0_zkvm::guest::entry!(main);
+
+
+pub fn main() {
+    // Receive input from host (owner private key or AES key stays private)
+    let input: RewrapInput = env::read();
+
+    // Unwrap the current key and wrap it to the new owner
+    let rewrap_output = rewrap_prove(input);
+
+    // Return result to host
+    env::commit(&rewrap_output);
+}
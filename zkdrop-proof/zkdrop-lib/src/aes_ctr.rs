@@ -2,46 +2,122 @@
 use aes::Aes256;
 use ctr::cipher::{KeyIvInit, StreamCipher}; // AES-CTR trait
 use hex::{decode};
+use alloc::format;
 use alloc::string::String;
+use alloc::vec::Vec;
+use zeroize::Zeroizing;
 
 type Aes256Ctr = ctr::Ctr128BE<Aes256>; // uses 128-bit (16-byte) IV, which you're already using
-use crate::types::AesCtrDecryptionProofInput;
+use crate::compression::decompress;
+use crate::ipfs::ipfs_cid;
+use crate::types::{AesCtrDecryptionProofInput, AesCtrDecryptionProofOutput, Compression};
+use crate::utils::sha256_hex;
+use crate::validation::MAX_PAYLOAD_LEN;
 
-pub fn aes_ctr_verify(
-    input : AesCtrDecryptionProofInput,
-) -> (bool, String) {
+/// Encrypts (or decrypts) `data` with AES-256-CTR under `key` and the 16-byte `iv`.
+pub fn aes_ctr_encrypt(key: &[u8], iv: &[u8], data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let mut cipher = Aes256Ctr::new_from_slices(key, iv).map_err(|_| "Invalid key or IV length")?;
+    let mut output = data.to_vec();
+    cipher.apply_keystream(&mut output);
+    Ok(output)
+}
+
+/// Checks that the ciphertext is the AES-CTR encryption of the plaintext or,
+/// with `compression` set, of a compressed stream that decompresses to the
+/// plaintext. Returns the decrypted compressed stream in the latter case.
+fn aes_ctr_check(
+    input: &AesCtrDecryptionProofInput,
+) -> Result<Option<Zeroizing<Vec<u8>>>, String> {
     // Convert hex fields to binary
-    let key = match decode(&input.aes_key_hex) {
-        Ok(k) => k,
-        Err(_) => return (false, String::from("Invalid AES key hex"))
+    let key = match decode(input.aes_key_hex.expose_secret()) {
+        Ok(k) => Zeroizing::new(k),
+        Err(_) => return Err(String::from("Invalid AES key hex"))
     };
-    
+
     let iv = match decode(&input.iv_hex) {
         Ok(i) => i,
-        Err(_) => return (false, String::from("Invalid IV hex"))
+        Err(_) => return Err(String::from("Invalid IV hex"))
     };
-    
+
     let ciphertext_expected = match decode(&input.ciphertext_hex) {
         Ok(c) => c,
-        Err(_) => return (false, String::from("Invalid ciphertext hex"))
+        Err(_) => return Err(String::from("Invalid ciphertext hex"))
     };
 
     // Convert plaintext to bytes
-    let plaintext_bytes = input.plaintext_utf8.as_bytes();
+    let plaintext_bytes = input.plaintext_utf8.expose_secret().as_bytes();
+
+    if input.compression != Compression::None {
+        // Decrypt, then decompress and compare with the plaintext
+        let compressed = aes_ctr_encrypt(key.as_slice(), &iv, &ciphertext_expected)?;
+        let compressed = Zeroizing::new(compressed);
+        let decompressed = decompress(input.compression, &compressed, MAX_PAYLOAD_LEN)?;
+        let decompressed = Zeroizing::new(decompressed);
+        return if decompressed.as_slice() == plaintext_bytes {
+            Ok(Some(compressed))
+        } else {
+            Err(String::from("❌ Decompressed plaintext mismatch"))
+        };
+    }
 
     // Encrypt using AES-CTR
-    let mut cipher = match Aes256Ctr::new_from_slices(&key, &iv) {
-        Ok(c) => c,
-        Err(_) => return (false, String::from("Invalid key or IV length"))
-    };
-    
-    let mut ciphertext = plaintext_bytes.to_vec();
-    cipher.apply_keystream(&mut ciphertext);
+    let ciphertext = aes_ctr_encrypt(key.as_slice(), &iv, plaintext_bytes)?;
 
     // Compare ciphertexts
     if ciphertext == ciphertext_expected {
-        return (true, String::from("✅ Ciphertext matches AES-CTR encryption"))
+        Ok(None)
     } else {
-        return (false, String::from("❌ Ciphertext mismatch"))
+        Err(String::from("❌ Ciphertext mismatch"))
+    }
+}
+
+pub fn aes_ctr_verify(
+    input : AesCtrDecryptionProofInput,
+) -> (bool, String) {
+    match aes_ctr_check(&input) {
+        Ok(_) => (true, verified_message(input.compression)),
+        Err(err) => (false, err),
+    }
+}
+
+fn verified_message(compression: Compression) -> String {
+    match compression {
+        Compression::None => String::from("✅ Ciphertext matches AES-CTR encryption"),
+        _ => format!(
+            "✅ Ciphertext matches AES-CTR encryption of the {}-compressed plaintext",
+            compression.name()
+        ),
+    }
+}
+
+/// AES-CTR decryption proof journal: `aes_ctr_verify` plus the hashes binding
+/// the plaintext to the ciphertext. With compression, both the decrypted
+/// compressed stream and the decompressed plaintext are committed. The IPFS
+/// CID of the ciphertext ties the proof to the `file_enc` pinned for a listing.
+pub fn aes_ctr_prove(input: AesCtrDecryptionProofInput) -> AesCtrDecryptionProofOutput {
+    let mut output = AesCtrDecryptionProofOutput {
+        is_valid: false,
+        message: String::new(),
+        compression: input.compression,
+        ciphertext_sha256_hex: String::new(),
+        ciphertext_cid: String::new(),
+        compressed_sha256_hex: None,
+        plaintext_sha256_hex: String::new(),
+    };
+    if let Ok(ciphertext) = decode(&input.ciphertext_hex) {
+        output.ciphertext_sha256_hex = sha256_hex(&ciphertext);
+        output.ciphertext_cid = ipfs_cid(&ciphertext);
+    }
+
+    match aes_ctr_check(&input) {
+        Ok(compressed) => {
+            output.is_valid = true;
+            output.message = verified_message(input.compression);
+            output.compressed_sha256_hex = compressed.map(|c| sha256_hex(&c));
+            output.plaintext_sha256_hex =
+                sha256_hex(input.plaintext_utf8.expose_secret().as_bytes());
+        }
+        Err(err) => output.message = err,
     }
-}
\ No newline at end of file
+    output
+}
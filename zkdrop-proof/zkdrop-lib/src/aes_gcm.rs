@@ -0,0 +1,81 @@
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hex::decode;
+use alloc::string::String;
+use zeroize::Zeroizing;
+
+use crate::types::{AesGcmEncryptionProofInput, AesGcmEncryptionProofOutput, ProofOutput};
+use crate::ipfs::ipfs_cid;
+use crate::utils::sha256_hex;
+
+/// AES-GCM nonce length in bytes
+pub const AES_GCM_NONCE_LEN: usize = 12;
+
+/// AES-GCM tag length in bytes
+pub const AES_GCM_TAG_LEN: usize = 16;
+
+/// Proves that `ciphertext_hex` (ciphertext || tag) is the AES-256-GCM encryption
+/// of the plaintext under the key, nonce and optional AAD.
+///
+/// Besides the verdict, the output commits the public nonce and AAD and SHA-256
+/// commitments to the key, plaintext and ciphertext, so a buyer can later check
+/// the key and content they receive against the proof. `file_cid` is the IPFS
+/// CID of the file as the frontend uploads it, `nonce || ciphertext || tag`.
+pub fn aes_gcm_verify(input: AesGcmEncryptionProofInput) -> AesGcmEncryptionProofOutput {
+    let mut output = AesGcmEncryptionProofOutput {
+        is_valid: false,
+        message: String::new(),
+        nonce_hex: input.nonce_hex.clone(),
+        aad_hex: input.aad_hex.clone(),
+        key_sha256_hex: String::new(),
+        plaintext_sha256_hex: String::new(),
+        ciphertext_sha256_hex: String::new(),
+        file_cid: String::new(),
+    };
+
+    let key = match decode(input.aes_key_hex.expose_secret()) {
+        Ok(k) => Zeroizing::new(k),
+        Err(_) => return output.fail("Invalid AES key hex"),
+    };
+
+    let nonce = match decode(&input.nonce_hex) {
+        Ok(n) if n.len() == AES_GCM_NONCE_LEN => n,
+        _ => return output.fail("Invalid nonce"),
+    };
+
+    let aad = match input.aad_hex.as_deref().map(decode).transpose() {
+        Ok(aad) => aad.unwrap_or_default(),
+        Err(_) => return output.fail("Invalid AAD hex"),
+    };
+
+    let ciphertext_expected = match decode(&input.ciphertext_hex) {
+        Ok(c) => c,
+        Err(_) => return output.fail("Invalid ciphertext hex"),
+    };
+
+    let cipher = match Aes256Gcm::new_from_slice(&key) {
+        Ok(c) => c,
+        Err(_) => return output.fail("Invalid key length"),
+    };
+
+    let plaintext_bytes = input.plaintext_utf8.expose_secret().as_bytes();
+    let payload = Payload { msg: plaintext_bytes, aad: &aad };
+    let ciphertext = match cipher.encrypt(Nonce::from_slice(&nonce), payload) {
+        Ok(c) => c,
+        Err(_) => return output.fail("Encryption failed"),
+    };
+
+    output.key_sha256_hex = sha256_hex(&key);
+    output.plaintext_sha256_hex = sha256_hex(plaintext_bytes);
+    output.ciphertext_sha256_hex = sha256_hex(&ciphertext_expected);
+    output.file_cid = ipfs_cid(&[nonce.as_slice(), &ciphertext_expected].concat());
+
+    // Compare ciphertexts (including the tag)
+    if ciphertext == ciphertext_expected {
+        output.is_valid = true;
+        output.message = String::from("✅ Ciphertext and tag match AES-GCM encryption");
+    } else {
+        output.message = String::from("❌ Ciphertext or tag mismatch");
+    }
+    output
+}
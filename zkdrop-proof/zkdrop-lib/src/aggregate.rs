@@ -0,0 +1,48 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+
+use crate::merkle::{leaf_hash, root_of_leaves};
+use crate::types::{AggregatedReceipt, AggregationInput, AggregationOutput};
+
+/// Image ID as the 32 bytes RISC Zero hashes and displays: each word little-endian.
+pub fn image_id_bytes(image_id: &[u32; 8]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (i, word) in image_id.iter().enumerate() {
+        bytes[4 * i..4 * i + 4].copy_from_slice(&word.to_le_bytes());
+    }
+    bytes
+}
+
+/// Merkle leaf of one sub-receipt: `SHA-256(0x00 || image_id || SHA-256(journal))`.
+/// `SHA-256(journal)` is the journal digest of the receipt claim, so a
+/// settlement contract can recompute the leaf from a decoded purchase journal.
+pub fn receipt_leaf(receipt: &AggregatedReceipt) -> [u8; 32] {
+    let mut preimage = [0u8; 64];
+    preimage[..32].copy_from_slice(&image_id_bytes(&receipt.image_id));
+    preimage[32..].copy_from_slice(&Sha256::digest(&receipt.journal));
+    leaf_hash(&preimage)
+}
+
+/// Commits the Merkle root over the leaves of `input.receipts`, in order. The
+/// guest verifies every sub-receipt with `env::verify` before calling this, so
+/// the root only covers claims that were proven.
+pub fn aggregate(input: &AggregationInput) -> AggregationOutput {
+    let mut output = AggregationOutput {
+        is_valid: false,
+        message: String::new(),
+        receipt_count: input.receipts.len() as u32,
+        merkle_root_hex: String::new(),
+    };
+    if input.receipts.is_empty() {
+        output.message = String::from("No receipts to aggregate");
+        return output;
+    }
+
+    let leaves: Vec<[u8; 32]> = input.receipts.iter().map(receipt_leaf).collect();
+    output.merkle_root_hex = hex::encode(root_of_leaves(leaves));
+    output.is_valid = true;
+    output.message = format!("✅ Aggregated {} receipts", output.receipt_count);
+    output
+}
@@ -0,0 +1,80 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use hex::decode as hex_decode;
+use rand_chacha::ChaCha20Rng;
+use zeroize::Zeroizing;
+
+use crate::ecies::{self, ECIES_PUBKEY_LEN};
+use crate::rsa::{rsa_wrap, wrap_rng};
+use crate::types::{
+    BatchEncryptAesKeyInput, BatchEncryptAesKeyOutput, BatchRecipient, OaepParams, WrappedAesKey,
+};
+use crate::utils::sha256_hex;
+
+/// Wraps `aes_key` to one buyer key, RSA recipients with `oaep` and an OAEP
+/// seed drawn from `rng`.
+pub fn wrap(
+    recipient: &BatchRecipient,
+    aes_key: &[u8],
+    oaep: &OaepParams,
+    rng: &mut ChaCha20Rng,
+) -> Result<WrappedAesKey, &'static str> {
+    let (wrapped, pubkey_sha256_hex) = match recipient {
+        BatchRecipient::Rsa(pubkey_base64) => rsa_wrap(pubkey_base64, aes_key, oaep, rng)?,
+        BatchRecipient::Ecies(pubkey_hex) => {
+            let pubkey: [u8; ECIES_PUBKEY_LEN] = hex_decode(pubkey_hex)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or("Invalid X25519 pubkey hex")?;
+            (ecies::seal(aes_key, &pubkey)?, sha256_hex(&pubkey))
+        }
+    };
+    Ok(WrappedAesKey { pubkey_sha256_hex, enc_aes_key_hex: hex::encode(wrapped) })
+}
+
+/// Wraps one AES key to every buyer key in `input.recipients` (RSA-OAEP or
+/// ECIES), so a licence sold to many buyers needs a single proof. The journal
+/// commits the AES key once, as its SHA-256, plus one wrapped key per buyer.
+pub fn batch_encrypt(input: BatchEncryptAesKeyInput) -> BatchEncryptAesKeyOutput {
+    let mut output = BatchEncryptAesKeyOutput {
+        is_valid: false,
+        message: String::new(),
+        aes_key_sha256_hex: String::new(),
+        wrapped: Vec::new(),
+        oaep: input.oaep.clone(),
+    };
+
+    let aes_key_bytes = match hex_decode(input.aes_key_hex.expose_secret()) {
+        Ok(bytes) => Zeroizing::new(bytes),
+        Err(_) => {
+            output.message = String::from("Invalid AES key hex");
+            return output;
+        }
+    };
+    output.aes_key_sha256_hex = sha256_hex(&aes_key_bytes);
+
+    let mut rng = match wrap_rng(input.wrap_seed_hex.expose_secret()) {
+        Ok(rng) => rng,
+        Err(err) => {
+            output.message = String::from(err);
+            return output;
+        }
+    };
+
+    let mut wrapped = Vec::with_capacity(input.recipients.len());
+    for (i, recipient) in input.recipients.iter().enumerate() {
+        match wrap(recipient, &aes_key_bytes, &input.oaep, &mut rng) {
+            Ok(key) => wrapped.push(key),
+            Err(err) => {
+                output.message = format!("Recipient {}: {}", i, err);
+                return output;
+            }
+        }
+    }
+
+    output.is_valid = true;
+    output.message = format!("✅ AES key wrapped for {} recipients", wrapped.len());
+    output.wrapped = wrapped;
+    output
+}
@@ -0,0 +1,159 @@
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, XChaCha20Poly1305};
+use hex::decode;
+use alloc::string::String;
+use alloc::vec::Vec;
+use zeroize::Zeroizing;
+
+use crate::ipfs::ipfs_cid;
+use crate::types::{
+    ChaChaDecryptionProofInput, ChaChaDecryptionProofOutput, ChaChaVariant, ProofOutput,
+};
+use crate::utils::sha256_hex;
+
+/// ChaCha20-Poly1305 nonce length in bytes
+pub const CHACHA_NONCE_LEN: usize = 12;
+
+/// XChaCha20-Poly1305 nonce length in bytes
+pub const XCHACHA_NONCE_LEN: usize = 24;
+
+/// Poly1305 tag length in bytes
+pub const CHACHA_TAG_LEN: usize = 16;
+
+/// Encrypts `plaintext` with the selected ChaCha20-Poly1305 variant and returns
+/// `ciphertext || tag`.
+pub fn chacha_encrypt(
+    variant: ChaChaVariant,
+    key: &[u8],
+    nonce: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, &'static str> {
+    if nonce.len() != variant.nonce_len() {
+        return Err("Invalid nonce length");
+    }
+    let payload = Payload { msg: plaintext, aad };
+    match variant {
+        ChaChaVariant::ChaCha20Poly1305 => ChaCha20Poly1305::new_from_slice(key)
+            .map_err(|_| "Invalid key length")?
+            .encrypt(nonce.into(), payload),
+        ChaChaVariant::XChaCha20Poly1305 => XChaCha20Poly1305::new_from_slice(key)
+            .map_err(|_| "Invalid key length")?
+            .encrypt(nonce.into(), payload),
+    }
+    .map_err(|_| "Encryption failed")
+}
+
+/// Proves that `ciphertext_hex` (ciphertext || tag) is the (X)ChaCha20-Poly1305
+/// encryption of the plaintext. Counterpart of `aes_ctr_verify` for sellers who
+/// prefer a cipher that is cheap to run in the guest (no AES hardware in RISC-V).
+///
+/// As in `aes_gcm_verify`, the output commits the variant, nonce and AAD in
+/// clear and SHA-256 commitments to the key, plaintext and ciphertext, plus the
+/// IPFS CID of the ciphertext.
+pub fn chacha_verify(input: ChaChaDecryptionProofInput) -> ChaChaDecryptionProofOutput {
+    let mut output = ChaChaDecryptionProofOutput {
+        is_valid: false,
+        message: String::new(),
+        cipher: input.cipher,
+        nonce_hex: input.nonce_hex.clone(),
+        aad_hex: input.aad_hex.clone(),
+        key_sha256_hex: String::new(),
+        plaintext_sha256_hex: String::new(),
+        ciphertext_sha256_hex: String::new(),
+        ciphertext_cid: String::new(),
+    };
+
+    let key = match decode(input.key_hex.expose_secret()) {
+        Ok(k) => Zeroizing::new(k),
+        Err(_) => return output.fail("Invalid key hex"),
+    };
+
+    let nonce = match decode(&input.nonce_hex) {
+        Ok(n) => n,
+        Err(_) => return output.fail("Invalid nonce hex"),
+    };
+
+    let aad = match input.aad_hex.as_deref().map(decode).transpose() {
+        Ok(aad) => aad.unwrap_or_default(),
+        Err(_) => return output.fail("Invalid AAD hex"),
+    };
+
+    let ciphertext_expected = match decode(&input.ciphertext_hex) {
+        Ok(c) => c,
+        Err(_) => return output.fail("Invalid ciphertext hex"),
+    };
+
+    let plaintext_bytes = input.plaintext_utf8.expose_secret().as_bytes();
+    let ciphertext = match chacha_encrypt(input.cipher, &key, &nonce, plaintext_bytes, &aad) {
+        Ok(c) => c,
+        Err(err) => return output.fail(err),
+    };
+
+    output.key_sha256_hex = sha256_hex(&key);
+    output.plaintext_sha256_hex = sha256_hex(plaintext_bytes);
+    output.ciphertext_sha256_hex = sha256_hex(&ciphertext_expected);
+    output.ciphertext_cid = ipfs_cid(&ciphertext_expected);
+
+    // Compare ciphertexts (including the tag)
+    if ciphertext == ciphertext_expected {
+        output.is_valid = true;
+        output.message = String::from("✅ Ciphertext and tag match ChaCha20-Poly1305 encryption");
+    } else {
+        output.message = String::from("❌ Ciphertext or tag mismatch");
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SecretString;
+
+    const KEY_HEX: &str = "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d";
+
+    fn input(cipher: ChaChaVariant, nonce_hex: &str, ciphertext_hex: String) -> ChaChaDecryptionProofInput {
+        ChaChaDecryptionProofInput {
+            cipher,
+            key_hex: SecretString::from(KEY_HEX),
+            nonce_hex: String::from(nonce_hex),
+            plaintext_utf8: SecretString::from("example fileeee ! "),
+            ciphertext_hex,
+            aad_hex: None,
+        }
+    }
+
+    #[test]
+    fn verifies_the_handler_example_and_commits_it() {
+        let ciphertext_hex = "894ddbc2b6927493b44b17580b8b5c0410e17e4cbb13df0beef98b1ded1b01ce4be8";
+        let nonce_hex = "010101010101010101010101";
+        let output = chacha_verify(input(ChaChaVariant::ChaCha20Poly1305, nonce_hex, String::from(ciphertext_hex)));
+        assert!(output.is_valid, "{}", output.message);
+        assert_eq!(output.cipher, ChaChaVariant::ChaCha20Poly1305);
+        assert_eq!(output.nonce_hex, nonce_hex);
+        assert_eq!(output.key_sha256_hex, sha256_hex(&decode(KEY_HEX).unwrap()));
+        assert_eq!(output.plaintext_sha256_hex, sha256_hex(b"example fileeee ! "));
+        let ciphertext = decode(ciphertext_hex).unwrap();
+        assert_eq!(output.ciphertext_sha256_hex, sha256_hex(&ciphertext));
+        assert_eq!(output.ciphertext_cid, ipfs_cid(&ciphertext));
+    }
+
+    #[test]
+    fn verifies_xchacha_and_rejects_tampered_tags() {
+        let nonce = [7u8; XCHACHA_NONCE_LEN];
+        let key = decode(KEY_HEX).unwrap();
+        let mut ciphertext =
+            chacha_encrypt(ChaChaVariant::XChaCha20Poly1305, &key, &nonce, b"example fileeee ! ", &[]).unwrap();
+        let nonce_hex = hex::encode(nonce);
+        let output = chacha_verify(input(ChaChaVariant::XChaCha20Poly1305, &nonce_hex, hex::encode(&ciphertext)));
+        assert!(output.is_valid, "{}", output.message);
+
+        // Same bytes under the other variant, and a flipped tag bit
+        let output = chacha_verify(input(ChaChaVariant::ChaCha20Poly1305, &nonce_hex, hex::encode(&ciphertext)));
+        assert_eq!(output.message, "Invalid nonce length");
+        *ciphertext.last_mut().unwrap() ^= 1;
+        let output = chacha_verify(input(ChaChaVariant::XChaCha20Poly1305, &nonce_hex, hex::encode(&ciphertext)));
+        assert!(!output.is_valid);
+        assert_eq!(output.ciphertext_sha256_hex, sha256_hex(&ciphertext));
+    }
+}
@@ -0,0 +1,262 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+use ruzstd::frame::ReadFrameHeaderError;
+use ruzstd::frame_decoder::FrameDecoderError;
+use ruzstd::io::Read;
+use ruzstd::{FrameDecoder, StreamingDecoder};
+
+use crate::types::Compression;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const GZIP_DEFLATE: u8 = 8;
+const GZIP_TRAILER_LEN: usize = 8;
+const GZIP_FHCRC: u8 = 0x02;
+const GZIP_FEXTRA: u8 = 0x04;
+const GZIP_FNAME: u8 = 0x08;
+const GZIP_FCOMMENT: u8 = 0x10;
+
+/// Bytes decoded per read of a zstd frame
+const ZSTD_READ_CHUNK: usize = 4096;
+
+/// Lookup table of the gzip CRC-32 (reflected polynomial 0xEDB88320)
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-32 of `data` as stored in the gzip trailer (RFC 1952, section 8).
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &b| CRC32_TABLE[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8))
+}
+
+/// Decompresses `data` with `compression`, failing if the output would exceed
+/// `limit` bytes. `Compression::None` returns the data unchanged.
+pub fn decompress(compression: Compression, data: &[u8], limit: usize) -> Result<Vec<u8>, String> {
+    match compression {
+        Compression::None => Ok(data.to_vec()),
+        Compression::Gzip => gunzip(data, limit),
+        Compression::Zstd => unzstd(data, limit),
+    }
+}
+
+/// Decompresses the zstd frames in `data`, skipping skippable frames. Each
+/// frame is read through a `StreamingDecoder` with at most `limit + 1` bytes
+/// of output requested overall, so the buffer grows with the output and a
+/// stream over `limit` fails without being decoded further.
+fn unzstd(data: &[u8], limit: usize) -> Result<Vec<u8>, String> {
+    let mut input = data;
+    let mut decoder = FrameDecoder::new();
+    let mut output = Vec::new();
+    let mut chunk = [0u8; ZSTD_READ_CHUNK];
+    while !input.is_empty() {
+        let mut frame = match StreamingDecoder::new_with_decoder(&mut input, &mut decoder) {
+            Ok(frame) => frame,
+            Err(FrameDecoderError::ReadFrameHeaderError(ReadFrameHeaderError::SkipFrame {
+                length,
+                ..
+            })) => {
+                input = input
+                    .get(length as usize..)
+                    .ok_or("Invalid zstd stream: skippable frame is truncated")?;
+                continue;
+            }
+            Err(err) => return Err(format!("Invalid zstd stream: {}", err)),
+        };
+        loop {
+            let wanted = chunk.len().min(limit + 1 - output.len());
+            let read = frame
+                .read(&mut chunk[..wanted])
+                .map_err(|err| format!("Invalid zstd stream: {}", err))?;
+            if read == 0 {
+                break;
+            }
+            output.extend_from_slice(&chunk[..read]);
+            if output.len() > limit {
+                return Err(format!("Invalid zstd stream: decompresses to more than {} bytes", limit));
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// Decompresses a single-member gzip stream (RFC 1952). The header is parsed
+/// by hand (checking its CRC-16 if present), the body inflated with
+/// miniz_oxide and the trailer CRC-32 and length checked against the output.
+fn gunzip(data: &[u8], limit: usize) -> Result<Vec<u8>, String> {
+    let truncated = || String::from("Invalid gzip stream: truncated header");
+    if data.len() < 10 + GZIP_TRAILER_LEN || data[..2] != GZIP_MAGIC {
+        return Err(String::from("Invalid gzip stream: bad magic"));
+    }
+    if data[2] != GZIP_DEFLATE {
+        return Err(String::from("Invalid gzip stream: compression method is not deflate"));
+    }
+    let flags = data[3];
+    let mut offset = 10;
+    if flags & GZIP_FEXTRA != 0 {
+        let extra = data.get(offset..offset + 2).ok_or_else(truncated)?;
+        offset += 2 + u16::from_le_bytes([extra[0], extra[1]]) as usize;
+    }
+    for flag in [GZIP_FNAME, GZIP_FCOMMENT] {
+        if flags & flag != 0 {
+            let len = data.get(offset..).and_then(|rest| rest.iter().position(|&b| b == 0));
+            offset += len.ok_or_else(truncated)? + 1;
+        }
+    }
+    if flags & GZIP_FHCRC != 0 {
+        // Low 16 bits of the CRC-32 of the header up to here
+        let stored = data.get(offset..offset + 2).ok_or_else(truncated)?;
+        if u16::from_le_bytes([stored[0], stored[1]]) != crc32(&data[..offset]) as u16 {
+            return Err(String::from("Invalid gzip stream: header CRC-16 does not match"));
+        }
+        offset += 2;
+    }
+    let body_end = data.len() - GZIP_TRAILER_LEN;
+    let body = data.get(offset..body_end).ok_or_else(truncated)?;
+    let output = decompress_to_vec_with_limit(body, limit)
+        .map_err(|_| String::from("Invalid gzip stream: deflate data is corrupt or too large"))?;
+
+    let trailer = &data[body_end..];
+    let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    if crc != crc32(&output) {
+        return Err(String::from("Invalid gzip stream: CRC-32 does not match the trailer"));
+    }
+    let isize = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+    if isize != output.len() as u32 {
+        return Err(String::from("Invalid gzip stream: length does not match the trailer"));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::MAX_INFLATED_LEN;
+
+    // Written by GNU gzip 1.12 and zstd 1.5.7; see testdata/README.md
+    const RECORDS: &[u8] = include_bytes!("../testdata/records.csv");
+    const GZIP: &[u8] = include_bytes!("../testdata/records.csv.gz");
+    const GZIP_NO_NAME: &[u8] = include_bytes!("../testdata/records-noname.csv.gz");
+    const GZIP_BOMB: &[u8] = include_bytes!("../testdata/zeros-16mib-plus-1.gz");
+    const ZSTD: &[u8] = include_bytes!("../testdata/records.csv.zst");
+    const ZSTD_TWO_FRAMES: &[u8] = include_bytes!("../testdata/records-two-frames.csv.zst");
+    const ZSTD_BOMB: &[u8] = include_bytes!("../testdata/zeros-16mib-plus-1.zst");
+
+    fn gunzip_err(data: &[u8]) -> String {
+        decompress(Compression::Gzip, data, MAX_INFLATED_LEN).unwrap_err()
+    }
+
+    /// `GZIP_NO_NAME` with `flags` set and `fields` inserted after the fixed header
+    fn with_header_fields(flags: u8, fields: &[u8]) -> Vec<u8> {
+        let mut data = GZIP_NO_NAME[..10].to_vec();
+        data[3] |= flags;
+        data.extend_from_slice(fields);
+        data.extend_from_slice(&GZIP_NO_NAME[10..]);
+        data
+    }
+
+    #[test]
+    fn inflates_gzip_streams() {
+        assert_eq!(GZIP[3], GZIP_FNAME);
+        for data in [GZIP, GZIP_NO_NAME] {
+            assert_eq!(decompress(Compression::Gzip, data, MAX_INFLATED_LEN).unwrap(), RECORDS);
+        }
+        assert_eq!(decompress(Compression::None, RECORDS, 0).unwrap(), RECORDS);
+    }
+
+    #[test]
+    fn skips_optional_gzip_header_fields() {
+        let extra = with_header_fields(GZIP_FEXTRA, b"\x06\x00AP\x02\x00hi");
+        let comment = with_header_fields(GZIP_FNAME | GZIP_FCOMMENT, b"records.csv\0from the seller\0");
+        for data in [extra, comment] {
+            assert_eq!(decompress(Compression::Gzip, &data, MAX_INFLATED_LEN).unwrap(), RECORDS);
+        }
+
+        let mut with_crc = with_header_fields(GZIP_FNAME | GZIP_FHCRC, b"records.csv\0");
+        let header_crc = (crc32(&with_crc[..22]) as u16).to_le_bytes();
+        with_crc.splice(22..22, header_crc);
+        assert_eq!(decompress(Compression::Gzip, &with_crc, MAX_INFLATED_LEN).unwrap(), RECORDS);
+        with_crc[22] ^= 1;
+        assert_eq!(gunzip_err(&with_crc), "Invalid gzip stream: header CRC-16 does not match");
+    }
+
+    #[test]
+    fn rejects_gzip_trailer_mismatches() {
+        let mut bad_crc = GZIP.to_vec();
+        let trailer = bad_crc.len() - GZIP_TRAILER_LEN;
+        bad_crc[trailer] ^= 1;
+        assert_eq!(gunzip_err(&bad_crc), "Invalid gzip stream: CRC-32 does not match the trailer");
+
+        let mut bad_isize = GZIP.to_vec();
+        bad_isize[trailer + 4] ^= 1;
+        assert_eq!(gunzip_err(&bad_isize), "Invalid gzip stream: length does not match the trailer");
+    }
+
+    #[test]
+    fn rejects_malformed_gzip_headers() {
+        // Cut inside the file name, before its NUL terminator
+        assert_eq!(gunzip_err(&GZIP[..20]), "Invalid gzip stream: truncated header");
+        assert_eq!(
+            gunzip_err(&with_header_fields(GZIP_FEXTRA, b"\xff\xff")),
+            "Invalid gzip stream: truncated header"
+        );
+        assert_eq!(gunzip_err(&ZSTD[..20]), "Invalid gzip stream: bad magic");
+        let mut stored = GZIP.to_vec();
+        stored[2] = 0;
+        assert_eq!(gunzip_err(&stored), "Invalid gzip stream: compression method is not deflate");
+        let mut corrupt = GZIP_NO_NAME.to_vec();
+        corrupt[10] = 0xff;
+        assert_eq!(gunzip_err(&corrupt), "Invalid gzip stream: deflate data is corrupt or too large");
+    }
+
+    #[test]
+    fn stops_gzip_streams_over_the_limit() {
+        assert_eq!(gunzip_err(GZIP_BOMB), "Invalid gzip stream: deflate data is corrupt or too large");
+        assert_eq!(decompress(Compression::Gzip, GZIP, RECORDS.len()).unwrap(), RECORDS);
+        assert!(decompress(Compression::Gzip, GZIP, RECORDS.len() - 1).is_err());
+    }
+
+    #[test]
+    fn decodes_zstd_frames() {
+        for data in [ZSTD, ZSTD_TWO_FRAMES] {
+            let output = decompress(Compression::Zstd, data, MAX_INFLATED_LEN).unwrap();
+            assert_eq!(output, RECORDS);
+            // Nothing is reserved up to the limit
+            assert!(output.capacity() < MAX_INFLATED_LEN / 2);
+        }
+
+        // A skippable frame (magic 0x184d2a50, 4-byte length) before the data
+        let mut skippable = Vec::from(&[0x50, 0x2a, 0x4d, 0x18, 3, 0, 0, 0, 1, 2, 3][..]);
+        skippable.extend_from_slice(ZSTD);
+        assert_eq!(decompress(Compression::Zstd, &skippable, MAX_INFLATED_LEN).unwrap(), RECORDS);
+    }
+
+    #[test]
+    fn rejects_malformed_zstd_streams() {
+        for data in [&ZSTD[..ZSTD.len() - 10], &ZSTD[4..], GZIP] {
+            let err = decompress(Compression::Zstd, data, MAX_INFLATED_LEN).unwrap_err();
+            assert!(err.starts_with("Invalid zstd stream: "), "{}", err);
+        }
+    }
+
+    #[test]
+    fn stops_zstd_streams_over_the_limit() {
+        let over = format!("Invalid zstd stream: decompresses to more than {} bytes", MAX_INFLATED_LEN);
+        assert_eq!(decompress(Compression::Zstd, ZSTD_BOMB, MAX_INFLATED_LEN).unwrap_err(), over);
+        assert_eq!(decompress(Compression::Zstd, ZSTD, RECORDS.len()).unwrap(), RECORDS);
+        let over = format!("Invalid zstd stream: decompresses to more than {} bytes", RECORDS.len() - 1);
+        assert_eq!(decompress(Compression::Zstd, ZSTD_TWO_FRAMES, RECORDS.len() - 1).unwrap_err(), over);
+    }
+}
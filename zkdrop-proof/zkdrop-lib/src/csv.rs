@@ -0,0 +1,335 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::iter::Peekable;
+use core::str::Chars;
+use hex::decode;
+use zeroize::Zeroizing;
+
+use crate::aes_ctr::aes_ctr_verify;
+use crate::types::{
+    CsvColumnStats, CsvColumnType, CsvPropertiesInput, CsvPropertiesOutput, CsvSchema, ProofOutput,
+};
+use crate::utils::sha256_hex;
+
+/// Records of an RFC 4180 CSV document: fields separated by `delimiter`,
+/// records by LF or CRLF, fields optionally quoted with `"` (`""` escapes a
+/// quote). A leading UTF-8 byte order mark is dropped, and blank lines
+/// (including trailing ones) are skipped rather than read as empty records.
+pub struct CsvRecords<'a> {
+    chars: Peekable<Chars<'a>>,
+    delimiter: char,
+}
+
+impl<'a> CsvRecords<'a> {
+    pub fn new(data: &'a str, delimiter: char) -> Self {
+        let data = data.strip_prefix('\u{feff}').unwrap_or(data);
+        Self { chars: data.chars().peekable(), delimiter }
+    }
+
+    /// Consumes line breaks up to the next record; `None` at the end of the data.
+    fn skip_blank_lines(&mut self) -> Option<()> {
+        loop {
+            let mut rest = self.chars.clone();
+            match rest.next()? {
+                '\n' => {}
+                '\r' if rest.next() == Some('\n') => {}
+                _ => return Some(()),
+            }
+            self.chars = rest;
+        }
+    }
+}
+
+impl Iterator for CsvRecords<'_> {
+    type Item = Result<Vec<String>, &'static str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.skip_blank_lines()?;
+
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut field_start = true;
+        loop {
+            let Some(c) = self.chars.next() else {
+                if quoted {
+                    return Some(Err("unterminated quoted field"));
+                }
+                break;
+            };
+            if quoted {
+                if c != '"' {
+                    field.push(c);
+                } else if self.chars.peek() == Some(&'"') {
+                    self.chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                    match self.chars.peek() {
+                        None | Some('\r') | Some('\n') => {}
+                        Some(&next) if next == self.delimiter => {}
+                        _ => return Some(Err("unexpected character after closing quote")),
+                    }
+                }
+                continue;
+            }
+            match c {
+                '"' if field_start => {
+                    quoted = true;
+                    field_start = false;
+                }
+                '"' => return Some(Err("quote inside unquoted field")),
+                '\n' => break,
+                '\r' if self.chars.peek() == Some(&'\n') => {
+                    self.chars.next();
+                    break;
+                }
+                c if c == self.delimiter => {
+                    fields.push(core::mem::take(&mut field));
+                    field_start = true;
+                }
+                c => {
+                    field.push(c);
+                    field_start = false;
+                }
+            }
+        }
+        fields.push(field);
+        Some(Ok(fields))
+    }
+}
+
+/// Running statistics of one column.
+#[derive(Default)]
+struct ColumnAccumulator {
+    null_count: u32,
+    count: u32,
+    min: f64,
+    max: f64,
+    sum: f64,
+}
+
+impl ColumnAccumulator {
+    /// Checks `value` against `kind`; empty fields are nulls.
+    fn add(&mut self, kind: CsvColumnType, value: &str) -> Result<(), &'static str> {
+        if value.is_empty() {
+            self.null_count += 1;
+            return Ok(());
+        }
+        let number = match kind {
+            CsvColumnType::String => return Ok(()),
+            CsvColumnType::Boolean => {
+                return match value {
+                    "true" | "false" => Ok(()),
+                    _ => Err("is not a boolean"),
+                }
+            }
+            CsvColumnType::Integer => value.parse::<i64>().map_err(|_| "is not an integer")? as f64,
+            CsvColumnType::Number => match value.parse::<f64>() {
+                Ok(n) if n.is_finite() => n,
+                _ => return Err("is not a finite number"),
+            },
+        };
+        if self.count == 0 || number < self.min {
+            self.min = number;
+        }
+        if self.count == 0 || number > self.max {
+            self.max = number;
+        }
+        self.sum += number;
+        self.count += 1;
+        Ok(())
+    }
+
+    fn into_stats(self, name: &str, kind: CsvColumnType) -> CsvColumnStats {
+        let numeric =
+            matches!(kind, CsvColumnType::Integer | CsvColumnType::Number) && self.count > 0;
+        CsvColumnStats {
+            name: String::from(name),
+            kind,
+            null_count: self.null_count,
+            min: numeric.then_some(self.min),
+            max: numeric.then_some(self.max),
+            mean: numeric.then(|| self.sum / self.count as f64),
+        }
+    }
+}
+
+/// Parses `data` as CSV, checks the header and every value against `schema`
+/// and returns the number of data rows with per-column statistics.
+pub fn csv_stats(data: &str, schema: &CsvSchema) -> Result<(u32, Vec<CsvColumnStats>), String> {
+    let mut records = CsvRecords::new(data, schema.delimiter);
+    let header = match records.next() {
+        Some(header) => header.map_err(|err| format!("Header: {}", err))?,
+        None => return Err(String::from("CSV is empty")),
+    };
+    let declared: Vec<&str> = schema.columns.iter().map(|c| c.name.as_str()).collect();
+    if header != declared {
+        return Err(String::from("Header does not match the declared columns"));
+    }
+
+    let mut columns: Vec<ColumnAccumulator> =
+        schema.columns.iter().map(|_| Default::default()).collect();
+    let mut row_count: u32 = 0;
+    for record in records {
+        row_count += 1;
+        let record = record.map_err(|err| format!("Row {}: {}", row_count, err))?;
+        if record.len() != columns.len() {
+            return Err(format!(
+                "Row {}: expected {} fields, got {}",
+                row_count,
+                columns.len(),
+                record.len()
+            ));
+        }
+        for ((value, column), acc) in record.iter().zip(&schema.columns).zip(&mut columns) {
+            acc.add(column.kind, value)
+                .map_err(|err| format!("Row {}: column '{}' {}", row_count, column.name, err))?;
+        }
+    }
+
+    let stats = columns
+        .into_iter()
+        .zip(&schema.columns)
+        .map(|(acc, column)| acc.into_stats(&column.name, column.kind))
+        .collect();
+    Ok((row_count, stats))
+}
+
+/// Proves properties of an AES-CTR encrypted CSV dataset: the ciphertext is
+/// the encryption of the plaintext (as in `aes_ctr_verify`), the plaintext
+/// conforms to the declared schema, and the committed row count and column
+/// statistics are computed from it. The output commits the same
+/// `ciphertext_sha256_hex` and `key_sha256_hex` as the other dataset proofs.
+pub fn csv_prove(input: CsvPropertiesInput) -> CsvPropertiesOutput {
+    let mut output = CsvPropertiesOutput {
+        is_valid: false,
+        message: String::new(),
+        iv_hex: input.decryption.iv_hex.clone(),
+        key_sha256_hex: String::new(),
+        ciphertext_sha256_hex: String::new(),
+        compression: input.decryption.compression,
+        delimiter: input.schema.delimiter,
+        row_count: 0,
+        columns: Vec::new(),
+    };
+
+    match decode(input.decryption.aes_key_hex.expose_secret()) {
+        Ok(key) => output.key_sha256_hex = sha256_hex(&Zeroizing::new(key)),
+        Err(_) => return output.fail("Invalid AES key hex"),
+    }
+    match decode(&input.decryption.ciphertext_hex) {
+        Ok(ciphertext) => output.ciphertext_sha256_hex = sha256_hex(&ciphertext),
+        Err(_) => return output.fail("Invalid ciphertext hex"),
+    }
+    let stats = csv_stats(input.decryption.plaintext_utf8.expose_secret(), &input.schema);
+
+    let (is_valid, message) = aes_ctr_verify(input.decryption);
+    if !is_valid {
+        return output.fail(&message);
+    }
+    match stats {
+        Ok((row_count, columns)) => {
+            output.is_valid = true;
+            output.message = String::from("✅ CSV matches the declared schema");
+            output.row_count = row_count;
+            output.columns = columns;
+        }
+        Err(err) => output.message = format!("❌ {}", err),
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CsvColumn;
+
+    fn records(data: &str) -> Vec<Result<Vec<String>, &'static str>> {
+        CsvRecords::new(data, ',').collect()
+    }
+
+    fn row(fields: &[&str]) -> Result<Vec<String>, &'static str> {
+        Ok(fields.iter().map(|f| String::from(*f)).collect())
+    }
+
+    fn csv_schema(columns: &[(&str, CsvColumnType)]) -> CsvSchema {
+        CsvSchema {
+            delimiter: ',',
+            columns: columns
+                .iter()
+                .map(|(name, kind)| CsvColumn { name: String::from(*name), kind: *kind })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn reads_quoted_fields() {
+        assert_eq!(
+            records("\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\",\"\"\n"),
+            [row(&["a,b", "say \"hi\"", "two\nlines", ""])]
+        );
+        assert_eq!(records("\"unterminated\n"), [Err("unterminated quoted field")]);
+        assert_eq!(records("\"a\"b\n")[0], Err("unexpected character after closing quote"));
+        assert_eq!(records("a\"b\n")[0], Err("quote inside unquoted field"));
+    }
+
+    #[test]
+    fn drops_a_byte_order_mark_and_blank_lines() {
+        assert_eq!(records("\u{feff}id,name\r\n\r\n1,x\n\n\n2,\r\n\n"), [
+            row(&["id", "name"]),
+            row(&["1", "x"]),
+            row(&["2", ""]),
+        ]);
+        // Only a leading BOM is dropped; a lone CR is data
+        assert_eq!(records("a\u{feff},b\rc"), [row(&["a\u{feff}", "b\rc"])]);
+        assert_eq!(records("\n\r\n"), []);
+    }
+
+    #[test]
+    fn honours_the_delimiter() {
+        let records: Vec<_> = CsvRecords::new("a;\"b;c\";d,e\n", ';').collect();
+        assert_eq!(records, [row(&["a", "b;c", "d,e"])]);
+    }
+
+    #[test]
+    fn computes_row_count_nulls_and_numeric_stats() {
+        let schema = csv_schema(&[
+            ("id", CsvColumnType::Integer),
+            ("score", CsvColumnType::Number),
+            ("ok", CsvColumnType::Boolean),
+            ("name", CsvColumnType::String),
+        ]);
+        let (rows, stats) =
+            csv_stats("id,score,ok,name\n1,2.5,true,a\n3,,false,\n\n-4,0.5,,\"c, d\"\n", &schema).unwrap();
+        assert_eq!(rows, 3);
+        assert_eq!((stats[0].min, stats[0].max, stats[0].mean), (Some(-4.0), Some(3.0), Some(0.0)));
+        assert_eq!((stats[1].null_count, stats[1].min, stats[1].max, stats[1].mean), (1, Some(0.5), Some(2.5), Some(1.5)));
+        assert_eq!((stats[2].null_count, stats[2].mean), (1, None));
+        assert_eq!((stats[3].null_count, stats[3].min), (1, None));
+
+        // A numeric column of nulls only has no statistics
+        let ids = csv_schema(&[("id", CsvColumnType::Integer)]);
+        let (_, stats) = csv_stats("id\n\"\"\n", &ids).unwrap();
+        assert_eq!((stats[0].null_count, stats[0].min), (1, None));
+    }
+
+    #[test]
+    fn rejects_data_that_breaks_the_schema() {
+        let schema = csv_schema(&[("id", CsvColumnType::Integer), ("ok", CsvColumnType::Boolean)]);
+        let cases = [
+            ("", "CSV is empty"),
+            ("id,flag\n1,true\n", "Header does not match the declared columns"),
+            ("id,ok\n1,true,x\n", "Row 1: expected 2 fields, got 3"),
+            ("id,ok\n1,true\n1.5,false\n", "Row 2: column 'id' is not an integer"),
+            ("id,ok\n1,yes\n", "Row 1: column 'ok' is not a boolean"),
+            ("id,ok\n1,\"x\n", "Row 1: unterminated quoted field"),
+        ];
+        for (data, message) in cases {
+            assert_eq!(csv_stats(data, &schema).unwrap_err(), message, "{:?}", data);
+        }
+        let numbers = csv_schema(&[("n", CsvColumnType::Number)]);
+        assert_eq!(csv_stats("n\ninf\n", &numbers).unwrap_err(), "Row 1: column 'n' is not a finite number");
+    }
+}
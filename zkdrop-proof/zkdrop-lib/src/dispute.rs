@@ -0,0 +1,223 @@
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use alloc::string::String;
+use alloc::vec::Vec;
+use base64::{engine::general_purpose, Engine as _};
+use hex::decode;
+use rsa::{pkcs8::DecodePrivateKey, RsaPrivateKey};
+use zeroize::Zeroizing;
+
+use crate::aes_ctr::aes_ctr_encrypt;
+use crate::aes_gcm::AES_GCM_NONCE_LEN;
+use crate::hashlock::key_hash_hex;
+use crate::rsa::{oaep_decrypt, rsa_pubkey_sha256_hex};
+use crate::types::{
+    CommittedCiphertext, DatasetCipher, DisputeProofInput, DisputeProofOutput, ProofOutput,
+};
+use crate::utils::sha256_hex;
+use crate::validation::{AES_CTR_IV_LEN, AES_KEY_LEN};
+
+/// Proves seller misbehaviour for a buyer dispute.
+///
+/// Unwraps the on-chain `encryptedSecret` with the buyer's RSA private key and
+/// checks the result against the seller's published commitments. Misbehaviour
+/// is any of: the secret is not valid hex, does not decrypt under the buyer key,
+/// is not an AES-256 key, does not hash to the committed key hash, fails the
+/// AES-GCM tag of the committed ciphertext, or decrypts it to a plaintext whose
+/// hash differs from the committed one. The delivered key is never committed.
+///
+/// "Does not decrypt" only holds under the OAEP parameters the seller wrapped
+/// with, so `oaep` is committed and the arbiter must match it against the
+/// seller's wrapping journal.
+pub fn dispute_prove(input: DisputeProofInput) -> DisputeProofOutput {
+    let mut output = DisputeProofOutput {
+        is_misbehaviour: false,
+        message: String::new(),
+        rsa_pubkey_sha256_hex: String::new(),
+        encrypted_secret_hex: input.encrypted_secret_hex.clone(),
+        oaep: input.oaep.clone(),
+        key_commitment: input.key_commitment.clone(),
+        cipher: input.ciphertext.as_ref().map(|c| c.cipher.clone()),
+        ciphertext_sha256_hex: None,
+        plaintext_sha256_hex: input.ciphertext.as_ref().and_then(|c| c.plaintext_sha256_hex.clone()),
+    };
+
+    let private_key = match general_purpose::STANDARD
+        .decode(input.rsa_private_key_base64.expose_secret())
+        .map(Zeroizing::new)
+    {
+        Ok(der) => match RsaPrivateKey::from_pkcs8_der(&der) {
+            Ok(key) => key,
+            Err(_) => return output.fail("RSA private key parse error"),
+        },
+        Err(_) => return output.fail("Invalid RSA private key base64"),
+    };
+    output.rsa_pubkey_sha256_hex = match rsa_pubkey_sha256_hex(&private_key.to_public_key()) {
+        Some(hash) => hash,
+        None => return output.fail("RSA pubkey encoding error"),
+    };
+    if let Err(err) = input.oaep.label() {
+        return output.fail(err);
+    }
+
+    let committed = match input.ciphertext.as_ref().map(DecodedCiphertext::decode).transpose() {
+        Ok(committed) => committed,
+        Err(err) => return output.fail(err),
+    };
+    output.ciphertext_sha256_hex = committed.as_ref().map(|c| sha256_hex(&c.ciphertext));
+
+    // From here on every failure is the seller's: the inputs are well-formed.
+    let encrypted_secret = match decode(&input.encrypted_secret_hex) {
+        Ok(bytes) => bytes,
+        Err(_) => return output.misbehaviour("❌ encryptedSecret is not valid hex"),
+    };
+    // Only the seller's fault under their own OAEP parameters; see `DisputeProofOutput`
+    let key = match oaep_decrypt(&private_key, &input.oaep, &encrypted_secret) {
+        Ok(key) => key,
+        Err(_) => return output.misbehaviour("❌ encryptedSecret does not decrypt under the buyer key"),
+    };
+    if key.len() != AES_KEY_LEN {
+        return output.misbehaviour("❌ encryptedSecret does not hold an AES-256 key");
+    }
+
+    if let Some(commitment) = &input.key_commitment {
+        if !key_hash_hex(commitment.digest, &key).eq_ignore_ascii_case(&commitment.key_hash_hex) {
+            return output.misbehaviour("❌ Delivered key does not match the committed key hash");
+        }
+    }
+    if let Some(committed) = &committed {
+        if let Err(message) = committed.check(&key) {
+            return output.misbehaviour(message);
+        }
+    }
+
+    output.message = String::from("✅ Delivered key matches the seller's commitments");
+    output
+}
+
+/// Committed ciphertext with its public parameters decoded.
+struct DecodedCiphertext {
+    cipher: DecodedCipher,
+    ciphertext: Vec<u8>,
+    plaintext_sha256_hex: Option<String>,
+}
+
+enum DecodedCipher {
+    AesCtr { iv: Vec<u8> },
+    AesGcm { nonce: Vec<u8>, aad: Vec<u8> },
+}
+
+impl DecodedCiphertext {
+    fn decode(committed: &CommittedCiphertext) -> Result<Self, &'static str> {
+        let cipher = match &committed.cipher {
+            // AES-CTR decrypts under any key, so only the plaintext hash can catch a wrong one
+            DatasetCipher::AesCtr { .. } if committed.plaintext_sha256_hex.is_none() => {
+                return Err("AES-CTR commitments need plaintext_sha256_hex")
+            }
+            DatasetCipher::AesCtr { iv_hex } => match decode(iv_hex) {
+                Ok(iv) if iv.len() == AES_CTR_IV_LEN => DecodedCipher::AesCtr { iv },
+                _ => return Err("Invalid IV"),
+            },
+            DatasetCipher::AesGcm { nonce_hex, aad_hex } => {
+                let nonce = match decode(nonce_hex) {
+                    Ok(n) if n.len() == AES_GCM_NONCE_LEN => n,
+                    _ => return Err("Invalid nonce"),
+                };
+                let aad = aad_hex.as_deref().map(decode).transpose().map_err(|_| "Invalid AAD hex")?;
+                DecodedCipher::AesGcm { nonce, aad: aad.unwrap_or_default() }
+            }
+        };
+        Ok(Self {
+            cipher,
+            ciphertext: decode(&committed.ciphertext_hex).map_err(|_| "Invalid ciphertext hex")?,
+            plaintext_sha256_hex: committed.plaintext_sha256_hex.clone(),
+        })
+    }
+
+    /// Decrypts the ciphertext under `key` and compares the plaintext hash, if
+    /// one was committed. Errors describe the misbehaviour.
+    fn check(&self, key: &[u8]) -> Result<(), &'static str> {
+        let plaintext = match &self.cipher {
+            DecodedCipher::AesCtr { iv } => Zeroizing::new(aes_ctr_encrypt(key, iv, &self.ciphertext)?),
+            DecodedCipher::AesGcm { nonce, aad } => {
+                let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| "Invalid key length")?;
+                let payload = Payload { msg: &self.ciphertext, aad };
+                let plaintext = cipher
+                    .decrypt(Nonce::from_slice(nonce), payload)
+                    .map_err(|_| "❌ Delivered key does not decrypt the committed ciphertext")?;
+                Zeroizing::new(plaintext)
+            }
+        };
+
+        match &self.plaintext_sha256_hex {
+            Some(hash) if !sha256_hex(&plaintext).eq_ignore_ascii_case(hash) => {
+                Err("❌ Delivered key decrypts the committed ciphertext to a different plaintext")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl DisputeProofOutput {
+    fn misbehaviour(mut self, message: &str) -> Self {
+        self.is_misbehaviour = true;
+        self.message = String::from(message);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rsa::oaep_encrypt;
+    use crate::types::{HashLockDigest, KeyCommitment, OaepDigest, OaepParams, SecretString};
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use rsa::pkcs8::EncodePrivateKey;
+
+    const AES_KEY: [u8; AES_KEY_LEN] = [0x5a; AES_KEY_LEN];
+
+    /// Dispute over a secret the seller wrapped to the buyer with default OAEP,
+    /// claimed under `oaep` against a commitment to `committed_key`.
+    fn dispute(oaep: OaepParams, committed_key: &[u8]) -> DisputeProofOutput {
+        let private_key = RsaPrivateKey::new(&mut ChaCha20Rng::seed_from_u64(7), 2048).unwrap();
+        let seed = [0x11; 32];
+        let secret =
+            oaep_encrypt(&private_key.to_public_key(), &OaepParams::default(), &seed, &AES_KEY).unwrap();
+        let der = private_key.to_pkcs8_der().unwrap();
+        dispute_prove(DisputeProofInput {
+            rsa_private_key_base64: SecretString::from(general_purpose::STANDARD.encode(der.as_bytes())),
+            encrypted_secret_hex: hex::encode(secret),
+            oaep,
+            key_commitment: Some(KeyCommitment {
+                digest: HashLockDigest::Sha256,
+                key_hash_hex: key_hash_hex(HashLockDigest::Sha256, committed_key),
+            }),
+            ciphertext: None,
+        })
+    }
+
+    #[test]
+    fn accepts_a_key_matching_the_commitment() {
+        let output = dispute(OaepParams::default(), &AES_KEY);
+        assert!(!output.is_misbehaviour, "{}", output.message);
+    }
+
+    #[test]
+    fn flags_a_key_breaking_the_commitment() {
+        let output = dispute(OaepParams::default(), &[0; AES_KEY_LEN]);
+        assert!(output.is_misbehaviour);
+        assert_eq!(output.message, "❌ Delivered key does not match the committed key hash");
+    }
+
+    #[test]
+    fn commits_the_oaep_params_a_failed_unwrap_was_tried_under() {
+        let sha1 = OaepParams { digest: OaepDigest::Sha1, mgf_digest: OaepDigest::Sha1, label_hex: None };
+        let output = dispute(sha1.clone(), &AES_KEY);
+        assert!(output.is_misbehaviour);
+        assert_eq!(output.message, "❌ encryptedSecret does not decrypt under the buyer key");
+        // The arbiter sees that these are not the params the seller wrapped with
+        assert_eq!(output.oaep, sha1);
+        assert_ne!(output.oaep, OaepParams::default());
+    }
+}
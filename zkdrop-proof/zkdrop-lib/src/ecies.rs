@@ -0,0 +1,252 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use hex::decode as hex_decode;
+use hkdf::Hkdf;
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::Zeroizing;
+use crate::types::{
+    EciesEncryptAesKeyInput, EciesEncryptAesKeyOutput, EciesEncryptedAesKeyInput,
+    EciesEncryptedAesKeyOutput, ProofOutput,
+};
+use crate::utils::sha256_hex;
+
+/// X25519 public key length in bytes
+pub const ECIES_PUBKEY_LEN: usize = 32;
+
+/// Poly1305 tag length in bytes
+pub const ECIES_TAG_LEN: usize = 16;
+
+const EPHEMERAL_INFO: &[u8] = b"zkdrop-ecies-v1/ephemeral";
+const ENVELOPE_INFO: &[u8] = b"zkdrop-ecies-v1/envelope";
+
+/// Wraps `secret` to an X25519 public key.
+///
+/// Envelope layout: `ephemeral_pubkey (32) || ChaCha20-Poly1305(secret) || tag (16)`.
+/// The cipher key and nonce come from HKDF-SHA256 over the ECDH shared secret,
+/// salted with both public keys.
+///
+/// The ephemeral secret is derived from `secret` and the recipient key instead of
+/// an RNG, so the guest can re-derive the exact same envelope without a random
+/// witness (as `rsa_verify` does). Only someone who already knows `secret` can
+/// compute it; the one thing leaked is that the same secret was wrapped twice
+/// for the same recipient.
+pub fn seal(secret: &[u8], recipient: &[u8; ECIES_PUBKEY_LEN]) -> Result<Vec<u8>, &'static str> {
+    let mut ephemeral_bytes = Zeroizing::new([0u8; 32]);
+    Hkdf::<Sha256>::new(Some(recipient), secret)
+        .expand(EPHEMERAL_INFO, ephemeral_bytes.as_mut())
+        .map_err(|_| "HKDF expand failed")?;
+    let ephemeral = StaticSecret::from(*ephemeral_bytes);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+
+    let shared = ephemeral.diffie_hellman(&PublicKey::from(*recipient));
+    if !shared.was_contributory() {
+        return Err("Recipient public key has low order");
+    }
+
+    let (cipher, nonce) = envelope_cipher(shared.as_bytes(), ephemeral_public.as_bytes(), recipient)?;
+    let sealed = cipher
+        .encrypt(Nonce::from_slice(&nonce), secret)
+        .map_err(|_| "Encryption failed")?;
+
+    let mut envelope = Vec::with_capacity(ECIES_PUBKEY_LEN + sealed.len());
+    envelope.extend_from_slice(ephemeral_public.as_bytes());
+    envelope.extend_from_slice(&sealed);
+    Ok(envelope)
+}
+
+/// Opens an envelope produced by [`seal`] with the recipient's X25519 secret key.
+pub fn open(envelope: &[u8], recipient_secret: &[u8; 32]) -> Result<Zeroizing<Vec<u8>>, &'static str> {
+    if envelope.len() < ECIES_PUBKEY_LEN + ECIES_TAG_LEN {
+        return Err("Envelope too short");
+    }
+    let (ephemeral_bytes, sealed) = envelope.split_at(ECIES_PUBKEY_LEN);
+    let mut ephemeral_public = [0u8; ECIES_PUBKEY_LEN];
+    ephemeral_public.copy_from_slice(ephemeral_bytes);
+
+    let recipient = StaticSecret::from(*recipient_secret);
+    let recipient_public = PublicKey::from(&recipient);
+    let shared = recipient.diffie_hellman(&PublicKey::from(ephemeral_public));
+    if !shared.was_contributory() {
+        return Err("Ephemeral public key has low order");
+    }
+
+    let (cipher, nonce) =
+        envelope_cipher(shared.as_bytes(), &ephemeral_public, recipient_public.as_bytes())?;
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), sealed)
+        .map(Zeroizing::new)
+        .map_err(|_| "Decryption failed")
+}
+
+fn envelope_cipher(
+    shared: &[u8; 32],
+    ephemeral_public: &[u8; 32],
+    recipient: &[u8; 32],
+) -> Result<(ChaCha20Poly1305, [u8; 12]), &'static str> {
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(ephemeral_public);
+    salt[32..].copy_from_slice(recipient);
+
+    let mut okm = Zeroizing::new([0u8; 44]);
+    Hkdf::<Sha256>::new(Some(&salt), shared)
+        .expand(ENVELOPE_INFO, okm.as_mut())
+        .map_err(|_| "HKDF expand failed")?;
+
+    let cipher = ChaCha20Poly1305::new_from_slice(&okm[..32]).map_err(|_| "Invalid cipher key")?;
+    let mut nonce = [0u8; 12];
+    nonce.copy_from_slice(&okm[32..]);
+    Ok((cipher, nonce))
+}
+
+fn decode_pubkey(pubkey_hex: &str) -> Option<[u8; ECIES_PUBKEY_LEN]> {
+    hex_decode(pubkey_hex).ok()?.try_into().ok()
+}
+
+/// Proves that `enc_aes_key_hex` is the envelope [`seal`] produces for the AES
+/// key and the recipient. The journal commits the recipient key hash, the
+/// envelope and the AES key hash, so a receipt is tied to one purchase.
+pub fn ecies_verify(input: EciesEncryptedAesKeyInput) -> EciesEncryptedAesKeyOutput {
+    let mut output = EciesEncryptedAesKeyOutput {
+        is_valid: false,
+        message: String::new(),
+        ecies_pubkey_sha256_hex: String::new(),
+        enc_aes_key_hex: input.enc_aes_key_hex.clone(),
+        aes_key_sha256_hex: String::new(),
+    };
+
+    let aes_key_bytes = match hex_decode(input.aes_key_hex.expose_secret()) {
+        Ok(bytes) => Zeroizing::new(bytes),
+        Err(_) => return output.fail("Invalid AES key hex"),
+    };
+    output.aes_key_sha256_hex = sha256_hex(&aes_key_bytes);
+
+    let recipient = match decode_pubkey(&input.ecies_pubkey_hex) {
+        Some(key) => key,
+        None => return output.fail("Invalid X25519 pubkey hex"),
+    };
+    output.ecies_pubkey_sha256_hex = sha256_hex(&recipient);
+
+    let enc_aes_key_bytes = match hex_decode(&input.enc_aes_key_hex) {
+        Ok(bytes) => bytes,
+        Err(_) => return output.fail("Invalid encrypted AES key hex"),
+    };
+
+    let enc_result = match seal(&aes_key_bytes, &recipient) {
+        Ok(data) => data,
+        Err(err) => return output.fail(err),
+    };
+
+    if enc_result == enc_aes_key_bytes {
+        output.is_valid = true;
+        output.message = String::from("✅ ECIES encryption matches");
+    } else {
+        output.message = String::from("❌ Mismatch in ECIES encryption");
+    }
+    output
+}
+
+/// Wraps the AES key to the recipient with [`seal`]. The journal commits the
+/// envelope with the recipient key hash and the AES key hash.
+pub fn ecies_encrypt(input: EciesEncryptAesKeyInput) -> EciesEncryptAesKeyOutput {
+    let mut output = EciesEncryptAesKeyOutput {
+        is_valid: false,
+        message: String::new(),
+        enc_aes_key_hex: String::new(),
+        ecies_pubkey_sha256_hex: String::new(),
+        aes_key_sha256_hex: String::new(),
+    };
+
+    let aes_key_bytes = match hex_decode(input.aes_key_hex.expose_secret()) {
+        Ok(bytes) => Zeroizing::new(bytes),
+        Err(_) => return output.fail("Invalid AES key hex"),
+    };
+    output.aes_key_sha256_hex = sha256_hex(&aes_key_bytes);
+
+    let recipient = match decode_pubkey(&input.ecies_pubkey_hex) {
+        Some(key) => key,
+        None => return output.fail("Invalid X25519 pubkey hex"),
+    };
+    output.ecies_pubkey_sha256_hex = sha256_hex(&recipient);
+
+    match seal(&aes_key_bytes, &recipient) {
+        Ok(envelope) => {
+            output.is_valid = true;
+            output.message = String::from("✅ ECIES encryption successful");
+            output.enc_aes_key_hex = hex::encode(envelope);
+            output
+        }
+        Err(err) => output.fail(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SecretString;
+
+    const AES_KEY_HEX: &str = "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d";
+    const RECIPIENT_SECRET: [u8; 32] = [0x42; 32];
+
+    fn recipient() -> [u8; 32] {
+        PublicKey::from(&StaticSecret::from(RECIPIENT_SECRET)).to_bytes()
+    }
+
+    fn recipient_hex() -> String {
+        hex::encode(recipient())
+    }
+
+    fn verify(enc_aes_key_hex: &str, pubkey_hex: &str) -> EciesEncryptedAesKeyOutput {
+        ecies_verify(EciesEncryptedAesKeyInput {
+            aes_key_hex: SecretString::from(AES_KEY_HEX),
+            ecies_pubkey_hex: String::from(pubkey_hex),
+            enc_aes_key_hex: String::from(enc_aes_key_hex),
+        })
+    }
+
+    #[test]
+    fn wrapped_keys_open_and_verify_with_their_commitments() {
+        let output = ecies_encrypt(EciesEncryptAesKeyInput {
+            aes_key_hex: SecretString::from(AES_KEY_HEX),
+            ecies_pubkey_hex: recipient_hex(),
+        });
+        assert!(output.is_valid, "{}", output.message);
+        let aes_key = hex::decode(AES_KEY_HEX).unwrap();
+        let pubkey_hash = sha256_hex(&recipient());
+        assert_eq!(output.aes_key_sha256_hex, sha256_hex(&aes_key));
+        assert_eq!(output.ecies_pubkey_sha256_hex, pubkey_hash);
+
+        let envelope = hex::decode(&output.enc_aes_key_hex).unwrap();
+        assert_eq!(open(&envelope, &RECIPIENT_SECRET).unwrap().as_slice(), aes_key.as_slice());
+
+        let verified = verify(&output.enc_aes_key_hex, &recipient_hex());
+        assert!(verified.is_valid, "{}", verified.message);
+        assert_eq!(verified.enc_aes_key_hex, output.enc_aes_key_hex);
+        assert_eq!(verified.ecies_pubkey_sha256_hex, pubkey_hash);
+        assert_eq!(verified.aes_key_sha256_hex, output.aes_key_sha256_hex);
+    }
+
+    #[test]
+    fn rejects_envelopes_for_another_key_or_recipient() {
+        let envelope = seal(&hex::decode(AES_KEY_HEX).unwrap(), &[9u8; 32]).unwrap();
+        let output = verify(&hex::encode(&envelope), &recipient_hex());
+        assert!(!output.is_valid);
+        // The commitments still name what was checked
+        assert_eq!(output.enc_aes_key_hex, hex::encode(&envelope));
+        assert!(open(&envelope, &RECIPIENT_SECRET).is_err());
+
+        let mut tampered = seal(&hex::decode(AES_KEY_HEX).unwrap(), &recipient()).unwrap();
+        tampered[40] ^= 1;
+        assert!(open(&tampered, &RECIPIENT_SECRET).is_err());
+        assert!(!verify(&hex::encode(&tampered), &recipient_hex()).is_valid);
+    }
+
+    #[test]
+    fn rejects_low_order_recipients() {
+        // The all-zero point yields an all-zero shared secret
+        assert_eq!(seal(&[1u8; 32], &[0u8; 32]), Err("Recipient public key has low order"));
+        assert!(!verify(&"00".repeat(80), &"00".repeat(32)).is_valid);
+    }
+}
@@ -0,0 +1,286 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use base64::{engine::general_purpose, Engine as _};
+use crypto_box::aead::Aead;
+use crypto_box::{Nonce, PublicKey, SalsaBox, SecretKey};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+use crate::types::{
+    EthEncryptAesKeyInput, EthEncryptAesKeyOutput, EthEncryptedAesKeyInput,
+    EthEncryptedAesKeyOutput, EthEncryptedData, ProofOutput,
+};
+use crate::utils::sha256_hex;
+
+/// Envelope version understood by MetaMask's `eth_decrypt`
+pub const ETH_ENCRYPTION_VERSION: &str = "x25519-xsalsa20-poly1305";
+
+/// X25519 public key length in bytes
+pub const ETH_PUBKEY_LEN: usize = 32;
+
+/// XSalsa20 nonce length in bytes
+pub const ETH_NONCE_LEN: usize = 24;
+
+/// Poly1305 tag length in bytes
+pub const ETH_TAG_LEN: usize = 16;
+
+const DERIVATION_INFO: &[u8] = b"zkdrop-x25519-xsalsa20-poly1305-v1";
+
+/// Seals `message` to a wallet encryption key (as returned by
+/// `eth_getEncryptionPublicKey`) in the `eth-sig-util` envelope format:
+/// NaCl `box` (X25519 + XSalsa20-Poly1305) with an ephemeral sender key.
+///
+/// As in `ecies::seal`, the ephemeral secret and nonce are derived from the
+/// message and the recipient key, so the guest can re-derive the exact envelope.
+pub fn seal(message: &[u8], recipient: &[u8; ETH_PUBKEY_LEN]) -> Result<EthEncryptedData, &'static str> {
+    let (ephemeral, nonce) = derive_ephemeral(message, recipient)?;
+    let salsa_box = SalsaBox::new(&PublicKey::from(*recipient), &ephemeral);
+    let ciphertext = salsa_box.encrypt(&nonce, message).map_err(|_| "Encryption failed")?;
+
+    Ok(EthEncryptedData {
+        version: String::from(ETH_ENCRYPTION_VERSION),
+        nonce: general_purpose::STANDARD.encode(nonce),
+        ephem_public_key: general_purpose::STANDARD.encode(ephemeral.public_key().as_bytes()),
+        ciphertext: general_purpose::STANDARD.encode(ciphertext),
+    })
+}
+
+/// Ephemeral secret and nonce [`seal`] uses for `message` and `recipient`.
+fn derive_ephemeral(message: &[u8], recipient: &[u8; ETH_PUBKEY_LEN]) -> Result<(SecretKey, Nonce), &'static str> {
+    let mut okm = Zeroizing::new([0u8; 32 + ETH_NONCE_LEN]);
+    Hkdf::<Sha256>::new(Some(recipient), message)
+        .expand(DERIVATION_INFO, okm.as_mut())
+        .map_err(|_| "HKDF expand failed")?;
+
+    let mut ephemeral_bytes = Zeroizing::new([0u8; 32]);
+    ephemeral_bytes.copy_from_slice(&okm[..32]);
+    Ok((SecretKey::from(*ephemeral_bytes), Nonce::clone_from_slice(&okm[32..])))
+}
+
+/// Opens an envelope from the sender side: with the ephemeral secret it was
+/// sealed with, which must match its `ephemPublicKey`. NaCl `box` derives the
+/// same key from either end, so this recovers what the recipient would.
+pub fn open_as_sender(
+    encrypted: &EthEncryptedData,
+    recipient: &[u8; ETH_PUBKEY_LEN],
+    ephemeral: &SecretKey,
+) -> Result<Zeroizing<Vec<u8>>, &'static str> {
+    if encrypted.version != ETH_ENCRYPTION_VERSION {
+        return Err("Unsupported envelope version");
+    }
+    let ephemeral_public = decode_pubkey(&encrypted.ephem_public_key).ok_or("Invalid ephemeral public key")?;
+    if ephemeral.public_key().as_bytes() != &ephemeral_public {
+        return Err("Ephemeral secret does not match ephemPublicKey");
+    }
+    let nonce = general_purpose::STANDARD.decode(&encrypted.nonce).map_err(|_| "Invalid nonce base64")?;
+    if nonce.len() != ETH_NONCE_LEN {
+        return Err("Invalid nonce length");
+    }
+    let ciphertext = general_purpose::STANDARD
+        .decode(&encrypted.ciphertext)
+        .map_err(|_| "Invalid ciphertext base64")?;
+
+    SalsaBox::new(&PublicKey::from(*recipient), ephemeral)
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map(Zeroizing::new)
+        .map_err(|_| "Decryption failed")
+}
+
+/// Opens an `eth-sig-util` envelope with the recipient's X25519 secret key,
+/// mirroring what `eth_decrypt` does inside the wallet.
+pub fn open(encrypted: &EthEncryptedData, recipient_secret: &[u8; 32]) -> Result<Zeroizing<Vec<u8>>, &'static str> {
+    if encrypted.version != ETH_ENCRYPTION_VERSION {
+        return Err("Unsupported envelope version");
+    }
+    let nonce = general_purpose::STANDARD.decode(&encrypted.nonce).map_err(|_| "Invalid nonce base64")?;
+    if nonce.len() != ETH_NONCE_LEN {
+        return Err("Invalid nonce length");
+    }
+    let ephemeral = decode_pubkey(&encrypted.ephem_public_key).ok_or("Invalid ephemeral public key")?;
+    let ciphertext = general_purpose::STANDARD
+        .decode(&encrypted.ciphertext)
+        .map_err(|_| "Invalid ciphertext base64")?;
+
+    SalsaBox::new(&PublicKey::from(ephemeral), &SecretKey::from(*recipient_secret))
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map(Zeroizing::new)
+        .map_err(|_| "Decryption failed")
+}
+
+/// Decodes a base64 X25519 public key.
+pub fn decode_pubkey(pubkey_base64: &str) -> Option<[u8; ETH_PUBKEY_LEN]> {
+    general_purpose::STANDARD.decode(pubkey_base64).ok()?.try_into().ok()
+}
+
+/// The wallet returns the decrypted message as a string, so the AES key is
+/// sealed as its lowercase hex encoding rather than as raw bytes. Also returns
+/// the SHA-256 of the raw key for the journal.
+fn aes_key_message(aes_key_hex: &str) -> Option<(Zeroizing<String>, String)> {
+    let key = Zeroizing::new(hex::decode(aes_key_hex).ok()?);
+    Some((Zeroizing::new(hex::encode(key.as_slice())), sha256_hex(&key)))
+}
+
+/// Proves that `input.encrypted` seals the AES key (as lowercase hex) to the
+/// wallet key. The envelope is opened with its ephemeral secret: the private
+/// `ephemeral_secret_hex` witness for envelopes made elsewhere, such as by
+/// `eth-sig-util` with a random sender key, or otherwise the one [`seal`]
+/// derives, which only matches envelopes this host sealed. The journal commits
+/// the wallet key, the envelope and the AES key hash.
+pub fn eth_verify(input: EthEncryptedAesKeyInput) -> EthEncryptedAesKeyOutput {
+    let mut output = EthEncryptedAesKeyOutput {
+        is_valid: false,
+        message: String::new(),
+        eth_pubkey_base64: input.eth_pubkey_base64.clone(),
+        encrypted: input.encrypted.clone(),
+        aes_key_sha256_hex: String::new(),
+    };
+
+    let message = match aes_key_message(input.aes_key_hex.expose_secret()) {
+        Some((message, key_hash)) => {
+            output.aes_key_sha256_hex = key_hash;
+            message
+        }
+        None => return output.fail("Invalid AES key hex"),
+    };
+
+    let recipient = match decode_pubkey(&input.eth_pubkey_base64) {
+        Some(key) => key,
+        None => return output.fail("Invalid encryption pubkey base64"),
+    };
+
+    let ephemeral = match &input.ephemeral_secret_hex {
+        Some(secret_hex) => {
+            let bytes = hex::decode(secret_hex.expose_secret()).map(Zeroizing::new);
+            match bytes.ok().and_then(|bytes| <[u8; 32]>::try_from(bytes.as_slice()).ok()) {
+                Some(bytes) => SecretKey::from(bytes),
+                None => return output.fail("Invalid ephemeral secret hex"),
+            }
+        }
+        None => match derive_ephemeral(message.as_bytes(), &recipient) {
+            Ok((ephemeral, _)) => ephemeral,
+            Err(err) => return output.fail(err),
+        },
+    };
+
+    match open_as_sender(&input.encrypted, &recipient, &ephemeral) {
+        Ok(opened) if opened.as_slice() == message.as_bytes() => {
+            output.is_valid = true;
+            output.message = String::from("✅ x25519-xsalsa20-poly1305 encryption matches");
+            output
+        }
+        Ok(_) => output.fail("❌ Envelope seals a different message"),
+        Err(err) => output.fail(err),
+    }
+}
+
+/// Seals the AES key (as lowercase hex) to the wallet key with [`seal`]. The
+/// journal commits the envelope, the wallet key and the AES key hash.
+pub fn eth_encrypt(input: EthEncryptAesKeyInput) -> EthEncryptAesKeyOutput {
+    let mut output = EthEncryptAesKeyOutput {
+        is_valid: false,
+        message: String::new(),
+        encrypted: EthEncryptedData::default(),
+        eth_pubkey_base64: input.eth_pubkey_base64.clone(),
+        aes_key_sha256_hex: String::new(),
+    };
+
+    let message = match aes_key_message(input.aes_key_hex.expose_secret()) {
+        Some((message, key_hash)) => {
+            output.aes_key_sha256_hex = key_hash;
+            message
+        }
+        None => return output.fail("Invalid AES key hex"),
+    };
+
+    let recipient = match decode_pubkey(&input.eth_pubkey_base64) {
+        Some(key) => key,
+        None => return output.fail("Invalid encryption pubkey base64"),
+    };
+
+    match seal(message.as_bytes(), &recipient) {
+        Ok(encrypted) => {
+            output.is_valid = true;
+            output.message = String::from("✅ x25519-xsalsa20-poly1305 encryption successful");
+            output.encrypted = encrypted;
+            output
+        }
+        Err(err) => output.fail(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SecretString;
+
+    const AES_KEY_HEX: &str = "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d";
+    const WALLET_SECRET: [u8; 32] = [0x24; 32];
+
+    fn wallet_key_base64() -> String {
+        general_purpose::STANDARD.encode(SecretKey::from(WALLET_SECRET).public_key().as_bytes())
+    }
+
+    /// Seals like `eth-sig-util` `encrypt`: a sender key and nonce chosen by the
+    /// caller rather than derived from the message.
+    fn seal_with(message: &[u8], ephemeral: &SecretKey, nonce: [u8; ETH_NONCE_LEN]) -> EthEncryptedData {
+        let recipient = SecretKey::from(WALLET_SECRET).public_key();
+        let ciphertext = SalsaBox::new(&recipient, ephemeral).encrypt(Nonce::from_slice(&nonce), message).unwrap();
+        EthEncryptedData {
+            version: String::from(ETH_ENCRYPTION_VERSION),
+            nonce: general_purpose::STANDARD.encode(nonce),
+            ephem_public_key: general_purpose::STANDARD.encode(ephemeral.public_key().as_bytes()),
+            ciphertext: general_purpose::STANDARD.encode(ciphertext),
+        }
+    }
+
+    fn verify(encrypted: EthEncryptedData, ephemeral_secret_hex: Option<&str>) -> EthEncryptedAesKeyOutput {
+        eth_verify(EthEncryptedAesKeyInput {
+            aes_key_hex: SecretString::from(AES_KEY_HEX),
+            eth_pubkey_base64: wallet_key_base64(),
+            encrypted,
+            ephemeral_secret_hex: ephemeral_secret_hex.map(SecretString::from),
+        })
+    }
+
+    #[test]
+    fn host_sealed_envelopes_open_in_the_wallet_and_verify() {
+        let output = eth_encrypt(EthEncryptAesKeyInput {
+            aes_key_hex: SecretString::from(AES_KEY_HEX),
+            eth_pubkey_base64: wallet_key_base64(),
+        });
+        assert!(output.is_valid, "{}", output.message);
+        assert_eq!(output.eth_pubkey_base64, wallet_key_base64());
+        assert_eq!(output.aes_key_sha256_hex, sha256_hex(&hex::decode(AES_KEY_HEX).unwrap()));
+        assert_eq!(open(&output.encrypted, &WALLET_SECRET).unwrap().as_slice(), AES_KEY_HEX.as_bytes());
+
+        let verified = verify(output.encrypted.clone(), None);
+        assert!(verified.is_valid, "{}", verified.message);
+        assert_eq!(verified.encrypted, output.encrypted);
+        assert_eq!(verified.aes_key_sha256_hex, output.aes_key_sha256_hex);
+    }
+
+    #[test]
+    fn envelopes_with_a_random_sender_key_need_the_ephemeral_witness() {
+        let ephemeral_bytes = [0x77u8; 32];
+        let ephemeral = SecretKey::from(ephemeral_bytes);
+        let encrypted = seal_with(AES_KEY_HEX.as_bytes(), &ephemeral, [3u8; ETH_NONCE_LEN]);
+        assert_eq!(open(&encrypted, &WALLET_SECRET).unwrap().as_slice(), AES_KEY_HEX.as_bytes());
+
+        assert!(!verify(encrypted.clone(), None).is_valid);
+        let verified = verify(encrypted.clone(), Some(&hex::encode(ephemeral_bytes)));
+        assert!(verified.is_valid, "{}", verified.message);
+
+        let wrong = verify(encrypted, Some(&hex::encode([0x78u8; 32])));
+        assert_eq!(wrong.message, "Ephemeral secret does not match ephemPublicKey");
+    }
+
+    #[test]
+    fn rejects_envelopes_of_another_key() {
+        let ephemeral_bytes = [0x77u8; 32];
+        let other_key = "00".repeat(32);
+        let encrypted = seal_with(other_key.as_bytes(), &SecretKey::from(ephemeral_bytes), [3u8; ETH_NONCE_LEN]);
+        let output = verify(encrypted, Some(&hex::encode(ephemeral_bytes)));
+        assert!(!output.is_valid);
+        assert_eq!(output.message, "❌ Envelope seals a different message");
+    }
+}
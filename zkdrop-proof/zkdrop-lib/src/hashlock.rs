@@ -0,0 +1,98 @@
+use alloc::string::String;
+use hex::decode;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use zeroize::Zeroizing;
+
+use crate::aes_ctr::aes_ctr_prove;
+use crate::types::{HashLockDigest, HashLockProofInput, HashLockProofOutput};
+
+/// Hash of the AES key under `digest`, hex-encoded. This is the lock `h` a
+/// hash-lock escrow releases payment against once `k` with `H(k) = h` is revealed.
+pub fn key_hash_hex(digest: HashLockDigest, key: &[u8]) -> String {
+    match digest {
+        HashLockDigest::Sha256 => hex::encode(Sha256::digest(key)),
+        HashLockDigest::Keccak256 => hex::encode(Keccak256::digest(key)),
+    }
+}
+
+/// Classic ZKCP statement: the ciphertext decrypts to the plaintext under `k`
+/// (AES-CTR, as in `aes_ctr_prove`) and the committed `key_hash_hex` is `H(k)`.
+/// The buyer needs no key pair; the seller later reveals `k` on-chain.
+pub fn hashlock_verify(input: HashLockProofInput) -> HashLockProofOutput {
+    let mut output = HashLockProofOutput {
+        is_valid: false,
+        message: String::new(),
+        digest: input.digest,
+        key_hash_hex: String::new(),
+        iv_hex: input.decryption.iv_hex.clone(),
+        ciphertext_sha256_hex: String::new(),
+        ciphertext_cid: String::new(),
+        compression: input.decryption.compression,
+        compressed_sha256_hex: None,
+        plaintext_sha256_hex: String::new(),
+    };
+
+    let key = match decode(input.decryption.aes_key_hex.expose_secret()) {
+        Ok(k) => Zeroizing::new(k),
+        Err(_) => {
+            output.message = String::from("Invalid AES key hex");
+            return output;
+        }
+    };
+    output.key_hash_hex = key_hash_hex(input.digest, &key);
+
+    let decryption = aes_ctr_prove(input.decryption);
+    output.is_valid = decryption.is_valid;
+    output.message = decryption.message;
+    output.ciphertext_sha256_hex = decryption.ciphertext_sha256_hex;
+    output.ciphertext_cid = decryption.ciphertext_cid;
+    output.compressed_sha256_hex = decryption.compressed_sha256_hex;
+    output.plaintext_sha256_hex = decryption.plaintext_sha256_hex;
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipfs::ipfs_cid;
+    use crate::types::{AesCtrDecryptionProofInput, Compression, SecretString};
+    use crate::utils::sha256_hex;
+
+    const KEY_HEX: &str = "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d";
+    const CIPHERTEXT_HEX: &str = "ef8d7b4abcaea121953432bd58aa69589312";
+
+    fn input(plaintext: &str, digest: HashLockDigest) -> HashLockProofInput {
+        HashLockProofInput {
+            decryption: AesCtrDecryptionProofInput {
+                aes_key_hex: SecretString::from(KEY_HEX),
+                iv_hex: String::from("01020300000000000000000000000000"),
+                plaintext_utf8: SecretString::from(plaintext),
+                ciphertext_hex: String::from(CIPHERTEXT_HEX),
+                compression: Compression::None,
+            },
+            digest,
+        }
+    }
+
+    #[test]
+    fn ties_the_lock_to_the_dataset() {
+        let output = hashlock_verify(input("example fileeee ! ", HashLockDigest::Keccak256));
+        assert!(output.is_valid, "{}", output.message);
+        assert_eq!(output.key_hash_hex, key_hash_hex(HashLockDigest::Keccak256, &decode(KEY_HEX).unwrap()));
+        assert_eq!(output.plaintext_sha256_hex, sha256_hex(b"example fileeee ! "));
+        let ciphertext = decode(CIPHERTEXT_HEX).unwrap();
+        assert_eq!(output.ciphertext_sha256_hex, sha256_hex(&ciphertext));
+        assert_eq!(output.ciphertext_cid, ipfs_cid(&ciphertext));
+        assert_eq!(output.compressed_sha256_hex, None);
+    }
+
+    #[test]
+    fn commits_no_plaintext_hash_for_another_dataset() {
+        let output = hashlock_verify(input("another fileeee ! ", HashLockDigest::Sha256));
+        assert!(!output.is_valid);
+        assert_eq!(output.message, "❌ Ciphertext mismatch");
+        assert_eq!(output.plaintext_sha256_hex, "");
+        assert_eq!(output.ciphertext_cid, ipfs_cid(&decode(CIPHERTEXT_HEX).unwrap()));
+    }
+}
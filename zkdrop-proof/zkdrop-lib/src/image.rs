@@ -0,0 +1,506 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use hex::decode;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+use zeroize::Zeroizing;
+
+use crate::aes_ctr::aes_ctr_encrypt;
+use crate::types::{
+    ImageContainer, ImageDimensionRange, ImageFormat, ImageFormatCount, ImageMetadataInput,
+    ImageMetadataOutput, ProofOutput,
+};
+use crate::utils::sha256_hex;
+use crate::validation::MAX_INFLATED_LEN;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const TAR_BLOCK_LEN: usize = 512;
+const ZIP_LOCAL_HEADER_SIG: u32 = 0x0403_4b50;
+const ZIP_CENTRAL_HEADER_SIG: u32 = 0x0201_4b50;
+const ZIP_END_OF_CENTRAL_DIR_SIG: u32 = 0x0605_4b50;
+const ZIP_END_OF_CENTRAL_DIR_LEN: usize = 22;
+
+/// Reads a little-endian integer of `N` bytes at `offset`.
+fn le<const N: usize>(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(N)?)?;
+    Some(bytes.iter().rev().fold(0, |acc, &b| (acc << 8) | b as u32))
+}
+
+/// Reads a big-endian integer of `N` bytes at `offset`.
+fn be<const N: usize>(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(N)?)?;
+    Some(bytes.iter().fold(0, |acc, &b| (acc << 8) | b as u32))
+}
+
+/// Regular files of a ZIP archive, read through the central directory so
+/// entries written with data descriptors are supported. Entries must be
+/// stored or deflated; encrypted entries and ZIP64 are rejected.
+///
+/// Each file is handed to `visit` with its index in the central directory and
+/// dropped before the next one is inflated. Central directory entries may all
+/// point at the same deflate stream, so `MAX_INFLATED_LEN` caps the bytes
+/// inflated over the whole archive, not per entry. Errors name entries by
+/// index only: file names are private.
+pub fn zip_entries(
+    data: &[u8],
+    mut visit: impl FnMut(usize, &[u8]) -> Result<(), String>,
+) -> Result<(), String> {
+    let search_start = data.len().saturating_sub(ZIP_END_OF_CENTRAL_DIR_LEN + u16::MAX as usize);
+    let eocd = (search_start..=data.len().saturating_sub(ZIP_END_OF_CENTRAL_DIR_LEN))
+        .rev()
+        .find(|&i| le::<4>(data, i) == Some(ZIP_END_OF_CENTRAL_DIR_SIG))
+        .ok_or("ZIP end of central directory not found")?;
+    let truncated = || String::from("ZIP central directory is truncated");
+    let entry_count = le::<2>(data, eocd + 10).ok_or_else(truncated)? as usize;
+    let mut offset = le::<4>(data, eocd + 16).ok_or_else(truncated)? as usize;
+
+    let mut inflate_budget = MAX_INFLATED_LEN;
+    for index in 0..entry_count {
+        if le::<4>(data, offset) != Some(ZIP_CENTRAL_HEADER_SIG) {
+            return Err(truncated());
+        }
+        let flags = le::<2>(data, offset + 8).ok_or_else(truncated)?;
+        let method = le::<2>(data, offset + 10).ok_or_else(truncated)?;
+        let compressed_len = le::<4>(data, offset + 20).ok_or_else(truncated)? as usize;
+        let uncompressed_len = le::<4>(data, offset + 24).ok_or_else(truncated)? as usize;
+        let name_len = le::<2>(data, offset + 28).ok_or_else(truncated)? as usize;
+        let extra_len = le::<2>(data, offset + 30).ok_or_else(truncated)? as usize;
+        let comment_len = le::<2>(data, offset + 32).ok_or_else(truncated)? as usize;
+        let local_offset = le::<4>(data, offset + 42).ok_or_else(truncated)? as usize;
+        let name = data.get(offset + 46..offset + 46 + name_len).ok_or_else(truncated)?;
+        offset += 46 + name_len + extra_len + comment_len;
+
+        if name.ends_with(b"/") {
+            continue;
+        }
+        if flags & 1 != 0 {
+            return Err(format!("ZIP entry {} is encrypted", index));
+        }
+        if [compressed_len, uncompressed_len, local_offset].contains(&(u32::MAX as usize)) {
+            return Err(format!("ZIP entry {} uses ZIP64, which is not supported", index));
+        }
+        if le::<4>(data, local_offset) != Some(ZIP_LOCAL_HEADER_SIG) {
+            return Err(format!("ZIP entry {} has no local header", index));
+        }
+        let local_name_len = le::<2>(data, local_offset + 26).ok_or_else(truncated)? as usize;
+        let local_extra_len = le::<2>(data, local_offset + 28).ok_or_else(truncated)? as usize;
+        let start = local_offset + 30 + local_name_len + local_extra_len;
+        let raw = data
+            .get(start..start.saturating_add(compressed_len))
+            .ok_or_else(|| format!("ZIP entry {} is truncated", index))?;
+        match method {
+            0 => visit(index, raw)?,
+            8 => {
+                if uncompressed_len > inflate_budget {
+                    return Err(format!(
+                        "ZIP archive inflates to more than {} bytes",
+                        MAX_INFLATED_LEN
+                    ));
+                }
+                let contents = decompress_to_vec_with_limit(raw, uncompressed_len)
+                    .map(Zeroizing::new)
+                    .map_err(|_| format!("ZIP entry {} does not inflate", index))?;
+                inflate_budget -= contents.len();
+                visit(index, &contents)?;
+            }
+            _ => {
+                return Err(format!(
+                    "ZIP entry {} uses compression method {}, expected stored or deflate",
+                    index, method
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Regular files of a (ustar or GNU) TAR archive, handed to `visit` with their
+/// index among the archive's headers. Header checksums are checked;
+/// directories, links and extended headers are skipped.
+pub fn tar_entries(
+    data: &[u8],
+    mut visit: impl FnMut(usize, &[u8]) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut offset = 0;
+    let mut index = 0;
+    while offset < data.len() {
+        let header = data
+            .get(offset..offset + TAR_BLOCK_LEN)
+            .ok_or("TAR header is truncated")?;
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        let checksum = parse_octal(&header[148..156]).ok_or("TAR header checksum is not octal")?;
+        let actual: u32 = header
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| if (148..156).contains(&i) { b' ' as u32 } else { b as u32 })
+            .sum();
+        if checksum != actual as u64 {
+            return Err(format!("TAR header at offset {} has a bad checksum", offset));
+        }
+        let size = parse_octal(&header[124..136])
+            .and_then(|size| usize::try_from(size).ok())
+            .ok_or("TAR entry size is not octal")?;
+        let start = offset + TAR_BLOCK_LEN;
+        let contents = data
+            .get(start..start.saturating_add(size))
+            .ok_or_else(|| format!("TAR entry {} is truncated", index))?;
+        if matches!(header[156], b'0' | 0) {
+            visit(index, contents)?;
+        }
+        offset = start + size.div_ceil(TAR_BLOCK_LEN) * TAR_BLOCK_LEN;
+        index += 1;
+    }
+    Ok(())
+}
+
+/// Parses a NUL or space terminated octal TAR header field.
+fn parse_octal(field: &[u8]) -> Option<u64> {
+    let digits = field
+        .iter()
+        .skip_while(|&&b| b == b' ')
+        .take_while(|&&b| b != 0 && b != b' ');
+    let mut value: u64 = 0;
+    for &b in digits {
+        if !(b'0'..=b'7').contains(&b) {
+            return None;
+        }
+        value = value.checked_mul(8)?.checked_add((b - b'0') as u64)?;
+    }
+    Some(value)
+}
+
+/// Detects a PNG, JPEG or GIF image and reads its `(width, height)` from the
+/// header. Returns `Ok(None)` for other files.
+pub fn image_dimensions(data: &[u8]) -> Result<Option<(ImageFormat, u32, u32)>, &'static str> {
+    if data.starts_with(PNG_SIGNATURE) {
+        if data.get(12..16) != Some(b"IHDR") {
+            return Err("PNG does not start with an IHDR chunk");
+        }
+        let width = be::<4>(data, 16).ok_or("PNG header is truncated")?;
+        let height = be::<4>(data, 20).ok_or("PNG header is truncated")?;
+        if width == 0 || height == 0 {
+            return Err("PNG has a zero dimension");
+        }
+        return Ok(Some((ImageFormat::Png, width, height)));
+    }
+    if data.starts_with(&[0xff, 0xd8]) {
+        let (width, height) = jpeg_dimensions(data)?;
+        return Ok(Some((ImageFormat::Jpeg, width, height)));
+    }
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        // Logical screen size, which every frame is drawn within
+        let width = le::<2>(data, 6).ok_or("GIF header is truncated")?;
+        let height = le::<2>(data, 8).ok_or("GIF header is truncated")?;
+        if width == 0 || height == 0 {
+            return Err("GIF has a zero dimension");
+        }
+        return Ok(Some((ImageFormat::Gif, width, height)));
+    }
+    Ok(None)
+}
+
+/// Walks the JPEG markers up to the first start-of-frame segment.
+fn jpeg_dimensions(data: &[u8]) -> Result<(u32, u32), &'static str> {
+    let mut offset = 2;
+    loop {
+        if data.get(offset) != Some(&0xff) {
+            return Err("JPEG marker expected");
+        }
+        while data.get(offset) == Some(&0xff) {
+            offset += 1;
+        }
+        let marker = *data.get(offset).ok_or("JPEG is truncated")?;
+        offset += 1;
+        match marker {
+            0x01 | 0xd0..=0xd7 => continue,
+            0xd9 | 0xda => return Err("JPEG has no start-of-frame segment"),
+            _ => {}
+        }
+        let segment_len = be::<2>(data, offset).ok_or("JPEG is truncated")? as usize;
+        if segment_len < 2 {
+            return Err("JPEG segment length is invalid");
+        }
+        if matches!(marker, 0xc0..=0xcf) && !matches!(marker, 0xc4 | 0xc8 | 0xcc) {
+            let height = be::<2>(data, offset + 3).ok_or("JPEG frame header is truncated")?;
+            let width = be::<2>(data, offset + 5).ok_or("JPEG frame header is truncated")?;
+            if width == 0 || height == 0 {
+                return Err("JPEG has a zero dimension");
+            }
+            return Ok((width, height));
+        }
+        offset += segment_len;
+    }
+}
+
+/// Summary of the images found in an archive
+struct ImageSummary {
+    image_count: u32,
+    other_file_count: u32,
+    formats: Vec<ImageFormatCount>,
+    dimensions: Option<ImageDimensionRange>,
+}
+
+/// Unpacks `data` as `container` and collects image count, format histogram
+/// and dimension ranges. Files that are not PNG, JPEG or GIF are counted as
+/// other files; a file with an image signature but a broken header is an error.
+fn summarize_images(data: &[u8], container: ImageContainer) -> Result<ImageSummary, String> {
+    let mut summary = ImageSummary {
+        image_count: 0,
+        other_file_count: 0,
+        formats: Vec::new(),
+        dimensions: None,
+    };
+    let visit = |index: usize, contents: &[u8]| {
+        summary.add(contents).map_err(|err| format!("Entry {}: {}", index, err))
+    };
+    match container {
+        ImageContainer::Zip => zip_entries(data, visit)?,
+        ImageContainer::Tar => tar_entries(data, visit)?,
+    }
+    summary.formats.sort_by_key(|f| f.format);
+    Ok(summary)
+}
+
+impl ImageSummary {
+    /// Counts one archive file.
+    fn add(&mut self, contents: &[u8]) -> Result<(), &'static str> {
+        let Some((format, width, height)) = image_dimensions(contents)? else {
+            self.other_file_count += 1;
+            return Ok(());
+        };
+        self.image_count += 1;
+        match self.formats.iter_mut().find(|f| f.format == format) {
+            Some(count) => count.count += 1,
+            None => self.formats.push(ImageFormatCount { format, count: 1 }),
+        }
+        let range = self.dimensions.get_or_insert(ImageDimensionRange {
+            min_width: width,
+            max_width: width,
+            min_height: height,
+            max_height: height,
+        });
+        range.min_width = range.min_width.min(width);
+        range.max_width = range.max_width.max(width);
+        range.min_height = range.min_height.min(height);
+        range.max_height = range.max_height.max(height);
+        Ok(())
+    }
+}
+
+/// Proves metadata of an AES-CTR encrypted image archive: the guest decrypts
+/// the committed ciphertext itself, unpacks the ZIP or TAR container and reads
+/// the PNG/JPEG/GIF headers. The plaintext never leaves the guest, and the output
+/// is bound to the dataset by `ciphertext_sha256_hex` and `key_sha256_hex`.
+pub fn image_prove(input: ImageMetadataInput) -> ImageMetadataOutput {
+    let mut output = ImageMetadataOutput {
+        is_valid: false,
+        message: String::new(),
+        iv_hex: input.iv_hex.clone(),
+        key_sha256_hex: String::new(),
+        ciphertext_sha256_hex: String::new(),
+        container: input.container,
+        image_count: 0,
+        other_file_count: 0,
+        formats: Vec::new(),
+        dimensions: None,
+    };
+
+    let key = match decode(input.aes_key_hex.expose_secret()) {
+        Ok(key) => Zeroizing::new(key),
+        Err(_) => return output.fail("Invalid AES key hex"),
+    };
+    output.key_sha256_hex = sha256_hex(&key);
+    let iv = match decode(&input.iv_hex) {
+        Ok(iv) => iv,
+        Err(_) => return output.fail("Invalid IV hex"),
+    };
+    let ciphertext = match decode(&input.ciphertext_hex) {
+        Ok(ciphertext) => ciphertext,
+        Err(_) => return output.fail("Invalid ciphertext hex"),
+    };
+    output.ciphertext_sha256_hex = sha256_hex(&ciphertext);
+    let plaintext = match aes_ctr_encrypt(&key, &iv, &ciphertext) {
+        Ok(plaintext) => Zeroizing::new(plaintext),
+        Err(err) => return output.fail(err),
+    };
+
+    match summarize_images(&plaintext, input.container) {
+        Ok(summary) => {
+            output.is_valid = true;
+            output.message =
+                String::from("✅ Image metadata read from the AES-CTR encrypted archive");
+            output.image_count = summary.image_count;
+            output.other_file_count = summary.other_file_count;
+            output.formats = summary.formats;
+            output.dimensions = summary.dimensions;
+        }
+        Err(err) => output.message = format!("❌ {}", err),
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SecretString;
+    use alloc::vec;
+
+    // Archives of the same images written by Info-ZIP `zip` (to a pipe, so
+    // with data descriptors) and GNU tar; see testdata/README.md
+    const ZIP: &[u8] = include_bytes!("../testdata/images.zip");
+    const ENCRYPTED_ZIP: &[u8] = include_bytes!("../testdata/images-encrypted.zip");
+    const GNU_TAR: &[u8] = include_bytes!("../testdata/images-gnu.tar");
+    const PAX_TAR: &[u8] = include_bytes!("../testdata/images-pax.tar");
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut png = Vec::from(PNG_SIGNATURE);
+        png.extend_from_slice(&[0, 0, 0, 13]);
+        png.extend_from_slice(b"IHDR");
+        png.extend_from_slice(&width.to_be_bytes());
+        png.extend_from_slice(&height.to_be_bytes());
+        png.extend_from_slice(&[8, 2, 0, 0, 0]);
+        png
+    }
+
+    /// SOI, an APP0 segment and a Huffman table (`0xc4`, not a frame) before
+    /// the `sof` frame header
+    fn jpeg(sof: u8, width: u16, height: u16) -> Vec<u8> {
+        let mut jpeg = vec![0xff, 0xd8, 0xff, 0xe0, 0, 7, b'J', b'F', b'I', b'F', 0];
+        jpeg.extend_from_slice(&[0xff, 0xc4, 0, 3, 0]);
+        jpeg.extend_from_slice(&[0xff, sof, 0, 11, 8]);
+        jpeg.extend_from_slice(&height.to_be_bytes());
+        jpeg.extend_from_slice(&width.to_be_bytes());
+        jpeg.extend_from_slice(&[1, 1, 0x11, 0]);
+        jpeg
+    }
+
+    fn gif(version: &[u8; 6], width: u16, height: u16) -> Vec<u8> {
+        let mut gif = Vec::from(&version[..]);
+        gif.extend_from_slice(&width.to_le_bytes());
+        gif.extend_from_slice(&height.to_le_bytes());
+        gif
+    }
+
+    fn expected_formats() -> Vec<ImageFormatCount> {
+        vec![
+            ImageFormatCount { format: ImageFormat::Png, count: 3 },
+            ImageFormatCount { format: ImageFormat::Jpeg, count: 2 },
+            ImageFormatCount { format: ImageFormat::Gif, count: 1 },
+        ]
+    }
+
+    fn assert_fixture_summary(summary: ImageSummary) {
+        assert_eq!((summary.image_count, summary.other_file_count), (6, 1));
+        assert_eq!(summary.formats, expected_formats());
+        assert_eq!(
+            summary.dimensions,
+            Some(ImageDimensionRange { min_width: 16, max_width: 1024, min_height: 9, max_height: 1200 })
+        );
+    }
+
+    #[test]
+    fn reads_png_jpeg_and_gif_dimensions() {
+        assert_eq!(image_dimensions(&png(640, 480)), Ok(Some((ImageFormat::Png, 640, 480))));
+        assert_eq!(image_dimensions(&jpeg(0xc0, 1024, 768)), Ok(Some((ImageFormat::Jpeg, 1024, 768))));
+        assert_eq!(image_dimensions(&jpeg(0xc2, 800, 1200)), Ok(Some((ImageFormat::Jpeg, 800, 1200))));
+        assert_eq!(image_dimensions(&gif(b"GIF87a", 16, 9)), Ok(Some((ImageFormat::Gif, 16, 9))));
+        assert_eq!(image_dimensions(&gif(b"GIF89a", 300, 2)), Ok(Some((ImageFormat::Gif, 300, 2))));
+        assert_eq!(image_dimensions(b"CC-BY-4.0\n"), Ok(None));
+        assert_eq!(image_dimensions(&gif(b"GIF86a", 16, 9)), Ok(None));
+    }
+
+    #[test]
+    fn rejects_broken_image_headers() {
+        let mut no_ihdr = png(640, 480);
+        no_ihdr[12..16].copy_from_slice(b"IDAT");
+        let mut sos_first = jpeg(0xc0, 1024, 768);
+        sos_first[12] = 0xda;
+        let cases: [(Vec<u8>, &str); 7] = [
+            (png(0, 480), "PNG has a zero dimension"),
+            (png(640, 480)[..20].to_vec(), "PNG header is truncated"),
+            (no_ihdr, "PNG does not start with an IHDR chunk"),
+            (jpeg(0xc0, 1024, 0), "JPEG has a zero dimension"),
+            (sos_first, "JPEG has no start-of-frame segment"),
+            (gif(b"GIF89a", 0, 9), "GIF has a zero dimension"),
+            (gif(b"GIF89a", 16, 9)[..9].to_vec(), "GIF header is truncated"),
+        ];
+        for (data, message) in cases {
+            assert_eq!(image_dimensions(&data), Err(message));
+        }
+    }
+
+    #[test]
+    fn walks_zip_archives_through_the_central_directory() {
+        let mut stored_and_deflated = 0;
+        zip_entries(ZIP, |_, _| {
+            stored_and_deflated += 1;
+            Ok(())
+        })
+        .unwrap();
+        // Two directory entries are skipped
+        assert_eq!(stored_and_deflated, 7);
+        assert_fixture_summary(summarize_images(ZIP, ImageContainer::Zip).unwrap());
+    }
+
+    #[test]
+    fn rejects_encrypted_and_truncated_zips() {
+        assert_eq!(zip_entries(ENCRYPTED_ZIP, |_, _| Ok(())).unwrap_err(), "ZIP entry 0 is encrypted");
+        assert_eq!(
+            zip_entries(&ZIP[..ZIP.len() - 1], |_, _| Ok(())).unwrap_err(),
+            "ZIP end of central directory not found"
+        );
+        assert!(summarize_images(GNU_TAR, ImageContainer::Zip).is_err());
+    }
+
+    #[test]
+    fn walks_gnu_and_pax_tar_archives() {
+        for tar in [GNU_TAR, PAX_TAR] {
+            assert_fixture_summary(summarize_images(tar, ImageContainer::Tar).unwrap());
+        }
+    }
+
+    #[test]
+    fn rejects_corrupt_tar_archives() {
+        let mut bad_checksum = GNU_TAR.to_vec();
+        bad_checksum[TAR_BLOCK_LEN] ^= 1;
+        assert_eq!(
+            tar_entries(&bad_checksum, |_, _| Ok(())).unwrap_err(),
+            "TAR header at offset 512 has a bad checksum"
+        );
+        // The directory header, then LICENSE.txt cut short
+        let truncated = &GNU_TAR[..2 * TAR_BLOCK_LEN + 100];
+        assert_eq!(tar_entries(truncated, |_, _| Ok(())).unwrap_err(), "TAR entry 1 is truncated");
+        assert_eq!(tar_entries(&GNU_TAR[..100], |_, _| Ok(())).unwrap_err(), "TAR header is truncated");
+    }
+
+    #[test]
+    fn proves_the_metadata_of_an_encrypted_archive() {
+        let key = [7u8; 32];
+        let iv = [1u8; 16];
+        let prove = |archive: &[u8]| {
+            image_prove(ImageMetadataInput {
+                aes_key_hex: SecretString::from(hex::encode(key)),
+                iv_hex: hex::encode(iv),
+                ciphertext_hex: hex::encode(aes_ctr_encrypt(&key, &iv, archive).unwrap()),
+                container: ImageContainer::Tar,
+            })
+        };
+
+        let output = prove(PAX_TAR);
+        assert!(output.is_valid, "{}", output.message);
+        assert_eq!((output.image_count, output.other_file_count), (6, 1));
+        assert_eq!(output.formats, expected_formats());
+        assert_eq!(output.key_sha256_hex, sha256_hex(&key));
+
+        // Zero the width in the IHDR of the first PNG
+        let mut broken = PAX_TAR.to_vec();
+        let png_start = broken.windows(PNG_SIGNATURE.len()).position(|w| w == PNG_SIGNATURE).unwrap();
+        broken[png_start + 16..png_start + 20].fill(0);
+        let output = prove(&broken);
+        assert!(!output.is_valid);
+        assert!(output.message.ends_with(": PNG has a zero dimension"), "{}", output.message);
+        assert_eq!(output.image_count, 0);
+    }
+}
@@ -0,0 +1,177 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+
+/// Leaf size of the default fixed-size chunker (`size-262144`)
+pub const IPFS_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Maximum links per node of the default balanced DAG layout
+pub const IPFS_MAX_LINKS: usize = 174;
+
+/// Multicodec of raw leaf blocks
+const CODEC_RAW: u64 = 0x55;
+
+/// Multicodec of UnixFS (dag-pb) interior nodes
+const CODEC_DAG_PB: u64 = 0x70;
+
+/// Multihash code and digest length of sha2-256
+const MULTIHASH_SHA2_256: u64 = 0x12;
+const SHA2_256_LEN: u64 = 32;
+
+/// UnixFS `Data.Type` of a file node
+const UNIXFS_FILE: u64 = 2;
+
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// A block referenced by its parent: CID bytes, the file bytes below it and
+/// the cumulative size of all blocks below it (`Tsize`).
+struct DagLink {
+    cid: Vec<u8>,
+    file_size: u64,
+    tsize: u64,
+}
+
+/// CIDv1 under which IPFS stores `data` when added with the defaults of
+/// `ipfs add --cid-version=1` and pinning services: raw leaves of
+/// `IPFS_CHUNK_SIZE` bytes, a balanced UnixFS DAG of at most `IPFS_MAX_LINKS`
+/// links per node and sha2-256 multihashes. A file of a single chunk is its
+/// raw leaf. Returned in the default base32 multibase (`bafk…` / `bafy…`).
+pub fn ipfs_cid(data: &[u8]) -> String {
+    let mut level: Vec<DagLink> = if data.is_empty() {
+        vec![raw_leaf(data)]
+    } else {
+        data.chunks(IPFS_CHUNK_SIZE).map(raw_leaf).collect()
+    };
+    while level.len() > 1 {
+        level = level.chunks(IPFS_MAX_LINKS).map(file_node).collect();
+    }
+    base32_multibase(&level[0].cid)
+}
+
+fn raw_leaf(chunk: &[u8]) -> DagLink {
+    DagLink {
+        cid: cid_v1(CODEC_RAW, chunk),
+        file_size: chunk.len() as u64,
+        tsize: chunk.len() as u64,
+    }
+}
+
+/// Encodes a UnixFS file node over `children` as dag-pb: links first, then
+/// the UnixFS `Data` message with the file size and one block size per link.
+fn file_node(children: &[DagLink]) -> DagLink {
+    let file_size = children.iter().map(|child| child.file_size).sum();
+    let mut unixfs = Vec::new();
+    put_varint_field(&mut unixfs, 1, UNIXFS_FILE);
+    put_varint_field(&mut unixfs, 3, file_size);
+    for child in children {
+        put_varint_field(&mut unixfs, 4, child.file_size);
+    }
+
+    let mut node = Vec::new();
+    for child in children {
+        let mut link = Vec::new();
+        put_bytes_field(&mut link, 1, &child.cid);
+        put_bytes_field(&mut link, 2, b"");
+        put_varint_field(&mut link, 3, child.tsize);
+        put_bytes_field(&mut node, 2, &link);
+    }
+    put_bytes_field(&mut node, 1, &unixfs);
+
+    let tsize = node.len() as u64 + children.iter().map(|child| child.tsize).sum::<u64>();
+    DagLink { cid: cid_v1(CODEC_DAG_PB, &node), file_size, tsize }
+}
+
+/// Binary CIDv1: version, content codec and sha2-256 multihash of `block`.
+fn cid_v1(codec: u64, block: &[u8]) -> Vec<u8> {
+    let mut cid = Vec::with_capacity(36);
+    put_varint(&mut cid, 1);
+    put_varint(&mut cid, codec);
+    put_varint(&mut cid, MULTIHASH_SHA2_256);
+    put_varint(&mut cid, SHA2_256_LEN);
+    cid.extend_from_slice(&Sha256::digest(block));
+    cid
+}
+
+/// Unsigned LEB128, as used by both multiformats and protobuf.
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn put_varint_field(out: &mut Vec<u8>, field: u64, value: u64) {
+    put_varint(out, field << 3);
+    put_varint(out, value);
+}
+
+fn put_bytes_field(out: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    put_varint(out, (field << 3) | 2);
+    put_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+/// Lowercase, unpadded RFC 4648 base32 with the `b` multibase prefix.
+fn base32_multibase(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(1 + (bytes.len() * 8).div_ceil(5));
+    encoded.push('b');
+    let (mut buffer, mut bits) = (0u16, 0);
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[(buffer >> bits) as usize & 31] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic content whose chunks all differ, so a leaf in the wrong
+    /// position changes the root.
+    fn pattern(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn single_chunk_files_are_raw_leaves() {
+        // `ipfs add --cid-version=1 --raw-leaves` of an empty file and of "hello world"
+        assert_eq!(ipfs_cid(b""), "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku");
+        assert_eq!(ipfs_cid(b"hello world"), "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e");
+    }
+
+    // The vectors below come from a separate Python implementation of go-unixfs'
+    // balanced builder (depth-first fill, dag-pb links with empty names and
+    // `Tsize`), which reproduces the two `ipfs add` CIDs above.
+
+    #[test]
+    fn files_past_one_chunk_get_a_dag_pb_root() {
+        assert_eq!(
+            ipfs_cid(&pattern(IPFS_CHUNK_SIZE)),
+            "bafkreibruh455iawsviqslif5c7uurdcfdemh22mtnytyzvnzn75kpejxy"
+        );
+        assert_eq!(
+            ipfs_cid(&pattern(IPFS_CHUNK_SIZE + 1)),
+            "bafybeiexg2oqkfnj56l7fcmawswqbijt5shq4b5rg6a546uwpkqqzwjioi"
+        );
+    }
+
+    #[test]
+    fn files_past_max_links_get_a_second_level() {
+        let data = pattern(IPFS_MAX_LINKS * IPFS_CHUNK_SIZE + 1);
+        assert_eq!(
+            ipfs_cid(&data[..IPFS_MAX_LINKS * IPFS_CHUNK_SIZE]),
+            "bafybeihpe5snhzneq7xs53nivmsopto5lrogo3wjynauqylqeym5a3irbm"
+        );
+        assert_eq!(ipfs_cid(&data), "bafybeib4y7ghw2rq7bracc4xwtxrbzo7cfvagdpte2tmrkgwl6dyard3cm");
+    }
+}
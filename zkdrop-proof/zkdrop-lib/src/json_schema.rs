@@ -0,0 +1,461 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use hex::decode;
+use serde_json::{Map, Value};
+use zeroize::Zeroizing;
+
+use crate::aes_ctr::aes_ctr_verify;
+use crate::types::{JsonConformanceInput, JsonConformanceOutput, JsonFormat, ProofOutput};
+use crate::utils::sha256_hex;
+
+/// Keywords that carry no constraint and are skipped when compiling a schema
+const ANNOTATIONS: [&str; 7] =
+    ["$schema", "$id", "$comment", "title", "description", "default", "examples"];
+
+/// Values of the `type` keyword
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JsonType {
+    Null,
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "null" => JsonType::Null,
+            "boolean" => JsonType::Boolean,
+            "integer" => JsonType::Integer,
+            "number" => JsonType::Number,
+            "string" => JsonType::String,
+            "array" => JsonType::Array,
+            "object" => JsonType::Object,
+            _ => return None,
+        })
+    }
+
+    fn matches(self, value: &Value) -> bool {
+        match (self, value) {
+            (JsonType::Null, Value::Null) => true,
+            (JsonType::Boolean, Value::Bool(_)) => true,
+            (JsonType::Integer, Value::Number(n)) => {
+                n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+            }
+            (JsonType::Number, Value::Number(_)) => true,
+            (JsonType::String, Value::String(_)) => true,
+            (JsonType::Array, Value::Array(_)) => true,
+            (JsonType::Object, Value::Object(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Compiled JSON Schema, restricted to a pragmatic subset of draft 2020-12:
+/// `type`, `enum`, `const`, `minimum`, `maximum`, `exclusiveMinimum`,
+/// `exclusiveMaximum`, `minLength`, `maxLength`, `items`, `minItems`,
+/// `maxItems`, `properties`, `required` and boolean `additionalProperties`.
+/// Any other keyword (except annotations such as `title`) is rejected rather
+/// than silently ignored, so a committed schema never promises more than is checked.
+#[derive(Debug, Default)]
+pub struct JsonSchema {
+    types: Option<Vec<JsonType>>,
+    enum_values: Option<Vec<Value>>,
+    const_value: Option<Value>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    exclusive_minimum: Option<f64>,
+    exclusive_maximum: Option<f64>,
+    min_length: Option<u64>,
+    max_length: Option<u64>,
+    items: Option<Box<JsonSchema>>,
+    min_items: Option<u64>,
+    max_items: Option<u64>,
+    properties: BTreeMap<String, JsonSchema>,
+    required: Vec<String>,
+    additional_properties: bool,
+}
+
+impl JsonSchema {
+    /// Parses and compiles `schema_json`.
+    pub fn parse(schema_json: &str) -> Result<Self, String> {
+        let schema: Value =
+            serde_json::from_str(schema_json).map_err(|err| format!("invalid JSON: {}", err))?;
+        Self::compile(&schema, "#")
+    }
+
+    /// Compiles `schema`; errors name the offending keyword as a JSON pointer from `at`.
+    fn compile(schema: &Value, at: &str) -> Result<Self, String> {
+        let mut compiled = Self { additional_properties: true, ..Default::default() };
+        let object = match schema {
+            Value::Object(object) => object,
+            Value::Bool(true) => return Ok(compiled),
+            _ => return Err(format!("{}: schema must be an object", at)),
+        };
+
+        for (keyword, value) in object {
+            let at = format!("{}/{}", at, keyword);
+            match keyword.as_str() {
+                "type" => compiled.types = Some(parse_types(value, &at)?),
+                "enum" => match value {
+                    Value::Array(values) => compiled.enum_values = Some(values.clone()),
+                    _ => return Err(format!("{}: must be an array", at)),
+                },
+                "const" => compiled.const_value = Some(value.clone()),
+                "minimum" => compiled.minimum = Some(number(value, &at)?),
+                "maximum" => compiled.maximum = Some(number(value, &at)?),
+                "exclusiveMinimum" => compiled.exclusive_minimum = Some(number(value, &at)?),
+                "exclusiveMaximum" => compiled.exclusive_maximum = Some(number(value, &at)?),
+                "minLength" => compiled.min_length = Some(count(value, &at)?),
+                "maxLength" => compiled.max_length = Some(count(value, &at)?),
+                "minItems" => compiled.min_items = Some(count(value, &at)?),
+                "maxItems" => compiled.max_items = Some(count(value, &at)?),
+                "items" => compiled.items = Some(Box::new(Self::compile(value, &at)?)),
+                "properties" => compiled.properties = compile_properties(value, &at)?,
+                "required" => compiled.required = parse_required(value, &at)?,
+                "additionalProperties" => match value {
+                    Value::Bool(allowed) => compiled.additional_properties = *allowed,
+                    _ => return Err(format!("{}: only true or false is supported", at)),
+                },
+                keyword if ANNOTATIONS.contains(&keyword) => {}
+                _ => return Err(format!("{}: unsupported keyword", at)),
+            }
+        }
+        Ok(compiled)
+    }
+
+    /// Checks `value` against the schema. Errors only name the failing keyword,
+    /// as a JSON pointer into the schema from `at` (`#` for the root), so the
+    /// journal never carries values, lengths or property names of the record.
+    pub fn check(&self, value: &Value, at: &str) -> Result<(), String> {
+        if let Some(types) = &self.types {
+            if !types.iter().any(|t| t.matches(value)) {
+                return Err(format!("{}/type", at));
+            }
+        }
+        if let Some(values) = &self.enum_values {
+            if !values.iter().any(|allowed| json_eq(allowed, value)) {
+                return Err(format!("{}/enum", at));
+            }
+        }
+        if let Some(expected) = &self.const_value {
+            if !json_eq(expected, value) {
+                return Err(format!("{}/const", at));
+            }
+        }
+
+        match value {
+            Value::Number(n) => match n.as_f64() {
+                Some(n) => self.check_number(n, at),
+                None => Ok(()),
+            },
+            Value::String(s) => self.check_length(s.chars().count() as u64, at),
+            Value::Array(items) => self.check_array(items, at),
+            Value::Object(object) => self.check_object(object, at),
+            Value::Null | Value::Bool(_) => Ok(()),
+        }
+    }
+
+    fn check_number(&self, n: f64, at: &str) -> Result<(), String> {
+        if self.minimum.is_some_and(|min| n < min) {
+            return Err(format!("{}/minimum", at));
+        }
+        if self.maximum.is_some_and(|max| n > max) {
+            return Err(format!("{}/maximum", at));
+        }
+        if self.exclusive_minimum.is_some_and(|min| n <= min) {
+            return Err(format!("{}/exclusiveMinimum", at));
+        }
+        if self.exclusive_maximum.is_some_and(|max| n >= max) {
+            return Err(format!("{}/exclusiveMaximum", at));
+        }
+        Ok(())
+    }
+
+    fn check_length(&self, len: u64, at: &str) -> Result<(), String> {
+        if self.min_length.is_some_and(|min| len < min) {
+            return Err(format!("{}/minLength", at));
+        }
+        if self.max_length.is_some_and(|max| len > max) {
+            return Err(format!("{}/maxLength", at));
+        }
+        Ok(())
+    }
+
+    fn check_array(&self, items: &[Value], at: &str) -> Result<(), String> {
+        let len = items.len() as u64;
+        if self.min_items.is_some_and(|min| len < min) {
+            return Err(format!("{}/minItems", at));
+        }
+        if self.max_items.is_some_and(|max| len > max) {
+            return Err(format!("{}/maxItems", at));
+        }
+        if let Some(schema) = &self.items {
+            let at = format!("{}/items", at);
+            for item in items {
+                schema.check(item, &at)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_object(&self, object: &Map<String, Value>, at: &str) -> Result<(), String> {
+        if self.required.iter().any(|key| !object.contains_key(key)) {
+            return Err(format!("{}/required", at));
+        }
+        for (key, value) in object {
+            match self.properties.get(key) {
+                Some(schema) => schema.check(value, &format!("{}/properties/{}", at, key))?,
+                None if !self.additional_properties => {
+                    return Err(format!("{}/additionalProperties", at))
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Equality as JSON Schema defines it for `enum` and `const`: numbers compare
+/// by mathematical value (`1` equals `1.0`), arrays and objects member-wise.
+fn json_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => {
+            if let (Some(a), Some(b)) = (a.as_i64(), b.as_i64()) {
+                a == b
+            } else if let (Some(a), Some(b)) = (a.as_u64(), b.as_u64()) {
+                a == b
+            } else {
+                a.as_f64() == b.as_f64()
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| json_eq(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter().all(|(key, a)| b.get(key).is_some_and(|b| json_eq(a, b)))
+        }
+        _ => a == b,
+    }
+}
+
+fn parse_types(value: &Value, at: &str) -> Result<Vec<JsonType>, String> {
+    let names = match value {
+        Value::String(name) => vec![name.as_str()],
+        Value::Array(names) => names
+            .iter()
+            .map(|name| name.as_str().ok_or_else(|| format!("{}: type names must be strings", at)))
+            .collect::<Result<_, _>>()?,
+        _ => return Err(format!("{}: must be a string or an array of strings", at)),
+    };
+    names
+        .into_iter()
+        .map(|name| JsonType::parse(name).ok_or_else(|| format!("{}: unknown type '{}'", at, name)))
+        .collect()
+}
+
+fn compile_properties(value: &Value, at: &str) -> Result<BTreeMap<String, JsonSchema>, String> {
+    let Value::Object(properties) = value else {
+        return Err(format!("{}: must be an object", at));
+    };
+    properties
+        .iter()
+        .map(|(key, schema)| {
+            let compiled = JsonSchema::compile(schema, &format!("{}/{}", at, key))?;
+            Ok((key.clone(), compiled))
+        })
+        .collect()
+}
+
+fn parse_required(value: &Value, at: &str) -> Result<Vec<String>, String> {
+    let Value::Array(keys) = value else {
+        return Err(format!("{}: must be an array of strings", at));
+    };
+    keys.iter()
+        .map(|key| {
+            key.as_str()
+                .map(ToString::to_string)
+                .ok_or_else(|| format!("{}: must be an array of strings", at))
+        })
+        .collect()
+}
+
+fn number(value: &Value, at: &str) -> Result<f64, String> {
+    value.as_f64().ok_or_else(|| format!("{}: must be a number", at))
+}
+
+fn count(value: &Value, at: &str) -> Result<u64, String> {
+    value.as_u64().ok_or_else(|| format!("{}: must be a non-negative integer", at))
+}
+
+/// Splits `data` into records: every non-blank line for JSON Lines; for JSON,
+/// the elements of a top-level array, or the document itself otherwise.
+pub fn json_records(data: &str, format: JsonFormat) -> Result<Vec<Value>, String> {
+    match format {
+        JsonFormat::Json => match serde_json::from_str(data) {
+            Ok(Value::Array(records)) => Ok(records),
+            Ok(record) => Ok(vec![record]),
+            Err(err) => Err(format!("Invalid JSON: {}", err)),
+        },
+        JsonFormat::JsonLines => data
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|err| format!("Line {}: invalid JSON: {}", i + 1, err))
+            })
+            .collect(),
+    }
+}
+
+/// Proves that an AES-CTR encrypted JSON or JSON Lines dataset conforms to a
+/// public JSON Schema: the ciphertext is the encryption of the plaintext (as
+/// in `aes_ctr_verify`) and every record validates against the schema. The
+/// output commits the schema text, the record count and the same
+/// `ciphertext_sha256_hex` and `key_sha256_hex` as the other dataset proofs.
+pub fn json_prove(input: JsonConformanceInput) -> JsonConformanceOutput {
+    let mut output = JsonConformanceOutput {
+        is_valid: false,
+        message: String::new(),
+        iv_hex: input.decryption.iv_hex.clone(),
+        key_sha256_hex: String::new(),
+        ciphertext_sha256_hex: String::new(),
+        compression: input.decryption.compression,
+        format: input.format,
+        schema_json: input.schema_json.clone(),
+        record_count: 0,
+    };
+
+    let schema = match JsonSchema::parse(&input.schema_json) {
+        Ok(schema) => schema,
+        Err(err) => return output.fail(&format!("Invalid schema: {}", err)),
+    };
+    match decode(input.decryption.aes_key_hex.expose_secret()) {
+        Ok(key) => output.key_sha256_hex = sha256_hex(&Zeroizing::new(key)),
+        Err(_) => return output.fail("Invalid AES key hex"),
+    }
+    match decode(&input.decryption.ciphertext_hex) {
+        Ok(ciphertext) => output.ciphertext_sha256_hex = sha256_hex(&ciphertext),
+        Err(_) => return output.fail("Invalid ciphertext hex"),
+    }
+    let conformance = json_records(input.decryption.plaintext_utf8.expose_secret(), input.format)
+        .and_then(|records| {
+            for record in &records {
+                schema.check(record, "#").map_err(|at| format!("A record fails {}", at))?;
+            }
+            Ok(records.len() as u32)
+        });
+
+    let (is_valid, message) = aes_ctr_verify(input.decryption);
+    if !is_valid {
+        return output.fail(&message);
+    }
+    match conformance {
+        Ok(record_count) => {
+            output.is_valid = true;
+            output.message = format!("✅ All {} records conform to the JSON Schema", record_count);
+            output.record_count = record_count;
+        }
+        Err(err) => output.message = format!("❌ {}", err),
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn check(schema: Value, value: Value) -> Result<(), String> {
+        JsonSchema::parse(&schema.to_string()).unwrap().check(&value, "#")
+    }
+
+    #[test]
+    fn checks_every_supported_keyword() {
+        // (schema, accepted value, rejected value, failing keyword)
+        let cases = [
+            (json!({"type": "integer"}), json!(3.0), json!(3.5), "#/type"),
+            (json!({"type": ["string", "null"]}), json!(null), json!(1), "#/type"),
+            (json!({"enum": [1, "a"]}), json!(1.0), json!("b"), "#/enum"),
+            (json!({"const": {"a": [1]}}), json!({"a": [1.0]}), json!({"a": [1], "b": 2}), "#/const"),
+            (json!({"minimum": 1}), json!(1), json!(0.5), "#/minimum"),
+            (json!({"maximum": 1}), json!(1), json!(2), "#/maximum"),
+            (json!({"exclusiveMinimum": 1}), json!(1.5), json!(1), "#/exclusiveMinimum"),
+            (json!({"exclusiveMaximum": 1}), json!(0), json!(1), "#/exclusiveMaximum"),
+            (json!({"minLength": 2}), json!("né"), json!("é"), "#/minLength"),
+            (json!({"maxLength": 2}), json!("né"), json!("abc"), "#/maxLength"),
+            (json!({"minItems": 1}), json!([0]), json!([]), "#/minItems"),
+            (json!({"maxItems": 1}), json!([0]), json!([0, 1]), "#/maxItems"),
+            (json!({"items": {"type": "string"}}), json!(["a"]), json!(["a", 1]), "#/items/type"),
+            (json!({"required": ["id"]}), json!({"id": 1}), json!({"name": "x"}), "#/required"),
+            (
+                json!({"properties": {"id": {"type": "integer"}}}),
+                json!({"id": 1, "other": true}),
+                json!({"id": "1"}),
+                "#/properties/id/type",
+            ),
+            (
+                json!({"properties": {"id": true}, "additionalProperties": false}),
+                json!({"id": 1}),
+                json!({"id": 1, "email": "a@b.c"}),
+                "#/additionalProperties",
+            ),
+        ];
+        for (schema, accepted, rejected, keyword) in cases {
+            assert_eq!(check(schema.clone(), accepted), Ok(()), "{}", schema);
+            assert_eq!(check(schema.clone(), rejected).unwrap_err(), keyword, "{}", schema);
+        }
+    }
+
+    #[test]
+    fn numeric_and_length_keywords_ignore_other_types() {
+        let schema = json!({"minimum": 5, "minLength": 5, "minItems": 5, "required": ["a"]});
+        for value in [json!("long enough"), json!(7), json!(null), json!([1, 2, 3, 4, 5])] {
+            assert_eq!(check(schema.clone(), value), Ok(()));
+        }
+    }
+
+    #[test]
+    fn failures_name_the_keyword_but_not_the_record() {
+        let schema = json!({"items": {"properties": {"email": {"maxLength": 3}}}});
+        let err = check(schema, json!([{"email": "secret@example.com"}])).unwrap_err();
+        assert_eq!(err, "#/items/properties/email/maxLength");
+        assert!(!err.contains("secret"));
+    }
+
+    #[test]
+    fn rejects_keywords_outside_the_subset() {
+        let cases = [
+            (json!({"pattern": "^a"}), "#/pattern: unsupported keyword"),
+            (json!({"properties": {"a": {"format": "email"}}}), "#/properties/a/format: unsupported keyword"),
+            (json!({"additionalProperties": {"type": "string"}}), "#/additionalProperties: only true or false is supported"),
+            (json!({"type": "float"}), "#/type: unknown type 'float'"),
+            (json!({"minLength": -1}), "#/minLength: must be a non-negative integer"),
+            (json!({"required": "id"}), "#/required: must be an array of strings"),
+            (json!(false), "#: schema must be an object"),
+        ];
+        for (schema, message) in cases {
+            assert_eq!(JsonSchema::parse(&schema.to_string()).unwrap_err(), message);
+        }
+        let annotated = json!({"$schema": "https://json-schema.org/draft/2020-12/schema", "title": "t", "type": "object"});
+        assert!(JsonSchema::parse(&annotated.to_string()).is_ok());
+    }
+
+    #[test]
+    fn splits_json_and_json_lines_into_records() {
+        assert_eq!(json_records("[1, {\"a\": 2}]", JsonFormat::Json).unwrap(), [json!(1), json!({"a": 2})]);
+        assert_eq!(json_records("{\"a\": 2}", JsonFormat::Json).unwrap(), [json!({"a": 2})]);
+        assert_eq!(json_records("1\n\n  \n[2]\r\n", JsonFormat::JsonLines).unwrap(), [json!(1), json!([2])]);
+        assert!(json_records("1\n{\n", JsonFormat::JsonLines).unwrap_err().starts_with("Line 2: invalid JSON"));
+        assert!(json_records("[1,", JsonFormat::Json).unwrap_err().starts_with("Invalid JSON"));
+    }
+}
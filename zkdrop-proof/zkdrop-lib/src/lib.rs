@@ -3,6 +3,26 @@
 extern crate alloc;
 
 pub mod aes_ctr;
+pub mod aes_gcm;
+pub mod aggregate;
+pub mod batch;
+pub mod chacha;
+pub mod compression;
+pub mod csv;
+pub mod dispute;
+pub mod ecies;
+pub mod eth_encryption;
+pub mod hashlock;
+pub mod image;
+pub mod ipfs;
+pub mod json_schema;
+pub mod lineage;
+pub mod merkle;
+pub mod pii;
+pub mod predicate;
+pub mod preview;
+pub mod rewrap;
 pub mod rsa;
 pub mod types;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
+pub mod validation;
\ No newline at end of file
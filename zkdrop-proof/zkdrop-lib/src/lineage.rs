@@ -0,0 +1,212 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use hex::decode;
+use zeroize::Zeroizing;
+
+use crate::aes_ctr::aes_ctr_verify;
+use crate::types::{
+    AesCtrDecryptionProofInput, LineageProofInput, LineageProofOutput, LineageRelation, ProofOutput,
+    VersionCommitment,
+};
+use crate::utils::sha256_hex;
+
+/// Records of a dataset version: its lines, without line endings. A trailing
+/// line break does not start an empty record.
+fn records(data: &str) -> Vec<&str> {
+    data.lines().collect()
+}
+
+/// Checks that `current` extends `previous` under `relation`.
+pub fn check_lineage(
+    previous: &str,
+    current: &str,
+    relation: LineageRelation,
+) -> Result<(), String> {
+    match relation {
+        LineageRelation::Prefix => {
+            if !previous.is_empty() && !previous.ends_with('\n') {
+                // Otherwise the current version could extend its last record
+                return Err(String::from("Previous version does not end with a line break"));
+            }
+            if !current.starts_with(previous) {
+                return Err(String::from("Previous version is not a prefix of the current version"));
+            }
+        }
+        LineageRelation::RowSubset => {
+            let mut old = records(previous);
+            let mut new = records(current);
+            old.sort_unstable();
+            new.sort_unstable();
+            // Multiset inclusion: every old record is matched by a distinct new one
+            let mut new_iter = new.into_iter();
+            for record in old {
+                if !new_iter.by_ref().any(|candidate| candidate == record) {
+                    return Err(String::from(
+                        "A record of the previous version is missing from the current version",
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Fills in the key and ciphertext hashes of one version.
+fn commit_version(
+    decryption: &AesCtrDecryptionProofInput,
+    commitment: &mut VersionCommitment,
+) -> Result<(), &'static str> {
+    let key = decode(decryption.aes_key_hex.expose_secret()).map_err(|_| "Invalid AES key hex")?;
+    commitment.key_sha256_hex = sha256_hex(&Zeroizing::new(key));
+    let ciphertext = decode(&decryption.ciphertext_hex).map_err(|_| "Invalid ciphertext hex")?;
+    commitment.ciphertext_sha256_hex = sha256_hex(&ciphertext);
+    Ok(())
+}
+
+fn version_commitment(decryption: &AesCtrDecryptionProofInput) -> VersionCommitment {
+    VersionCommitment {
+        iv_hex: decryption.iv_hex.clone(),
+        key_sha256_hex: String::new(),
+        ciphertext_sha256_hex: String::new(),
+        compression: decryption.compression,
+        record_count: 0,
+    }
+}
+
+/// Proves that one encrypted dataset version extends another: both
+/// ciphertexts are AES-CTR encryptions of their plaintexts (as in
+/// `aes_ctr_verify`, keys may differ), and the previous plaintext is a
+/// line-aligned prefix or a multiset of records of the current one. Both
+/// versions are committed by `ciphertext_sha256_hex` and `key_sha256_hex`.
+pub fn lineage_prove(input: LineageProofInput) -> LineageProofOutput {
+    let mut output = LineageProofOutput {
+        is_valid: false,
+        message: String::new(),
+        relation: input.relation,
+        previous: version_commitment(&input.previous),
+        current: version_commitment(&input.current),
+    };
+
+    if let Err(err) = commit_version(&input.previous, &mut output.previous) {
+        return output.fail(&format!("Previous version: {}", err));
+    }
+    if let Err(err) = commit_version(&input.current, &mut output.current) {
+        return output.fail(&format!("Current version: {}", err));
+    }
+    let previous = input.previous.plaintext_utf8.expose_secret();
+    let current = input.current.plaintext_utf8.expose_secret();
+    let lineage = check_lineage(previous, current, input.relation);
+    let record_counts = (records(previous).len() as u32, records(current).len() as u32);
+
+    let (is_valid, message) = aes_ctr_verify(input.previous);
+    if !is_valid {
+        return output.fail(&format!("Previous version: {}", message));
+    }
+    let (is_valid, message) = aes_ctr_verify(input.current);
+    if !is_valid {
+        return output.fail(&format!("Current version: {}", message));
+    }
+    match lineage {
+        Ok(()) => {
+            output.is_valid = true;
+            (output.previous.record_count, output.current.record_count) = record_counts;
+            output.message = String::from(match input.relation {
+                LineageRelation::Prefix => "✅ Current version appends to the previous version",
+                LineageRelation::RowSubset => {
+                    "✅ Current version contains every record of the previous version"
+                }
+            });
+        }
+        Err(err) => output.message = format!("❌ {}", err),
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aes_ctr::aes_ctr_encrypt;
+    use crate::types::{Compression, SecretString};
+
+    const KEY_HEX: &str = "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d";
+
+    fn version(plaintext: &str, iv_hex: &str) -> AesCtrDecryptionProofInput {
+        let key = decode(KEY_HEX).unwrap();
+        let ciphertext = aes_ctr_encrypt(&key, &decode(iv_hex).unwrap(), plaintext.as_bytes()).unwrap();
+        AesCtrDecryptionProofInput {
+            aes_key_hex: SecretString::from(KEY_HEX),
+            iv_hex: String::from(iv_hex),
+            plaintext_utf8: SecretString::from(plaintext),
+            ciphertext_hex: hex::encode(ciphertext),
+            compression: Compression::None,
+        }
+    }
+
+    fn prove(previous: &str, current: &str, relation: LineageRelation) -> LineageProofOutput {
+        lineage_prove(LineageProofInput {
+            previous: version(previous, "01020300000000000000000000000000"),
+            current: version(current, "04050600000000000000000000000000"),
+            relation,
+        })
+    }
+
+    #[test]
+    fn accepts_appended_records() {
+        for (previous, current) in [("a\nb\n", "a\nb\nc\n"), ("a\r\n", "a\r\nb"), ("", "a\n"), ("a\n", "a\n")] {
+            assert_eq!(check_lineage(previous, current, LineageRelation::Prefix), Ok(()), "{:?}", previous);
+        }
+    }
+
+    #[test]
+    fn rejects_prefixes_that_are_not_line_aligned_or_not_prefixes() {
+        let err = |previous, current| check_lineage(previous, current, LineageRelation::Prefix).unwrap_err();
+        // "a\nb" → "a\nbc\n" would silently rewrite the record "b"
+        assert_eq!(err("a\nb", "a\nbc\n"), "Previous version does not end with a line break");
+        assert_eq!(err("a\nx\n", "a\nb\nx\n"), "Previous version is not a prefix of the current version");
+        assert_eq!(err("a\nb\n", "a\n"), "Previous version is not a prefix of the current version");
+    }
+
+    #[test]
+    fn accepts_reordered_supersets_of_records() {
+        let cases = [("a\nb\n", "c\nb\na\n"), ("a\na\nb", "b\na\nc\na\n"), ("a\r\nb\r\n", "b\na"), ("", "a\n")];
+        for (previous, current) in cases {
+            assert_eq!(check_lineage(previous, current, LineageRelation::RowSubset), Ok(()), "{:?}", previous);
+        }
+    }
+
+    #[test]
+    fn rejects_missing_or_fewer_duplicate_records() {
+        let missing = "A record of the previous version is missing from the current version";
+        for (previous, current) in [("a\nb\n", "a\nc\n"), ("a\na\n", "a\nb\n"), ("a\n", "ab\n")] {
+            assert_eq!(check_lineage(previous, current, LineageRelation::RowSubset).unwrap_err(), missing);
+        }
+    }
+
+    #[test]
+    fn commits_record_counts_only_when_the_relation_holds() {
+        let output = prove("id\n1\n", "id\n1\n2\n", LineageRelation::Prefix);
+        assert!(output.is_valid, "{}", output.message);
+        assert_eq!((output.previous.record_count, output.current.record_count), (2, 3));
+        assert_eq!(output.previous.key_sha256_hex, output.current.key_sha256_hex);
+
+        let output = prove("id\n1\n2\n", "id\n2\n", LineageRelation::RowSubset);
+        assert!(!output.is_valid);
+        assert_eq!(output.message, "❌ A record of the previous version is missing from the current version");
+        assert_eq!((output.previous.record_count, output.current.record_count), (0, 0));
+        assert!(!output.current.ciphertext_sha256_hex.is_empty());
+    }
+
+    #[test]
+    fn rejects_a_reused_keystream_unless_the_ciphertext_is_extended() {
+        let iv_hex = "01020300000000000000000000000000";
+        let input = |current| LineageProofInput {
+            previous: version("id\n1\n", iv_hex),
+            current: version(current, iv_hex),
+            relation: LineageRelation::RowSubset,
+        };
+        let errors = input("id\n2\n1\n").validate().unwrap_err();
+        assert_eq!(errors[0].field, "current.iv_hex");
+        assert_eq!(input("id\n1\n2\n").validate(), Ok(()));
+    }
+}
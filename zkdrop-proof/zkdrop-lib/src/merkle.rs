@@ -0,0 +1,126 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+
+/// Domain separation prefix of leaf hashes
+const LEAF_PREFIX: u8 = 0x00;
+
+/// Domain separation prefix of inner node hashes
+const NODE_PREFIX: u8 = 0x01;
+
+/// `SHA-256(0x00 || chunk)`
+pub fn leaf_hash(chunk: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(chunk);
+    hasher.finalize().into()
+}
+
+/// `SHA-256(0x01 || left || right)`
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Root of the binary SHA-256 Merkle tree over `data` split into `chunk_len`
+/// byte chunks (the last one may be shorter). Leaves and inner nodes are
+/// domain-separated; an odd node at the end of a level is promoted unchanged.
+/// Empty data has a single empty leaf. `chunk_len` must be non-zero.
+pub fn merkle_root(data: &[u8], chunk_len: usize) -> [u8; 32] {
+    if data.is_empty() {
+        root_of_leaves(vec![leaf_hash(&[])])
+    } else {
+        root_of_leaves(data.chunks(chunk_len).map(leaf_hash).collect())
+    }
+}
+
+/// Root over already hashed `leaves`, with the same layout as [`merkle_root`].
+/// `leaves` must not be empty.
+pub fn root_of_leaves(mut level: Vec<[u8; 32]>) -> [u8; 32] {
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Sibling hashes from leaf `index` up to the root, bottom first. Levels where
+/// the node is promoted without a sibling contribute nothing, so a verifier
+/// walks the levels using `index` and the leaf count to tell left from right
+/// and to skip promoted nodes.
+pub fn inclusion_proof(leaves: &[[u8; 32]], mut index: usize) -> Vec<[u8; 32]> {
+    let mut proof = Vec::new();
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    proof
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair.get(1) {
+            Some(right) => node_hash(&pair[0], right),
+            None => pair[0],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(hash: [u8; 32]) -> alloc::string::String {
+        hex::encode(hash)
+    }
+
+    #[test]
+    fn leaves_and_nodes_are_domain_separated() {
+        // SHA-256 of the single byte 0x00
+        assert_eq!(hex(leaf_hash(&[])), "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d");
+        assert_eq!(merkle_root(&[], 4), leaf_hash(&[]));
+
+        let (left, right) = (leaf_hash(b"abcd"), leaf_hash(b"efgh"));
+        let mut concatenated = Vec::from(left);
+        concatenated.extend_from_slice(&right);
+        assert_ne!(node_hash(&left, &right), leaf_hash(&concatenated));
+        assert_ne!(node_hash(&left, &right), Sha256::digest(&concatenated).as_slice());
+
+        // A leaf holding two child hashes does not collide with their parent
+        assert_eq!(merkle_root(b"abcdefgh", 4), node_hash(&left, &right));
+        assert_ne!(merkle_root(&concatenated, 64), merkle_root(b"abcdefgh", 4));
+    }
+
+    #[test]
+    fn promotes_an_odd_node_unchanged() {
+        let leaves = [leaf_hash(b"abcd"), leaf_hash(b"efgh"), leaf_hash(b"ij")];
+        let root = node_hash(&node_hash(&leaves[0], &leaves[1]), &leaves[2]);
+        assert_eq!(merkle_root(b"abcdefghij", 4), root);
+        assert_eq!(root_of_leaves(leaves.to_vec()), root);
+
+        // Not Bitcoin-style duplication of the last node
+        let duplicated = node_hash(&node_hash(&leaves[0], &leaves[1]), &node_hash(&leaves[2], &leaves[2]));
+        assert_ne!(root, duplicated);
+        assert_eq!(merkle_root(b"abcd", 4), leaves[0]);
+    }
+
+    #[test]
+    fn inclusion_proofs_skip_promoted_levels() {
+        let leaves: Vec<[u8; 32]> = [b"a", b"b", b"c", b"d", b"e"].iter().map(|c| leaf_hash(*c)).collect();
+        let ab = node_hash(&leaves[0], &leaves[1]);
+        let cd = node_hash(&leaves[2], &leaves[3]);
+
+        assert_eq!(inclusion_proof(&leaves, 0), [leaves[1], cd, leaves[4]]);
+        assert_eq!(inclusion_proof(&leaves, 3), [leaves[2], ab, leaves[4]]);
+        // `e` is promoted twice and only meets a sibling at the top
+        assert_eq!(inclusion_proof(&leaves, 4), [node_hash(&ab, &cd)]);
+        assert_eq!(inclusion_proof(&leaves[..1], 0), Vec::<[u8; 32]>::new());
+    }
+}
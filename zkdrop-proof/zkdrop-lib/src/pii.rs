@@ -0,0 +1,248 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use hex::decode;
+use regex_automata::meta::Regex;
+use zeroize::Zeroizing;
+
+use crate::aes_ctr::aes_ctr_verify;
+use crate::types::{PiiAttestationInput, PiiAttestationOutput, PiiDetector, PiiHits, ProofOutput};
+use crate::utils::sha256_hex;
+
+/// Every built-in detector, in the order hits are committed by default
+pub const PII_DETECTORS: [PiiDetector; 6] = [
+    PiiDetector::Email,
+    PiiDetector::Phone,
+    PiiDetector::Iban,
+    PiiDetector::CreditCard,
+    PiiDetector::Ipv4,
+    PiiDetector::EthAddress,
+];
+
+impl PiiDetector {
+    /// Candidate pattern; candidates are then checked by `is_hit`. Word
+    /// boundaries are ASCII-only so the patterns build without Unicode tables.
+    fn pattern(self) -> &'static str {
+        match self {
+            PiiDetector::Email => concat!(
+                r"(?-u:\b)[A-Za-z0-9._%+-]+@",
+                r"[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}(?-u:\b)",
+            ),
+            // International (`+` prefixed) or North American, `(555) 123-4567`
+            // style or bare `5551234567`
+            PiiDetector::Phone => concat!(
+                r"\+[0-9][0-9 ().-]{6,20}[0-9](?-u:\b)",
+                r"|(?-u:\b)\(?[0-9]{3}\)?[ .-]?[0-9]{3}[ .-]?[0-9]{4}(?-u:\b)",
+            ),
+            PiiDetector::Iban => r"(?-u:\b)[A-Z]{2}[0-9]{2}(?: ?[A-Z0-9]){11,30}(?-u:\b)",
+            PiiDetector::CreditCard => r"(?-u:\b)(?:[0-9][ -]?){12,18}[0-9](?-u:\b)",
+            PiiDetector::Ipv4 => r"(?-u:\b)[0-9]{1,3}(?:\.[0-9]{1,3}){3}(?-u:\b)",
+            PiiDetector::EthAddress => r"(?-u:\b)0x[0-9a-fA-F]{40}(?-u:\b)",
+        }
+    }
+
+    /// Checks a candidate match: digit counts for phone numbers, the ISO 13616
+    /// check digits for IBANs, the Luhn checksum for card numbers and octet
+    /// ranges for IPv4 addresses.
+    fn is_hit(self, candidate: &str) -> bool {
+        match self {
+            PiiDetector::Email | PiiDetector::EthAddress => true,
+            PiiDetector::Phone => {
+                let digits = candidate.bytes().filter(u8::is_ascii_digit).count();
+                (10..=15).contains(&digits)
+            }
+            PiiDetector::Iban => iban_is_valid(candidate),
+            PiiDetector::CreditCard => luhn_is_valid(candidate),
+            PiiDetector::Ipv4 => candidate.split('.').all(|octet| octet.parse::<u8>().is_ok()),
+        }
+    }
+}
+
+/// ISO 13616 IBAN check: 15 to 34 characters whose rearranged digits are 1 mod 97.
+fn iban_is_valid(candidate: &str) -> bool {
+    let iban: Vec<u8> = candidate.bytes().filter(|b| *b != b' ').collect();
+    if !(15..=34).contains(&iban.len()) {
+        return false;
+    }
+    let mut remainder: u32 = 0;
+    for &b in iban[4..].iter().chain(&iban[..4]) {
+        let value = match b {
+            b'0'..=b'9' => (b - b'0') as u32,
+            b'A'..=b'Z' => (b - b'A') as u32 + 10,
+            _ => return false,
+        };
+        let scale = if value < 10 { 10 } else { 100 };
+        remainder = (remainder * scale + value) % 97;
+    }
+    remainder == 1
+}
+
+/// Luhn checksum over the digits of `candidate` (13 to 19 digits).
+fn luhn_is_valid(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let checksum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2 == 1, d * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => d,
+        })
+        .sum::<u32>()
+        % 10;
+    checksum == 0
+}
+
+/// Counts the hits of each detector over `text`.
+pub fn pii_scan(text: &str, detectors: &[PiiDetector]) -> Result<Vec<PiiHits>, String> {
+    detectors
+        .iter()
+        .map(|&detector| {
+            let regex = Regex::new(detector.pattern())
+                .map_err(|err| format!("Detector {:?}: {}", detector, err))?;
+            let count = regex
+                .find_iter(text)
+                .filter(|m| detector.is_hit(&text[m.range()]))
+                .count() as u32;
+            Ok(PiiHits { detector, count })
+        })
+        .collect()
+}
+
+/// Proves a PII scan of an AES-CTR encrypted dataset: the ciphertext is the
+/// encryption of the plaintext (as in `aes_ctr_verify`) and the committed hit
+/// counts come from running the built-in detectors over it. `pii_free` is set
+/// only when every built-in detector ran and none fired; a scan restricted to
+/// some detectors commits its hits but never claims the dataset is PII-free.
+pub fn pii_prove(input: PiiAttestationInput) -> PiiAttestationOutput {
+    let mut output = PiiAttestationOutput {
+        is_valid: false,
+        message: String::new(),
+        iv_hex: input.decryption.iv_hex.clone(),
+        key_sha256_hex: String::new(),
+        ciphertext_sha256_hex: String::new(),
+        compression: input.decryption.compression,
+        pii_free: false,
+        hits: Vec::new(),
+    };
+
+    match decode(input.decryption.aes_key_hex.expose_secret()) {
+        Ok(key) => output.key_sha256_hex = sha256_hex(&Zeroizing::new(key)),
+        Err(_) => return output.fail("Invalid AES key hex"),
+    }
+    match decode(&input.decryption.ciphertext_hex) {
+        Ok(ciphertext) => output.ciphertext_sha256_hex = sha256_hex(&ciphertext),
+        Err(_) => return output.fail("Invalid ciphertext hex"),
+    }
+    let hits = match pii_scan(input.decryption.plaintext_utf8.expose_secret(), &input.detectors) {
+        Ok(hits) => hits,
+        Err(err) => return output.fail(&err),
+    };
+
+    let all_detectors_ran = PII_DETECTORS.iter().all(|d| input.detectors.contains(d));
+
+    let (is_valid, message) = aes_ctr_verify(input.decryption);
+    if !is_valid {
+        return output.fail(&message);
+    }
+    output.is_valid = true;
+    let no_hits = hits.iter().all(|h| h.count == 0);
+    output.pii_free = all_detectors_ran && no_hits;
+    output.message = String::from(match (no_hits, all_detectors_ran) {
+        (true, true) => "✅ No PII detected in the AES-CTR encrypted dataset",
+        (true, false) => "✅ The selected detectors found no PII in the AES-CTR encrypted dataset",
+        (false, _) => "⚠️ PII detected in the AES-CTR encrypted dataset",
+    });
+    output.hits = hits;
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aes_ctr::aes_ctr_encrypt;
+    use crate::types::{AesCtrDecryptionProofInput, Compression, SecretString};
+
+    const KEY_HEX: &str = "de15a7f6957c3eb9a86689106a98e3bea6f4b7222a63aa0ba7afda647d2ff98d";
+    const IV_HEX: &str = "01020300000000000000000000000000";
+
+    fn hits(detector: PiiDetector, text: &str) -> u32 {
+        pii_scan(text, &[detector]).unwrap()[0].count
+    }
+
+    fn attest(plaintext: &str, detectors: &[PiiDetector]) -> PiiAttestationOutput {
+        let ciphertext =
+            aes_ctr_encrypt(&decode(KEY_HEX).unwrap(), &decode(IV_HEX).unwrap(), plaintext.as_bytes()).unwrap();
+        pii_prove(PiiAttestationInput {
+            decryption: AesCtrDecryptionProofInput {
+                aes_key_hex: SecretString::from(KEY_HEX),
+                iv_hex: String::from(IV_HEX),
+                plaintext_utf8: SecretString::from(plaintext),
+                ciphertext_hex: hex::encode(ciphertext),
+                compression: Compression::None,
+            },
+            detectors: detectors.to_vec(),
+        })
+    }
+
+    #[test]
+    fn detects_emails() {
+        assert_eq!(hits(PiiDetector::Email, "to alice.smith+data@example.co.uk, bob@mail.io"), 2);
+        assert_eq!(hits(PiiDetector::Email, "alice@localhost, a@b.c, @example.com"), 0);
+    }
+
+    #[test]
+    fn detects_phone_numbers_with_10_to_15_digits() {
+        assert_eq!(hits(PiiDetector::Phone, "+44 20 7946 0958; (555) 123-4567; 5551234567"), 3);
+        assert_eq!(hits(PiiDetector::Phone, "+12 345 678; 555-1234; 2024-01-15"), 0);
+    }
+
+    #[test]
+    fn detects_ibans_with_valid_check_digits() {
+        assert_eq!(hits(PiiDetector::Iban, "GB82 WEST 1234 5698 7654 32"), 1);
+        assert_eq!(hits(PiiDetector::Iban, "DE89370400440532013000"), 1);
+        assert_eq!(hits(PiiDetector::Iban, "GB82WEST12345698765433"), 0);
+        assert_eq!(hits(PiiDetector::Iban, "DE8937040044"), 0);
+    }
+
+    #[test]
+    fn detects_card_numbers_passing_luhn() {
+        assert_eq!(hits(PiiDetector::CreditCard, "4111 1111 1111 1111 / 4111-1111-1111-1111"), 2);
+        assert_eq!(hits(PiiDetector::CreditCard, "4111 1111 1111 1112"), 0);
+        // Luhn-valid but too short for a card
+        assert_eq!(hits(PiiDetector::CreditCard, "4111 1111 1111"), 0);
+    }
+
+    #[test]
+    fn detects_ipv4_addresses_with_valid_octets() {
+        assert_eq!(hits(PiiDetector::Ipv4, "from 192.168.0.1 and 10.0.0.255"), 2);
+        assert_eq!(hits(PiiDetector::Ipv4, "256.1.1.1, 1.2.3, v1.2.3.4000"), 0);
+    }
+
+    #[test]
+    fn detects_eth_addresses() {
+        assert_eq!(hits(PiiDetector::EthAddress, "paid 0x52908400098527886E0F7030069857D2E4169EE7"), 1);
+        assert_eq!(hits(PiiDetector::EthAddress, "0x52908400098527886E0F7030069857D2E4169EE"), 0);
+        assert_eq!(hits(PiiDetector::EthAddress, "0x52908400098527886E0F7030069857D2E4169EE7a"), 0);
+    }
+
+    #[test]
+    fn only_a_full_scan_without_hits_is_pii_free() {
+        let clean = "id,score\n1,0.5\n2,0.75\n";
+        let output = attest(clean, &PII_DETECTORS);
+        assert!(output.is_valid, "{}", output.message);
+        assert!(output.pii_free);
+        assert_eq!(output.hits.len(), PII_DETECTORS.len());
+
+        let output = attest(clean, &[PiiDetector::Email]);
+        assert!(output.is_valid && !output.pii_free);
+
+        let output = attest("id,email\n1,alice@example.com\n", &PII_DETECTORS);
+        assert!(output.is_valid && !output.pii_free);
+        assert_eq!(output.hits[0], PiiHits { detector: PiiDetector::Email, count: 1 });
+    }
+}
@@ -0,0 +1,95 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use hex::decode;
+use regex_automata::meta::Regex;
+use regex_automata::util::syntax;
+use zeroize::Zeroizing;
+
+use crate::aes_ctr::aes_ctr_verify;
+use crate::types::{
+    Predicate, PredicateMode, PredicateProofInput, PredicateProofOutput, PredicateResult,
+    ProofOutput,
+};
+use crate::utils::sha256_hex;
+
+/// Compiles `predicate`, escaping it first when it is a literal keyword.
+/// Patterns that can match the empty string are rejected, since their match
+/// count would depend on the plaintext length rather than its content.
+pub fn compile_predicate(predicate: &Predicate) -> Result<Regex, String> {
+    let pattern = if predicate.literal {
+        regex_syntax::escape(&predicate.pattern)
+    } else {
+        predicate.pattern.clone()
+    };
+    let hir = syntax::parse(&pattern).map_err(|err| format!("invalid regex: {}", err))?;
+    if hir.properties().minimum_len() == Some(0) {
+        return Err(String::from("must not match the empty string"));
+    }
+    Regex::new(&pattern).map_err(|err| format!("invalid regex: {}", err))
+}
+
+/// Proves predicates over an AES-CTR encrypted dataset: the ciphertext is the
+/// encryption of the plaintext (as in `aes_ctr_verify`), and each committed
+/// pattern was evaluated over the whole plaintext. `count` predicates commit
+/// the number of non-overlapping matches, `presence` predicates only whether
+/// there is one. Bound to the dataset by `ciphertext_sha256_hex` and `key_sha256_hex`.
+pub fn predicate_prove(input: PredicateProofInput) -> PredicateProofOutput {
+    let mut output = PredicateProofOutput {
+        is_valid: false,
+        message: String::new(),
+        iv_hex: input.decryption.iv_hex.clone(),
+        key_sha256_hex: String::new(),
+        ciphertext_sha256_hex: String::new(),
+        compression: input.decryption.compression,
+        results: Vec::new(),
+    };
+
+    let mut regexes = Vec::with_capacity(input.predicates.len());
+    for (i, predicate) in input.predicates.iter().enumerate() {
+        match compile_predicate(predicate) {
+            Ok(regex) => regexes.push(regex),
+            Err(err) => return output.fail(&format!("Predicate {}: {}", i, err)),
+        }
+    }
+    match decode(input.decryption.aes_key_hex.expose_secret()) {
+        Ok(key) => output.key_sha256_hex = sha256_hex(&Zeroizing::new(key)),
+        Err(_) => return output.fail("Invalid AES key hex"),
+    }
+    match decode(&input.decryption.ciphertext_hex) {
+        Ok(ciphertext) => output.ciphertext_sha256_hex = sha256_hex(&ciphertext),
+        Err(_) => return output.fail("Invalid ciphertext hex"),
+    }
+
+    let plaintext = input.decryption.plaintext_utf8.expose_secret();
+    let results = input
+        .predicates
+        .iter()
+        .zip(&regexes)
+        .map(|(predicate, regex)| {
+            let (match_count, matched) = match predicate.mode {
+                PredicateMode::Count => {
+                    let count = regex.find_iter(plaintext).count() as u32;
+                    (Some(count), count > 0)
+                }
+                PredicateMode::Presence => (None, regex.is_match(plaintext)),
+            };
+            PredicateResult {
+                pattern: predicate.pattern.clone(),
+                literal: predicate.literal,
+                mode: predicate.mode,
+                match_count,
+                matched,
+            }
+        })
+        .collect();
+
+    let (is_valid, message) = aes_ctr_verify(input.decryption);
+    if !is_valid {
+        return output.fail(&message);
+    }
+    output.is_valid = true;
+    output.message = String::from("✅ Predicates evaluated over the AES-CTR encrypted dataset");
+    output.results = results;
+    output
+}
@@ -0,0 +1,126 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+use hex::decode;
+use zeroize::Zeroizing;
+
+use crate::aes_ctr::aes_ctr_verify;
+use crate::merkle::merkle_root;
+use crate::types::{
+    PreviewSelection, ProofOutput, RevealedExcerpt, SamplePreviewInput, SamplePreviewOutput,
+};
+use crate::utils::sha256_hex;
+use crate::validation::{MAX_PAYLOAD_LEN, MAX_PREVIEW_LEN};
+
+/// Merkle chunk length used when a preview request does not set one
+pub const PREVIEW_DEFAULT_CHUNK_LEN: u32 = 1024;
+
+/// Byte ranges of a plaintext of `plaintext_len` bytes revealed by `selection`.
+/// Fails if the selection is empty, out of bounds, unordered or reveals more
+/// than [`MAX_PREVIEW_LEN`] bytes.
+pub fn selected_ranges(
+    selection: &PreviewSelection,
+    chunk_len: u32,
+    plaintext_len: usize,
+) -> Result<Vec<Range<usize>>, String> {
+    let chunk_len = chunk_len as usize;
+    if chunk_len == 0 || chunk_len > MAX_PAYLOAD_LEN {
+        return Err(format!("chunk_len must be between 1 and {} bytes", MAX_PAYLOAD_LEN));
+    }
+
+    let ranges = match selection {
+        PreviewSelection::Range { offset, len } => {
+            let start = *offset as usize;
+            let end = start.saturating_add(*len as usize);
+            if *len == 0 || end > plaintext_len {
+                return Err(format!(
+                    "range must be non-empty and within the {} byte plaintext",
+                    plaintext_len
+                ));
+            }
+            alloc::vec![start..end]
+        }
+        PreviewSelection::Chunks { indices } => {
+            let chunk_count = plaintext_len.div_ceil(chunk_len);
+            if indices.is_empty() {
+                return Err(String::from("indices must not be empty"));
+            }
+            if !indices.windows(2).all(|pair| pair[0] < pair[1]) {
+                return Err(String::from("indices must be strictly increasing"));
+            }
+            if let Some(index) = indices.iter().find(|&&i| i as usize >= chunk_count) {
+                return Err(format!("chunk {} is out of range ({} chunks)", index, chunk_count));
+            }
+            indices
+                .iter()
+                .map(|&i| {
+                    let start = i as usize * chunk_len;
+                    start..plaintext_len.min(start + chunk_len)
+                })
+                .collect()
+        }
+    };
+
+    let revealed: usize = ranges.iter().map(|r| r.len()).sum();
+    if revealed > MAX_PREVIEW_LEN {
+        return Err(format!("must reveal at most {} bytes, got {}", MAX_PREVIEW_LEN, revealed));
+    }
+    Ok(ranges)
+}
+
+/// Proves a sample preview: the ciphertext is the AES-CTR encryption of the
+/// plaintext (as in `aes_ctr_verify`), and the revealed excerpts are the
+/// selected bytes of that plaintext.
+///
+/// The output commits the ciphertext both as a SHA-256 hash and as the root of
+/// a chunked Merkle tree (see [`merkle_root`]), with the key hash, so the
+/// preview is bound to the dataset the buyer later pays for. Nothing outside
+/// the selection is revealed.
+pub fn preview_prove(input: SamplePreviewInput) -> SamplePreviewOutput {
+    let mut output = SamplePreviewOutput {
+        is_valid: false,
+        message: String::new(),
+        iv_hex: input.decryption.iv_hex.clone(),
+        key_sha256_hex: String::new(),
+        ciphertext_sha256_hex: String::new(),
+        compression: input.decryption.compression,
+        ciphertext_merkle_root_hex: String::new(),
+        chunk_len: input.chunk_len,
+        plaintext_len: 0,
+        revealed: Vec::new(),
+    };
+
+    let plaintext = input.decryption.plaintext_utf8.expose_secret().as_bytes();
+    let ranges = match selected_ranges(&input.selection, input.chunk_len, plaintext.len()) {
+        Ok(ranges) => ranges,
+        Err(err) => return output.fail(&err),
+    };
+    match decode(input.decryption.aes_key_hex.expose_secret()) {
+        Ok(key) => output.key_sha256_hex = sha256_hex(&Zeroizing::new(key)),
+        Err(_) => return output.fail("Invalid AES key hex"),
+    }
+    let ciphertext = match decode(&input.decryption.ciphertext_hex) {
+        Ok(c) => c,
+        Err(_) => return output.fail("Invalid ciphertext hex"),
+    };
+    output.plaintext_len = plaintext.len() as u32;
+    output.ciphertext_sha256_hex = sha256_hex(&ciphertext);
+    output.ciphertext_merkle_root_hex = hex::encode(merkle_root(&ciphertext, input.chunk_len as usize));
+    let revealed = ranges
+        .into_iter()
+        .map(|range| RevealedExcerpt {
+            offset: range.start as u32,
+            bytes_hex: hex::encode(&plaintext[range]),
+        })
+        .collect();
+
+    let (is_valid, message) = aes_ctr_verify(input.decryption);
+    if !is_valid {
+        return output.fail(&message);
+    }
+    output.is_valid = true;
+    output.message = String::from("✅ Preview excerpts come from the AES-CTR encrypted dataset");
+    output.revealed = revealed;
+    output
+}
@@ -0,0 +1,85 @@
+use alloc::string::String;
+use base64::{engine::general_purpose, Engine as _};
+use hex::decode;
+use rsa::{pkcs8::DecodePrivateKey, RsaPrivateKey};
+use zeroize::Zeroizing;
+
+use crate::batch::wrap;
+use crate::hashlock::key_hash_hex;
+use crate::rsa::{oaep_decrypt, rsa_pubkey_sha256_hex, wrap_rng};
+use crate::types::{KeyCommitment, ProofOutput, RewrapInput, RewrapOutput, RewrapSource};
+use crate::validation::AES_KEY_LEN;
+
+/// Proves a licence transfer: the AES key held by the current owner (unwrapped
+/// from `enc_aes_key_hex` with their RSA private key) or by the seller is
+/// wrapped to the new owner's key. The journal commits `H(key)`, which a
+/// verifier compares with the listing's key commitment, the old and new public
+/// key hashes and the new wrapped key. The key itself is never committed.
+pub fn rewrap_prove(input: RewrapInput) -> RewrapOutput {
+    let mut output = RewrapOutput {
+        is_valid: false,
+        message: String::new(),
+        key_commitment: KeyCommitment { digest: input.key_digest, key_hash_hex: String::new() },
+        old_pubkey_sha256_hex: None,
+        old_enc_aes_key_hex: None,
+        old_oaep: None,
+        new_pubkey_sha256_hex: String::new(),
+        new_enc_aes_key_hex: String::new(),
+        oaep: input.oaep.clone(),
+    };
+
+    let key = match &input.source {
+        RewrapSource::Owner { rsa_private_key_base64, enc_aes_key_hex, oaep } => {
+            output.old_enc_aes_key_hex = Some(enc_aes_key_hex.clone());
+            output.old_oaep = Some(oaep.clone());
+            let private_key = match general_purpose::STANDARD
+                .decode(rsa_private_key_base64.expose_secret())
+                .map(Zeroizing::new)
+            {
+                Ok(der) => match RsaPrivateKey::from_pkcs8_der(&der) {
+                    Ok(key) => key,
+                    Err(_) => return output.fail("RSA private key parse error"),
+                },
+                Err(_) => return output.fail("Invalid RSA private key base64"),
+            };
+            match rsa_pubkey_sha256_hex(&private_key.to_public_key()) {
+                Some(hash) => output.old_pubkey_sha256_hex = Some(hash),
+                None => return output.fail("RSA pubkey encoding error"),
+            }
+            if let Err(err) = oaep.label() {
+                return output.fail(err);
+            }
+            let enc_aes_key = match decode(enc_aes_key_hex) {
+                Ok(bytes) => bytes,
+                Err(_) => return output.fail("Invalid encrypted AES key hex"),
+            };
+            match oaep_decrypt(&private_key, oaep, &enc_aes_key) {
+                Ok(key) => key,
+                Err(_) => return output.fail("❌ Wrapped key does not decrypt under the owner key"),
+            }
+        }
+        RewrapSource::Seller { aes_key_hex } => match decode(aes_key_hex.expose_secret()) {
+            Ok(key) => Zeroizing::new(key),
+            Err(_) => return output.fail("Invalid AES key hex"),
+        },
+    };
+    if key.len() != AES_KEY_LEN {
+        return output.fail("❌ Wrapped key is not an AES-256 key");
+    }
+    output.key_commitment.key_hash_hex = key_hash_hex(input.key_digest, &key);
+
+    let mut rng = match wrap_rng(input.wrap_seed_hex.expose_secret()) {
+        Ok(rng) => rng,
+        Err(err) => return output.fail(err),
+    };
+    match wrap(&input.recipient, &key, &input.oaep, &mut rng) {
+        Ok(wrapped) => {
+            output.is_valid = true;
+            output.message = String::from("✅ AES key re-wrapped to the new owner");
+            output.new_pubkey_sha256_hex = wrapped.pubkey_sha256_hex;
+            output.new_enc_aes_key_hex = wrapped.enc_aes_key_hex;
+        }
+        Err(err) => output.message = String::from(err),
+    }
+    output
+}
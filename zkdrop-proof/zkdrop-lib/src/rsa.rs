@@ -1,73 +1,252 @@
-use rsa::{pkcs8::DecodePublicKey, Oaep, RsaPublicKey};
-use sha2::Sha256;
+use rsa::{
+    hazmat,
+    pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePublicKey},
+    traits::PublicKeyParts,
+    BigUint, RsaPrivateKey, RsaPublicKey,
+};
+use sha1::Sha1;
+use sha2::digest::{Digest, DynDigest};
+use sha2::{Sha256, Sha384, Sha512};
 use base64::{engine::general_purpose, Engine as _};
 use hex::{decode as hex_decode};
 use rand_chacha::ChaCha20Rng;
-use rand_chacha::rand_core::{SeedableRng};
-use crate::types::{RsaEncryptedAesKeyInput, RsaEncryptAesKeyInput};
+use rand_chacha::rand_core::{RngCore, SeedableRng};
+use crate::types::{OaepDigest, OaepParams, RsaEncryptedAesKeyInput, RsaEncryptAesKeyInput, RsaWrapWitness};
+use alloc::boxed::Box;
 use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use zeroize::Zeroizing;
+use crate::utils::sha256_hex;
 
-// ECIES keep that in mind for future work
-pub fn rsa_verify(input: RsaEncryptedAesKeyInput) -> (bool, String) {
-    let aes_key_bytes = match hex_decode(&input.aes_key_hex) {
-        Ok(bytes) => bytes,
-        Err(_) => return (false, String::from("Invalid AES key hex")),
+/// Length of the private seed OAEP seeds are drawn from
+pub const WRAP_SEED_LEN: usize = 32;
+
+/// SHA-256 (hex) of the key's canonical SPKI DER encoding. Journals commit to
+/// this so a buyer can check which key was used whatever encoding they submitted.
+pub fn rsa_pubkey_sha256_hex(pubkey: &RsaPublicKey) -> Option<String> {
+    let der = pubkey.to_public_key_der().ok()?;
+    Some(sha256_hex(der.as_bytes()))
+}
+
+fn dyn_digest(digest: OaepDigest) -> Box<dyn DynDigest + Send + Sync> {
+    match digest {
+        OaepDigest::Sha1 => Box::new(Sha1::new()),
+        OaepDigest::Sha256 => Box::new(Sha256::new()),
+        OaepDigest::Sha384 => Box::new(Sha384::new()),
+        OaepDigest::Sha512 => Box::new(Sha512::new()),
+    }
+}
+
+/// MGF1 (RFC 8017, B.2.1): XORs `out` with the mask generated from `seed`.
+fn mgf1_xor(out: &mut [u8], digest: &mut dyn DynDigest, seed: &[u8]) {
+    let chunk_len = digest.output_size();
+    for (counter, chunk) in out.chunks_mut(chunk_len).enumerate() {
+        digest.update(seed);
+        digest.update(&(counter as u32).to_be_bytes());
+        for (byte, mask) in chunk.iter_mut().zip(digest.finalize_reset().iter()) {
+            *byte ^= mask;
+        }
+    }
+}
+
+/// Big-endian bytes of `value`, left-padded to `len`.
+fn to_bytes_be_padded(value: &BigUint, len: usize) -> Option<Zeroizing<Vec<u8>>> {
+    let bytes = Zeroizing::new(value.to_bytes_be());
+    let pad = len.checked_sub(bytes.len())?;
+    let mut padded = Zeroizing::new(vec![0u8; len]);
+    padded[pad..].copy_from_slice(&bytes);
+    Some(padded)
+}
+
+/// RSA-OAEP encryption (RFC 8017, 7.1.1) with a caller-supplied `seed`, which
+/// must be as long as the OAEP digest. Implemented here rather than through
+/// `rsa::Oaep` so the label can be arbitrary bytes and the seed a witness.
+pub fn oaep_encrypt(
+    pubkey: &RsaPublicKey,
+    params: &OaepParams,
+    seed: &[u8],
+    msg: &[u8],
+) -> Result<Vec<u8>, &'static str> {
+    let label = params.label()?;
+    let mut digest = dyn_digest(params.digest);
+    let mut mgf_digest = dyn_digest(params.mgf_digest);
+    let h_len = digest.output_size();
+    let k = pubkey.size();
+    if seed.len() != h_len {
+        return Err("OAEP seed must be as long as the OAEP digest");
+    }
+    if msg.len() + 2 * h_len + 2 > k {
+        return Err("Message too long for the RSA key");
+    }
+
+    // EM = 0x00 || maskedSeed || maskedDB, with DB = lHash || PS || 0x01 || M
+    let mut em = Zeroizing::new(vec![0u8; k]);
+    let (masked_seed, db) = em[1..].split_at_mut(h_len);
+    masked_seed.copy_from_slice(seed);
+    digest.update(&label);
+    db[..h_len].copy_from_slice(&digest.finalize_reset());
+    let msg_start = db.len() - msg.len();
+    db[msg_start - 1] = 1;
+    db[msg_start..].copy_from_slice(msg);
+    mgf1_xor(db, mgf_digest.as_mut(), masked_seed);
+    mgf1_xor(masked_seed, mgf_digest.as_mut(), db);
+
+    let c = hazmat::rsa_encrypt(pubkey, &BigUint::from_bytes_be(&em)).map_err(|_| "Encryption failed")?;
+    to_bytes_be_padded(&c, k).map(|c| c.to_vec()).ok_or("Encryption failed")
+}
+
+/// RSA-OAEP decryption (RFC 8017, 7.1.2) matching [`oaep_encrypt`]. Every
+/// padding failure returns the same error.
+pub fn oaep_decrypt(
+    private_key: &RsaPrivateKey,
+    params: &OaepParams,
+    ciphertext: &[u8],
+) -> Result<Zeroizing<Vec<u8>>, &'static str> {
+    const FAILED: &str = "Decryption failed";
+    let label = params.label()?;
+    let mut digest = dyn_digest(params.digest);
+    let mut mgf_digest = dyn_digest(params.mgf_digest);
+    let h_len = digest.output_size();
+    let k = private_key.size();
+    if ciphertext.len() != k || k < 2 * h_len + 2 {
+        return Err(FAILED);
+    }
+
+    let m = hazmat::rsa_decrypt_and_check(
+        private_key,
+        None::<&mut ChaCha20Rng>,
+        &BigUint::from_bytes_be(ciphertext),
+    )
+    .map_err(|_| FAILED)?;
+    let mut em = to_bytes_be_padded(&m, k).ok_or(FAILED)?;
+    let (first, rest) = em.split_at_mut(1);
+    let (masked_seed, db) = rest.split_at_mut(h_len);
+    mgf1_xor(masked_seed, mgf_digest.as_mut(), db);
+    mgf1_xor(db, mgf_digest.as_mut(), masked_seed);
+
+    digest.update(&label);
+    let label_matches = db[..h_len] == *digest.finalize_reset();
+    let separator = db[h_len..].iter().position(|&byte| byte != 0);
+    match separator {
+        Some(index) if first[0] == 0 && label_matches && db[h_len + index] == 1 => {
+            Ok(Zeroizing::new(db[h_len + index + 1..].to_vec()))
+        }
+        _ => Err(FAILED),
+    }
+}
+
+/// Creates the generator OAEP seeds are drawn from, seeded with the private
+/// `wrap_seed_hex` the host draws from its OS RNG for every proof.
+pub fn wrap_rng(wrap_seed_hex: &str) -> Result<ChaCha20Rng, &'static str> {
+    let seed = Zeroizing::new(hex_decode(wrap_seed_hex).map_err(|_| "Invalid wrap seed hex")?);
+    let seed: [u8; WRAP_SEED_LEN] = seed.as_slice().try_into().map_err(|_| "Invalid wrap seed length")?;
+    Ok(ChaCha20Rng::from_seed(seed))
+}
+
+/// Wraps `secret` to a base64 SPKI DER RSA key with OAEP, drawing the OAEP seed
+/// from `rng`. Shared by `rsa_encrypt`, batch and rewrap; returns the ciphertext
+/// and the key hash from [`rsa_pubkey_sha256_hex`].
+pub fn rsa_wrap(
+    rsa_pubkey_base64: &str,
+    secret: &[u8],
+    oaep: &OaepParams,
+    rng: &mut ChaCha20Rng,
+) -> Result<(Vec<u8>, String), &'static str> {
+    let der = general_purpose::STANDARD.decode(rsa_pubkey_base64).map_err(|_| "Invalid RSA pubkey base64")?;
+    let pubkey = RsaPublicKey::from_public_key_der(&der).map_err(|_| "RSA pubkey parse error")?;
+    let pubkey_hash = rsa_pubkey_sha256_hex(&pubkey).ok_or("RSA pubkey encoding error")?;
+    let mut seed = Zeroizing::new(vec![0u8; oaep.digest.output_len()]);
+    rng.fill_bytes(&mut seed);
+    let wrapped = oaep_encrypt(&pubkey, oaep, &seed, secret)?;
+    Ok((wrapped, pubkey_hash))
+}
+
+pub fn rsa_verify(input: RsaEncryptedAesKeyInput) -> (bool, String, String, OaepParams) {
+    let aes_key_bytes = match hex_decode(input.aes_key_hex.expose_secret()) {
+        Ok(bytes) => Zeroizing::new(bytes),
+        Err(_) => return (false, String::from("Invalid AES key hex"), String::new(), input.oaep),
     };
 
     let rsa_pubkey_der = match general_purpose::STANDARD.decode(&input.rsa_pubkey_base64) {
         Ok(der) => der,
-        Err(_) => return (false, String::from("Invalid RSA pubkey base64")),
+        Err(_) => return (false, String::from("Invalid RSA pubkey base64"), String::new(), input.oaep),
     };
 
     let enc_aes_key_bytes = match hex_decode(&input.enc_aes_key_hex) {
         Ok(bytes) => bytes,
-        Err(_) => return (false, String::from("Invalid encrypted AES key hex")),
+        Err(_) => return (false, String::from("Invalid encrypted AES key hex"), String::new(), input.oaep),
     };
 
     let pubkey = match RsaPublicKey::from_public_key_der(&rsa_pubkey_der) {
         Ok(key) => key,
-        Err(_) => return (false, String::from("RSA pubkey parse error")),
+        Err(_) => return (false, String::from("RSA pubkey parse error"), String::new(), input.oaep),
     };
 
-    let mut rng = ChaCha20Rng::seed_from_u64(42);
-    let padding = Oaep::new::<Sha256>();
-    let enc_result = match pubkey.encrypt(&mut rng, padding, &aes_key_bytes) {
-        Ok(data) => data,
-        Err(_) => return (false, String::from("Encryption failed")),
+    let pubkey_hash = match rsa_pubkey_sha256_hex(&pubkey) {
+        Some(hash) => hash,
+        None => return (false, String::from("RSA pubkey encoding error"), String::new(), input.oaep),
     };
-   
-    if enc_result == enc_aes_key_bytes {
-        (true, String::from("✅ RSA encryption matches"))
+
+    // OAEP is randomised, so the wrapping is proven from a private witness: either
+    // the seed it was drawn with, or the recipient key that unwraps it.
+    let matches = match &input.witness {
+        RsaWrapWitness::OaepSeedHex(seed_hex) => {
+            let seed = match hex_decode(seed_hex.expose_secret()) {
+                Ok(bytes) => Zeroizing::new(bytes),
+                Err(_) => return (false, String::from("Invalid OAEP seed hex"), pubkey_hash, input.oaep),
+            };
+            match oaep_encrypt(&pubkey, &input.oaep, &seed, &aes_key_bytes) {
+                Ok(enc_result) => enc_result == enc_aes_key_bytes,
+                Err(err) => return (false, String::from(err), pubkey_hash, input.oaep),
+            }
+        }
+        RsaWrapWitness::RsaPrivateKeyBase64(private_key_base64) => {
+            let private_key = match general_purpose::STANDARD
+                .decode(private_key_base64.expose_secret())
+                .map(Zeroizing::new)
+            {
+                Ok(der) => match RsaPrivateKey::from_pkcs8_der(&der) {
+                    Ok(key) => key,
+                    Err(_) => return (false, String::from("RSA private key parse error"), pubkey_hash, input.oaep),
+                },
+                Err(_) => return (false, String::from("Invalid RSA private key base64"), pubkey_hash, input.oaep),
+            };
+            if private_key.to_public_key() != pubkey {
+                return (false, String::from("RSA private key does not match the pubkey"), pubkey_hash, input.oaep);
+            }
+            if let Err(err) = input.oaep.label() {
+                return (false, String::from(err), pubkey_hash, input.oaep);
+            }
+            match oaep_decrypt(&private_key, &input.oaep, &enc_aes_key_bytes) {
+                Ok(key) => key.as_slice() == aes_key_bytes.as_slice(),
+                Err(_) => false,
+            }
+        }
+    };
+
+    if matches {
+        (true, String::from("✅ RSA encryption matches"), pubkey_hash, input.oaep)
     } else {
-        (false, String::from("❌ Mismatch in RSA encryption"))
+        (false, String::from("❌ Mismatch in RSA encryption"), pubkey_hash, input.oaep)
     }
 }
 
-pub fn rsa_encrypt(input: RsaEncryptAesKeyInput) -> (bool, String, String) {
-    let aes_key_bytes = match hex_decode(&input.aes_key_hex) {
-        Ok(bytes) => bytes,
-        Err(_) => return (false, String::from("Invalid AES key hex"), String::new()),
-    };
-
-    let rsa_pubkey_der = match general_purpose::STANDARD.decode(&input.rsa_pubkey_base64) {
-        Ok(der) => der,
-        Err(_) => return (false, String::from("Invalid RSA pubkey base64"), String::new()),
+pub fn rsa_encrypt(input: RsaEncryptAesKeyInput) -> (bool, String, String, String, OaepParams) {
+    let aes_key_bytes = match hex_decode(input.aes_key_hex.expose_secret()) {
+        Ok(bytes) => Zeroizing::new(bytes),
+        Err(_) => return (false, String::from("Invalid AES key hex"), String::new(), String::new(), input.oaep),
     };
 
-    let pubkey = match RsaPublicKey::from_public_key_der(&rsa_pubkey_der) {
-        Ok(key) => key,
-        Err(_) => return (false, String::from("RSA pubkey parse error"), String::new()),
+    let mut rng = match wrap_rng(input.wrap_seed_hex.expose_secret()) {
+        Ok(rng) => rng,
+        Err(err) => return (false, String::from(err), String::new(), String::new(), input.oaep),
     };
 
-    let mut rng = ChaCha20Rng::seed_from_u64(42);
-    let padding = Oaep::new::<Sha256>();
-    let enc_result = match pubkey.encrypt(&mut rng, padding, &aes_key_bytes) {
-        Ok(data) => data,
-        Err(_) => return (false, String::from("Encryption failed"), String::new()),
-    };
-   
-    // Convert the encrypted bytes to hex string
-    let encrypted_hex = hex::encode(&enc_result);
-    
-    (true, String::from("✅ RSA encryption successful"), encrypted_hex)
-}
\ No newline at end of file
+    match rsa_wrap(&input.rsa_pubkey_base64, &aes_key_bytes, &input.oaep, &mut rng) {
+        Ok((enc_result, pubkey_hash)) => {
+            (true, String::from("✅ RSA encryption successful"), hex::encode(enc_result), pubkey_hash, input.oaep)
+        }
+        Err(err) => (false, String::from(err), String::new(), String::new(), input.oaep),
+    }
+}
@@ -1,48 +1,1099 @@
 
 extern crate alloc;
 
+use alloc::format;
 use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
 use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::aes_gcm::{AES_GCM_NONCE_LEN, AES_GCM_TAG_LEN};
+use crate::chacha::{CHACHA_NONCE_LEN, CHACHA_TAG_LEN, XCHACHA_NONCE_LEN};
+use crate::ecies::{ECIES_PUBKEY_LEN, ECIES_TAG_LEN};
+use crate::eth_encryption::{ETH_ENCRYPTION_VERSION, ETH_NONCE_LEN, ETH_PUBKEY_LEN, ETH_TAG_LEN};
+use crate::json_schema::JsonSchema;
+use crate::pii::PII_DETECTORS;
+use crate::predicate::compile_predicate;
+use crate::rsa::WRAP_SEED_LEN;
+use crate::preview::selected_ranges;
+use crate::validation::{
+    FieldError, Validator, AES_CTR_IV_LEN, AES_KEY_LEN, DIGEST_LEN, MAX_AGGREGATED_RECEIPTS,
+    MAX_BATCH_RECIPIENTS, MAX_CSV_COLUMNS, MAX_JSON_SCHEMA_LEN, MAX_PATTERN_LEN, MAX_PAYLOAD_LEN,
+    MAX_PREDICATES, OAEP_MAX_LABEL_LEN, RSA_MAX_MODULUS_BITS,
+};
+
+/// String holding secret material (AES keys, plaintext).
+///
+/// Serializes exactly like a `String`, so guest inputs keep their wire format,
+/// but its `Debug` output is redacted and the buffer is wiped on drop.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+#[serde(transparent)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(value: String) -> Self {
+        Self(value)
+    }
+
+    /// Borrows the secret; callers must not log or persist the result in clear.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        Self(String::from(value))
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+/// Compression applied to the plaintext before AES-CTR encryption
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// The ciphertext encrypts the plaintext itself
+    #[default]
+    None,
+
+    /// Single-member gzip (RFC 1952) stream
+    Gzip,
+
+    /// Zstandard frames
+    Zstd,
+}
+
+impl Compression {
+    pub fn name(self) -> &'static str {
+        match self {
+            Compression::None => "none",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+}
 
 /// Inputs for AES-CTR decryption proof
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AesCtrDecryptionProofInput {
     /// AES-256 key, hex-encoded (64 hex chars → 32 bytes)
-    pub aes_key_hex: String,
+    pub aes_key_hex: SecretString,
 
     /// AES-CTR IV / counter, hex-encoded (32 hex chars → 16 bytes)
     pub iv_hex: String,
 
-    /// Original UTF-8 plaintext (e.g., JSON or message)
-    pub plaintext_utf8: String,
+    /// Original UTF-8 plaintext (e.g., JSON or message); the decompressed
+    /// content when `compression` is set
+    pub plaintext_utf8: SecretString,
 
     /// Ciphertext as hex-encoded string
     pub ciphertext_hex: String,
+
+    /// Compression applied before encryption; the guest decompresses the
+    /// decrypted stream and compares it with the plaintext
+    #[serde(default)]
+    pub compression: Compression,
+}
+
+impl AesCtrDecryptionProofInput {
+    /// Checks key/IV lengths, hex encoding and payload size before proving.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.hex_exact("aes_key_hex", self.aes_key_hex.expose_secret(), AES_KEY_LEN);
+        v.hex_exact("iv_hex", &self.iv_hex, AES_CTR_IV_LEN);
+
+        let plaintext_len = self.plaintext_utf8.expose_secret().len();
+        let plaintext_ok = v.max_len("plaintext_utf8", plaintext_len, MAX_PAYLOAD_LEN);
+        let ciphertext_len = v.hex_max("ciphertext_hex", &self.ciphertext_hex, MAX_PAYLOAD_LEN);
+        if let (true, Some(len)) = (plaintext_ok, ciphertext_len) {
+            if self.compression == Compression::None && len != plaintext_len {
+                v.error(
+                    "ciphertext_hex",
+                    format!("must be {} bytes to match plaintext_utf8, got {}", plaintext_len, len),
+                );
+            }
+        }
+        v.finish()
+    }
+}
+
+/// Journal of the AES-CTR decryption proof. The hashes of the (decompressed)
+/// plaintext and of the compressed stream are only set when the ciphertext matches.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AesCtrDecryptionProofOutput {
+    pub is_valid: bool,
+    pub message: String,
+    pub compression: Compression,
+    /// SHA-256 of the ciphertext, hex-encoded
+    pub ciphertext_sha256_hex: String,
+    /// IPFS CIDv1 of the ciphertext file, as pinned with default chunking
+    pub ciphertext_cid: String,
+    /// SHA-256 of the decrypted compressed stream, hex-encoded; `None` without compression
+    pub compressed_sha256_hex: Option<String>,
+    /// SHA-256 of the plaintext after decompression, hex-encoded
+    pub plaintext_sha256_hex: String,
+}
+
+/// Hash binding the AES key in a hash-locked exchange
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashLockDigest {
+    /// SHA-256, checked on-chain with the `sha256` precompile
+    #[default]
+    Sha256,
+
+    /// Keccak-256, checked on-chain with `keccak256`
+    Keccak256,
+}
+
+/// Journals that report failures through `message`. Provers fill in the
+/// public fields as they go and return `output.fail(..)` on the first error,
+/// so whatever was already established is still committed.
+pub trait ProofOutput: Sized {
+    fn message_mut(&mut self) -> &mut String;
+
+    /// Sets `message` and returns the output; `is_valid` stays `false`.
+    fn fail(mut self, message: &str) -> Self {
+        *self.message_mut() = String::from(message);
+        self
+    }
+}
+
+macro_rules! impl_proof_output {
+    ($($output:ty),* $(,)?) => {
+        $(impl ProofOutput for $output {
+            fn message_mut(&mut self) -> &mut String {
+                &mut self.message
+            }
+        })*
+    };
+}
+
+impl_proof_output!(
+    AesGcmEncryptionProofOutput,
+    ChaChaDecryptionProofOutput,
+    SamplePreviewOutput,
+    CsvPropertiesOutput,
+    JsonConformanceOutput,
+    PredicateProofOutput,
+    PiiAttestationOutput,
+    ImageMetadataOutput,
+    LineageProofOutput,
+    RewrapOutput,
+    DisputeProofOutput,
+    EciesEncryptedAesKeyOutput,
+    EciesEncryptAesKeyOutput,
+    EthEncryptedAesKeyOutput,
+    EthEncryptAesKeyOutput,
+);
+
+/// Inputs for the hash-lock proof: an AES-CTR decryption proof plus the hash
+/// under which the key is locked
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HashLockProofInput {
+    pub decryption: AesCtrDecryptionProofInput,
+
+    #[serde(default)]
+    pub digest: HashLockDigest,
+}
+
+impl HashLockProofInput {
+    /// Checks the wrapped decryption input; the digest needs no check.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        self.decryption.validate()
+    }
+}
+
+/// Journal of the hash-lock proof. `key_hash_hex` is the lock; the key itself
+/// stays private until the seller reveals it. The plaintext hash and the
+/// ciphertext CID tie the lock to a dataset, as in [`AesCtrDecryptionProofOutput`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HashLockProofOutput {
+    pub is_valid: bool,
+    pub message: String,
+    pub digest: HashLockDigest,
+    /// `H(aes_key)` under `digest`, hex-encoded
+    pub key_hash_hex: String,
+    pub iv_hex: String,
+    /// SHA-256 of the ciphertext, hex-encoded
+    pub ciphertext_sha256_hex: String,
+    /// IPFS CIDv1 of the ciphertext file, as pinned with default chunking
+    pub ciphertext_cid: String,
+    /// Compression applied before encryption
+    pub compression: Compression,
+    /// SHA-256 of the decrypted compressed stream, hex-encoded; `None` without compression
+    pub compressed_sha256_hex: Option<String>,
+    /// SHA-256 of the plaintext after decompression, hex-encoded; only set when
+    /// the ciphertext matches
+    pub plaintext_sha256_hex: String,
+}
+
+/// Layout of a JSON dataset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonFormat {
+    /// One document; a top-level array holds one record per element
+    #[default]
+    Json,
+
+    /// One record per non-blank line
+    #[serde(rename = "jsonl")]
+    JsonLines,
+}
+
+/// Inputs for the JSON Schema conformance proof: an AES-CTR decryption proof
+/// plus the public schema every record must validate against
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonConformanceInput {
+    pub decryption: AesCtrDecryptionProofInput,
+
+    #[serde(default)]
+    pub format: JsonFormat,
+
+    /// JSON Schema (supported subset, see `json_schema::JsonSchema`), committed verbatim
+    pub schema_json: String,
+}
+
+impl JsonConformanceInput {
+    /// Checks the decryption input, then the schema size and keywords.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.extend_from(self.decryption.validate());
+        if v.max_len("schema_json", self.schema_json.len(), MAX_JSON_SCHEMA_LEN) {
+            if let Err(message) = JsonSchema::parse(&self.schema_json) {
+                v.error("schema_json", message);
+            }
+        }
+        v.finish()
+    }
+}
+
+/// Journal of the JSON Schema conformance proof. `record_count` is only set
+/// when the ciphertext matches and every record conforms.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonConformanceOutput {
+    pub is_valid: bool,
+    pub message: String,
+    pub iv_hex: String,
+    /// SHA-256 of the AES key, hex-encoded
+    pub key_sha256_hex: String,
+    /// SHA-256 of the ciphertext, hex-encoded
+    pub ciphertext_sha256_hex: String,
+    /// Compression applied before encryption
+    pub compression: Compression,
+    pub format: JsonFormat,
+    pub schema_json: String,
+    pub record_count: u32,
+}
+
+/// Part of the plaintext revealed by a sample preview
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewSelection {
+    /// `len` bytes starting at byte `offset`
+    Range { offset: u32, len: u32 },
+
+    /// Whole Merkle chunks, by strictly increasing index
+    Chunks { indices: Vec<u32> },
+}
+
+/// Inputs for the sample preview proof: an AES-CTR decryption proof plus the
+/// excerpt to reveal
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SamplePreviewInput {
+    pub decryption: AesCtrDecryptionProofInput,
+
+    /// Chunk length of the ciphertext Merkle tree, in bytes
+    pub chunk_len: u32,
+
+    pub selection: PreviewSelection,
+}
+
+impl SamplePreviewInput {
+    /// Checks the decryption input and that the selection lies within the plaintext.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.extend_from(self.decryption.validate());
+        let plaintext_len = self.decryption.plaintext_utf8.expose_secret().len();
+        if let Err(message) = selected_ranges(&self.selection, self.chunk_len, plaintext_len) {
+            v.error("selection", message);
+        }
+        v.finish()
+    }
+}
+
+/// Plaintext bytes revealed by a preview
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevealedExcerpt {
+    /// Offset of the excerpt in the plaintext
+    pub offset: u32,
+    pub bytes_hex: String,
+}
+
+/// Journal of the sample preview proof. The excerpts are only set when the
+/// ciphertext matches.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SamplePreviewOutput {
+    pub is_valid: bool,
+    pub message: String,
+    pub iv_hex: String,
+    /// SHA-256 of the AES key, hex-encoded
+    pub key_sha256_hex: String,
+    /// SHA-256 of the ciphertext, hex-encoded
+    pub ciphertext_sha256_hex: String,
+    /// Compression applied before encryption
+    pub compression: Compression,
+    /// Root of the chunked Merkle tree over the ciphertext, hex-encoded
+    pub ciphertext_merkle_root_hex: String,
+    pub chunk_len: u32,
+    pub plaintext_len: u32,
+    pub revealed: Vec<RevealedExcerpt>,
+}
+
+/// Declared type of a CSV column. Empty fields are nulls in every type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CsvColumnType {
+    String,
+    /// 64-bit signed integer
+    Integer,
+    /// Finite decimal number
+    Number,
+    /// `true` or `false`
+    Boolean,
+}
+
+/// One declared CSV column
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsvColumn {
+    pub name: String,
+
+    #[serde(rename = "type")]
+    pub kind: CsvColumnType,
+}
+
+fn default_csv_delimiter() -> char {
+    ','
+}
+
+/// Declared CSV layout; the header row must list exactly these column names
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvSchema {
+    #[serde(default = "default_csv_delimiter")]
+    pub delimiter: char,
+
+    pub columns: Vec<CsvColumn>,
+}
+
+impl CsvSchema {
+    fn validate_into(&self, v: &mut Validator, field: &str) {
+        if matches!(self.delimiter, '"' | '\r' | '\n') {
+            v.error(
+                &format!("{}.delimiter", field),
+                String::from("must not be a quote or line break"),
+            );
+        }
+        if !(1..=MAX_CSV_COLUMNS).contains(&self.columns.len()) {
+            v.error(
+                &format!("{}.columns", field),
+                format!(
+                    "must declare between 1 and {} columns, got {}",
+                    MAX_CSV_COLUMNS,
+                    self.columns.len()
+                ),
+            );
+        }
+        for (i, column) in self.columns.iter().enumerate() {
+            let name_field = format!("{}.columns[{}].name", field, i);
+            if column.name.is_empty() {
+                v.error(&name_field, String::from("must not be empty"));
+            } else if self.columns[..i].iter().any(|c| c.name == column.name) {
+                v.error(&name_field, format!("duplicate column '{}'", column.name));
+            }
+        }
+    }
+}
+
+/// Inputs for the CSV property proof: an AES-CTR decryption proof plus the
+/// declared schema of the plaintext
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CsvPropertiesInput {
+    pub decryption: AesCtrDecryptionProofInput,
+
+    pub schema: CsvSchema,
+}
+
+impl CsvPropertiesInput {
+    /// Checks the decryption input and the declared columns.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.extend_from(self.decryption.validate());
+        self.schema.validate_into(&mut v, "schema");
+        v.finish()
+    }
+}
+
+/// Committed statistics of one CSV column. `min`, `max` and `mean` are only
+/// set for `integer` and `number` columns with at least one value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvColumnStats {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: CsvColumnType,
+    pub null_count: u32,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+}
+
+/// Journal of the CSV property proof. Row count and statistics are only set
+/// when the ciphertext matches and the plaintext conforms to the schema.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CsvPropertiesOutput {
+    pub is_valid: bool,
+    pub message: String,
+    pub iv_hex: String,
+    /// SHA-256 of the AES key, hex-encoded
+    pub key_sha256_hex: String,
+    /// SHA-256 of the ciphertext, hex-encoded
+    pub ciphertext_sha256_hex: String,
+    /// Compression applied before encryption
+    pub compression: Compression,
+    pub delimiter: char,
+    /// Data rows, excluding the header
+    pub row_count: u32,
+    pub columns: Vec<CsvColumnStats>,
+}
+
+/// What a predicate commits about its pattern
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PredicateMode {
+    /// Number of non-overlapping matches
+    #[default]
+    Count,
+
+    /// Only whether the pattern occurs at all
+    Presence,
+}
+
+/// Public pattern evaluated over the private plaintext
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Predicate {
+    /// Regular expression (Rust `regex` syntax), or a keyword when `literal` is set
+    pub pattern: String,
+
+    #[serde(default)]
+    pub literal: bool,
+
+    #[serde(default)]
+    pub mode: PredicateMode,
+}
+
+/// Inputs for the predicate proof: an AES-CTR decryption proof plus the
+/// public patterns to evaluate over the plaintext
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PredicateProofInput {
+    pub decryption: AesCtrDecryptionProofInput,
+
+    pub predicates: Vec<Predicate>,
+}
+
+impl PredicateProofInput {
+    /// Checks the decryption input and the number, length and syntax of the
+    /// patterns.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.extend_from(self.decryption.validate());
+        if !(1..=MAX_PREDICATES).contains(&self.predicates.len()) {
+            v.error(
+                "predicates",
+                format!(
+                    "must contain between 1 and {} predicates, got {}",
+                    MAX_PREDICATES,
+                    self.predicates.len()
+                ),
+            );
+        }
+        for (i, predicate) in self.predicates.iter().enumerate() {
+            let field = format!("predicates[{}].pattern", i);
+            if v.max_len(&field, predicate.pattern.len(), MAX_PATTERN_LEN) {
+                if let Err(message) = compile_predicate(predicate) {
+                    v.error(&field, message);
+                }
+            }
+        }
+        v.finish()
+    }
+}
+
+/// Committed result of one predicate. `match_count` is only set in `count` mode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PredicateResult {
+    pub pattern: String,
+    pub literal: bool,
+    pub mode: PredicateMode,
+    pub matched: bool,
+    pub match_count: Option<u32>,
+}
+
+/// Journal of the predicate proof. `results` is only set when the ciphertext
+/// matches the plaintext the patterns were evaluated over.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PredicateProofOutput {
+    pub is_valid: bool,
+    pub message: String,
+    pub iv_hex: String,
+    /// SHA-256 of the AES key, hex-encoded
+    pub key_sha256_hex: String,
+    /// SHA-256 of the ciphertext, hex-encoded
+    pub ciphertext_sha256_hex: String,
+    /// Compression applied before encryption
+    pub compression: Compression,
+    pub results: Vec<PredicateResult>,
+}
+
+/// Built-in PII detector of the PII-absence attestation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiDetector {
+    Email,
+    /// International (`+` prefixed) or North American phone number (separators
+    /// optional), 10 to 15 digits
+    Phone,
+    /// IBAN with valid check digits
+    Iban,
+    /// 13 to 19 digit card number passing the Luhn check
+    CreditCard,
+    Ipv4,
+    /// `0x` followed by 40 hex digits
+    EthAddress,
+}
+
+fn default_pii_detectors() -> Vec<PiiDetector> {
+    PII_DETECTORS.to_vec()
+}
+
+/// Inputs for the PII-absence attestation: an AES-CTR decryption proof plus
+/// the detectors to run (all of them by default)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PiiAttestationInput {
+    pub decryption: AesCtrDecryptionProofInput,
+
+    #[serde(default = "default_pii_detectors")]
+    pub detectors: Vec<PiiDetector>,
+}
+
+impl PiiAttestationInput {
+    /// Checks the decryption input and that the detector list is non-empty
+    /// without duplicates.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.extend_from(self.decryption.validate());
+        if self.detectors.is_empty() {
+            v.error("detectors", String::from("must not be empty"));
+        }
+        for (i, detector) in self.detectors.iter().enumerate() {
+            if self.detectors[..i].contains(detector) {
+                v.error(&format!("detectors[{}]", i), String::from("duplicate detector"));
+            }
+        }
+        v.finish()
+    }
+}
+
+/// Committed hit count of one detector
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PiiHits {
+    pub detector: PiiDetector,
+    pub count: u32,
+}
+
+/// Journal of the PII-absence attestation. `hits` and `pii_free` are only set
+/// when the ciphertext matches the scanned plaintext.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PiiAttestationOutput {
+    pub is_valid: bool,
+    pub message: String,
+    pub iv_hex: String,
+    /// SHA-256 of the AES key, hex-encoded
+    pub key_sha256_hex: String,
+    /// SHA-256 of the ciphertext, hex-encoded
+    pub ciphertext_sha256_hex: String,
+    /// Compression applied before encryption
+    pub compression: Compression,
+    /// Every built-in detector ran and none fired
+    pub pii_free: bool,
+    pub hits: Vec<PiiHits>,
+}
+
+/// Archive format of an image dataset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageContainer {
+    /// Stored or deflated entries
+    Zip,
+    /// ustar or GNU TAR
+    Tar,
+}
+
+/// Image format recognised by the image metadata proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+}
+
+/// Inputs for the image metadata proof. The plaintext archive is binary, so
+/// the guest decrypts the ciphertext itself instead of taking a plaintext.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageMetadataInput {
+    /// AES-256 key, hex-encoded (64 hex chars → 32 bytes)
+    pub aes_key_hex: SecretString,
+
+    /// AES-CTR IV / counter, hex-encoded (32 hex chars → 16 bytes)
+    pub iv_hex: String,
+
+    /// Encrypted archive as hex-encoded string
+    pub ciphertext_hex: String,
+
+    pub container: ImageContainer,
+}
+
+impl ImageMetadataInput {
+    /// Checks key/IV lengths, hex encoding and ciphertext size before proving.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.hex_exact("aes_key_hex", self.aes_key_hex.expose_secret(), AES_KEY_LEN);
+        v.hex_exact("iv_hex", &self.iv_hex, AES_CTR_IV_LEN);
+        v.hex_max("ciphertext_hex", &self.ciphertext_hex, MAX_PAYLOAD_LEN);
+        v.finish()
+    }
+}
+
+/// Number of images of one format
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageFormatCount {
+    pub format: ImageFormat,
+    pub count: u32,
+}
+
+/// Smallest and largest width and height over all images, in pixels
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageDimensionRange {
+    pub min_width: u32,
+    pub max_width: u32,
+    pub min_height: u32,
+    pub max_height: u32,
+}
+
+/// Journal of the image metadata proof. Counts and ranges are only set when
+/// the decrypted archive and every image header parse; `dimensions` is `None`
+/// when the archive holds no images.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageMetadataOutput {
+    pub is_valid: bool,
+    pub message: String,
+    pub iv_hex: String,
+    /// SHA-256 of the AES key, hex-encoded
+    pub key_sha256_hex: String,
+    /// SHA-256 of the ciphertext, hex-encoded
+    pub ciphertext_sha256_hex: String,
+    pub container: ImageContainer,
+    pub image_count: u32,
+    /// Archive files that are not PNG, JPEG or GIF
+    pub other_file_count: u32,
+    pub formats: Vec<ImageFormatCount>,
+    pub dimensions: Option<ImageDimensionRange>,
+}
+
+/// How a new dataset version must extend the previous one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineageRelation {
+    /// Append-only: the previous plaintext, ending with a line break, is a prefix
+    #[default]
+    Prefix,
+
+    /// Every line of the previous version is a line of the current one, in any order
+    RowSubset,
+}
+
+/// Inputs for the version lineage proof: AES-CTR decryption proofs of the
+/// previous and the current version, possibly under different keys
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LineageProofInput {
+    pub previous: AesCtrDecryptionProofInput,
+
+    pub current: AesCtrDecryptionProofInput,
+
+    #[serde(default)]
+    pub relation: LineageRelation,
+}
+
+impl LineageProofInput {
+    /// Checks both decryption inputs (fields prefixed with `previous.` and
+    /// `current.`). The current version may only reuse the previous key and IV
+    /// when its ciphertext starts with the previous ciphertext byte for byte;
+    /// otherwise the same keystream covers different bytes (a reordered or
+    /// recompressed dataset).
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.extend_prefixed("previous", self.previous.validate());
+        v.extend_prefixed("current", self.current.validate());
+        if self.reuses_keystream() {
+            v.error("current.iv_hex", String::from("must not reuse the previous key and IV"));
+        }
+        v.finish()
+    }
+
+    /// Whether both versions share a key and IV but the previous ciphertext is
+    /// not a prefix of the current one. Malformed hex is left to `validate`.
+    fn reuses_keystream(&self) -> bool {
+        let decoded = |a: &str, b: &str| Some((hex::decode(a).ok()?, hex::decode(b).ok()?));
+        let same = |a: &str, b: &str| decoded(a, b).is_some_and(|(a, b)| a == b);
+        if !same(self.previous.aes_key_hex.expose_secret(), self.current.aes_key_hex.expose_secret())
+            || !same(&self.previous.iv_hex, &self.current.iv_hex)
+        {
+            return false;
+        }
+        match decoded(&self.previous.ciphertext_hex, &self.current.ciphertext_hex) {
+            Some((previous, current)) => !current.starts_with(&previous),
+            None => false,
+        }
+    }
+}
+
+/// Public commitment to one dataset version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionCommitment {
+    pub iv_hex: String,
+    /// SHA-256 of the AES key, hex-encoded
+    pub key_sha256_hex: String,
+    /// SHA-256 of the ciphertext, hex-encoded
+    pub ciphertext_sha256_hex: String,
+    /// Compression applied before encryption
+    pub compression: Compression,
+    /// Lines of the plaintext
+    pub record_count: u32,
+}
+
+/// Journal of the version lineage proof. Record counts are only set when both
+/// ciphertexts match and the relation holds.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LineageProofOutput {
+    pub is_valid: bool,
+    pub message: String,
+    pub relation: LineageRelation,
+    pub previous: VersionCommitment,
+    pub current: VersionCommitment,
+}
+
+/// Inputs for AES-GCM encryption proof
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AesGcmEncryptionProofInput {
+    /// AES-256 key, hex-encoded (64 hex chars → 32 bytes)
+    pub aes_key_hex: SecretString,
+
+    /// AES-GCM nonce, hex-encoded (24 hex chars → 12 bytes)
+    pub nonce_hex: String,
+
+    /// Original UTF-8 plaintext (e.g., JSON or message)
+    pub plaintext_utf8: SecretString,
+
+    /// Ciphertext followed by the 16-byte tag, hex-encoded
+    pub ciphertext_hex: String,
+
+    /// Optional additional authenticated data, hex-encoded; committed publicly
+    pub aad_hex: Option<String>,
+}
+
+impl AesGcmEncryptionProofInput {
+    /// Checks key/nonce lengths, hex encoding and payload size before proving.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.hex_exact("aes_key_hex", self.aes_key_hex.expose_secret(), AES_KEY_LEN);
+        v.hex_exact("nonce_hex", &self.nonce_hex, AES_GCM_NONCE_LEN);
+        if let Some(aad_hex) = &self.aad_hex {
+            v.hex_max("aad_hex", aad_hex, MAX_PAYLOAD_LEN);
+        }
+
+        let plaintext_len = self.plaintext_utf8.expose_secret().len();
+        let plaintext_ok = v.max_len("plaintext_utf8", plaintext_len, MAX_PAYLOAD_LEN);
+        let ciphertext_len =
+            v.hex_max("ciphertext_hex", &self.ciphertext_hex, MAX_PAYLOAD_LEN + AES_GCM_TAG_LEN);
+        if let (true, Some(len)) = (plaintext_ok, ciphertext_len) {
+            if len != plaintext_len + AES_GCM_TAG_LEN {
+                v.error(
+                    "ciphertext_hex",
+                    format!(
+                        "must be {} bytes (plaintext_utf8 plus {}-byte tag), got {}",
+                        plaintext_len + AES_GCM_TAG_LEN,
+                        AES_GCM_TAG_LEN,
+                        len
+                    ),
+                );
+            }
+        }
+        v.finish()
+    }
+}
+
+/// Journal of the AES-GCM encryption proof. The key, plaintext and ciphertext
+/// stay private and are only committed as SHA-256 digests (hex).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AesGcmEncryptionProofOutput {
+    pub is_valid: bool,
+    pub message: String,
+    pub nonce_hex: String,
+    pub aad_hex: Option<String>,
+    pub key_sha256_hex: String,
+    pub plaintext_sha256_hex: String,
+    pub ciphertext_sha256_hex: String,
+    /// IPFS CIDv1 of `nonce || ciphertext || tag`, the file the frontend pins
+    pub file_cid: String,
+}
+
+/// ChaCha20-Poly1305 flavour used to encrypt a dataset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChaChaVariant {
+    /// IETF ChaCha20-Poly1305 with a 12-byte nonce
+    #[default]
+    #[serde(rename = "chacha20-poly1305")]
+    ChaCha20Poly1305,
+
+    /// XChaCha20-Poly1305 with a 24-byte nonce, safe to pick at random
+    #[serde(rename = "xchacha20-poly1305")]
+    XChaCha20Poly1305,
+}
+
+impl ChaChaVariant {
+    /// Nonce length in bytes expected by this variant.
+    pub fn nonce_len(self) -> usize {
+        match self {
+            ChaChaVariant::ChaCha20Poly1305 => CHACHA_NONCE_LEN,
+            ChaChaVariant::XChaCha20Poly1305 => XCHACHA_NONCE_LEN,
+        }
+    }
+}
+
+/// Inputs for ChaCha20-Poly1305 decryption proof
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChaChaDecryptionProofInput {
+    /// Cipher variant, `chacha20-poly1305` unless set
+    #[serde(default)]
+    pub cipher: ChaChaVariant,
+
+    /// 256-bit key, hex-encoded (64 hex chars → 32 bytes)
+    pub key_hex: SecretString,
+
+    /// Nonce, hex-encoded (12 bytes, or 24 bytes for XChaCha20)
+    pub nonce_hex: String,
+
+    /// Original UTF-8 plaintext (e.g., JSON or message)
+    pub plaintext_utf8: SecretString,
+
+    /// Ciphertext followed by the 16-byte tag, hex-encoded
+    pub ciphertext_hex: String,
+
+    /// Optional additional authenticated data, hex-encoded
+    pub aad_hex: Option<String>,
 }
 
+impl ChaChaDecryptionProofInput {
+    /// Checks key/nonce lengths, hex encoding and payload size before proving.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.hex_exact("key_hex", self.key_hex.expose_secret(), AES_KEY_LEN);
+        v.hex_exact("nonce_hex", &self.nonce_hex, self.cipher.nonce_len());
+        if let Some(aad_hex) = &self.aad_hex {
+            v.hex_max("aad_hex", aad_hex, MAX_PAYLOAD_LEN);
+        }
+
+        let plaintext_len = self.plaintext_utf8.expose_secret().len();
+        let plaintext_ok = v.max_len("plaintext_utf8", plaintext_len, MAX_PAYLOAD_LEN);
+        let ciphertext_len =
+            v.hex_max("ciphertext_hex", &self.ciphertext_hex, MAX_PAYLOAD_LEN + CHACHA_TAG_LEN);
+        if let (true, Some(len)) = (plaintext_ok, ciphertext_len) {
+            if len != plaintext_len + CHACHA_TAG_LEN {
+                v.error(
+                    "ciphertext_hex",
+                    format!(
+                        "must be {} bytes (plaintext_utf8 plus {}-byte tag), got {}",
+                        plaintext_len + CHACHA_TAG_LEN,
+                        CHACHA_TAG_LEN,
+                        len
+                    ),
+                );
+            }
+        }
+        v.finish()
+    }
+}
+
+/// Journal of the (X)ChaCha20-Poly1305 decryption proof
 #[derive(Debug, Serialize, Deserialize)]
-pub struct AesCtrDecryptionProofOutput{
+pub struct ChaChaDecryptionProofOutput {
     pub is_valid: bool,
     pub message: String,
+    pub cipher: ChaChaVariant,
+    pub nonce_hex: String,
+    pub aad_hex: Option<String>,
+    pub key_sha256_hex: String,
+    pub plaintext_sha256_hex: String,
+    /// SHA-256 of `ciphertext || tag`, hex-encoded
+    pub ciphertext_sha256_hex: String,
+    /// IPFS CIDv1 of `ciphertext || tag`
+    pub ciphertext_cid: String,
+}
+
+/// Hash functions allowed for RSA-OAEP and its MGF1. Anything else is rejected
+/// when the input is decoded, so the guest never proves other parameters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OaepDigest {
+    Sha1,
+    #[default]
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl OaepDigest {
+    /// Digest length in bytes, which is also the OAEP seed length
+    pub fn output_len(self) -> usize {
+        match self {
+            OaepDigest::Sha1 => 20,
+            OaepDigest::Sha256 => 32,
+            OaepDigest::Sha384 => 48,
+            OaepDigest::Sha512 => 64,
+        }
+    }
+}
+
+/// RSA-OAEP parameters, committed to the journal. Defaults to SHA-256 for both
+/// the label hash and MGF1, with no label. Unknown fields are rejected so that a
+/// misspelt label is not silently proven as "no label".
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OaepParams {
+    /// Hash of the label (and OAEP digest)
+    #[serde(default)]
+    pub digest: OaepDigest,
+
+    /// Hash used by MGF1
+    #[serde(default)]
+    pub mgf_digest: OaepDigest,
+
+    /// Optional label, hex-encoded, e.g. binding the wrapped key to a purchase.
+    /// Raw bytes, so any WebCrypto `label` buffer can be expressed.
+    #[serde(default)]
+    pub label_hex: Option<String>,
+}
+
+impl OaepParams {
+    /// Decodes the label (empty when unset), rejecting invalid hex and labels
+    /// longer than [`OAEP_MAX_LABEL_LEN`].
+    pub fn label(&self) -> Result<Vec<u8>, &'static str> {
+        let label = match &self.label_hex {
+            Some(label_hex) => hex::decode(label_hex).map_err(|_| "Invalid OAEP label hex")?,
+            None => Vec::new(),
+        };
+        if label.len() > OAEP_MAX_LABEL_LEN {
+            return Err("OAEP label too long");
+        }
+        Ok(label)
+    }
+}
+
+/// Private evidence that an RSA-OAEP ciphertext wraps a given AES key. The
+/// public key alone cannot tell, since OAEP encryption is randomised.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RsaWrapWitness {
+    /// OAEP seed the wrapper drew, hex-encoded (as long as the OAEP digest).
+    /// Re-encrypting the key with it must reproduce the ciphertext.
+    OaepSeedHex(SecretString),
+
+    /// Recipient RSA private key, base64-encoded PKCS#8 DER. The ciphertext must
+    /// decrypt to the key. The host normalises PEM and PKCS#1 keys to this form.
+    RsaPrivateKeyBase64(SecretString),
 }
 
 /// Inputs for verifying RSA encryption of AES key
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RsaEncryptedAesKeyInput {
     /// AES-256 key, hex-encoded (64 hex chars → 32 bytes)
-    pub aes_key_hex: String,
+    pub aes_key_hex: SecretString,
 
-    /// RSA public key (base64-encoded DER, 294 bytes for 2048-bit key)
+    /// RSA public key, base64-encoded SPKI DER (2048 to 4096-bit modulus). The host
+    /// normalises PEM, PKCS#1 and JWK keys to this form before proving.
     pub rsa_pubkey_base64: String,
 
     /// AES key encrypted with RSA public key (hex-encoded)
     pub enc_aes_key_hex: String,
+
+    /// OAEP digests and label used for the wrapping
+    #[serde(default)]
+    pub oaep: OaepParams,
+
+    /// OAEP seed or recipient private key proving the wrapping
+    pub witness: RsaWrapWitness,
+}
+
+impl RsaEncryptedAesKeyInput {
+    /// Checks the AES key length, RSA key size, wrapped key length and the
+    /// witness before proving.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.hex_exact("aes_key_hex", self.aes_key_hex.expose_secret(), AES_KEY_LEN);
+        let key = v.rsa_public_key("rsa_pubkey_base64", &self.rsa_pubkey_base64);
+        v.rsa_ciphertext("enc_aes_key_hex", &self.enc_aes_key_hex, key.as_ref());
+        v.oaep("oaep", &self.oaep);
+        match &self.witness {
+            RsaWrapWitness::OaepSeedHex(seed_hex) => {
+                v.hex_exact(
+                    "witness.oaep_seed_hex",
+                    seed_hex.expose_secret(),
+                    self.oaep.digest.output_len(),
+                );
+            }
+            RsaWrapWitness::RsaPrivateKeyBase64(private_key) => {
+                let field = "witness.rsa_private_key_base64";
+                let private_key = v.rsa_private_key(field, private_key.expose_secret());
+                if let (Some(private_key), Some(key)) = (private_key, key) {
+                    if private_key.to_public_key() != key {
+                        v.error(field, String::from("does not belong to rsa_pubkey_base64"));
+                    }
+                }
+            }
+        }
+        v.finish()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RsaEncryptedAesKeyOutput {
     pub is_valid: bool,
     pub message: String,
+    /// SHA-256 of the canonical SPKI DER of the RSA public key, hex-encoded
+    pub rsa_pubkey_sha256_hex: String,
+    /// OAEP parameters the key was wrapped with
+    pub oaep: OaepParams,
 }
 
 
@@ -51,11 +1102,31 @@ pub struct RsaEncryptedAesKeyOutput {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RsaEncryptAesKeyInput {
     /// AES-256 key, hex-encoded (64 hex chars → 32 bytes)
-    pub aes_key_hex: String,
+    pub aes_key_hex: SecretString,
 
-    /// RSA public key (base64-encoded DER, 294 bytes for 2048-bit key)
+    /// RSA public key, base64-encoded SPKI DER (2048 to 4096-bit modulus). The host
+    /// normalises PEM, PKCS#1 and JWK keys to this form before proving.
     pub rsa_pubkey_base64: String,
 
+    /// OAEP digests and label to wrap with
+    #[serde(default)]
+    pub oaep: OaepParams,
+
+    /// Private seed the OAEP seeds are drawn from (32 bytes, hex-encoded). The
+    /// host draws a fresh one from its OS RNG for every proof; it is never committed.
+    pub wrap_seed_hex: SecretString,
+}
+
+impl RsaEncryptAesKeyInput {
+    /// Checks the AES key length and RSA key size before proving.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.hex_exact("aes_key_hex", self.aes_key_hex.expose_secret(), AES_KEY_LEN);
+        v.rsa_public_key("rsa_pubkey_base64", &self.rsa_pubkey_base64);
+        v.oaep("oaep", &self.oaep);
+        v.hex_exact("wrap_seed_hex", self.wrap_seed_hex.expose_secret(), WRAP_SEED_LEN);
+        v.finish()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -64,5 +1135,604 @@ pub struct RsaEncryptAesKeyOutput {
     pub message: String,
     /// Encrypted AES key as hex string
     pub enc_aes_key_hex: String,
+    /// SHA-256 of the canonical SPKI DER of the RSA public key, hex-encoded
+    pub rsa_pubkey_sha256_hex: String,
+    /// OAEP parameters the key was wrapped with
+    pub oaep: OaepParams,
+}
+
+/// Buyer public key in a batch wrapping. Variants are named after the key
+/// encoding, so validation errors read `recipients[0].rsa_pubkey_base64`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BatchRecipient {
+    /// RSA public key, base64-encoded SPKI DER, wrapped with the batch OAEP parameters
+    #[serde(rename = "rsa_pubkey_base64")]
+    Rsa(String),
+
+    /// X25519 public key, hex-encoded, wrapped with `ecies::seal`
+    #[serde(rename = "ecies_pubkey_hex")]
+    Ecies(String),
+}
+
+/// Inputs for wrapping one AES key to several buyers in a single proof
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchEncryptAesKeyInput {
+    /// AES-256 key, hex-encoded (64 hex chars → 32 bytes)
+    pub aes_key_hex: SecretString,
+
+    /// Buyer public keys, at most [`MAX_BATCH_RECIPIENTS`]
+    pub recipients: Vec<BatchRecipient>,
+
+    /// OAEP digests and label used for every RSA recipient
+    #[serde(default)]
+    pub oaep: OaepParams,
+
+    /// Private seed the OAEP seeds are drawn from (32 bytes, hex-encoded). The
+    /// host draws a fresh one from its OS RNG for every proof; it is never committed.
+    pub wrap_seed_hex: SecretString,
+}
+
+impl BatchEncryptAesKeyInput {
+    /// Checks the AES key, the recipient count and every recipient key before proving.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.hex_exact("aes_key_hex", self.aes_key_hex.expose_secret(), AES_KEY_LEN);
+        if !(1..=MAX_BATCH_RECIPIENTS).contains(&self.recipients.len()) {
+            v.error(
+                "recipients",
+                format!(
+                    "must contain between 1 and {} public keys, got {}",
+                    MAX_BATCH_RECIPIENTS,
+                    self.recipients.len()
+                ),
+            );
+        }
+        for (i, recipient) in self.recipients.iter().enumerate() {
+            recipient.check(&mut v, &format!("recipients[{}]", i));
+        }
+        v.oaep("oaep", &self.oaep);
+        v.hex_exact("wrap_seed_hex", self.wrap_seed_hex.expose_secret(), WRAP_SEED_LEN);
+        v.finish()
+    }
+}
+
+impl BatchRecipient {
+    /// Checks the RSA key size or X25519 key length, reported under `field`.
+    fn check(&self, v: &mut Validator, field: &str) {
+        match self {
+            BatchRecipient::Rsa(key) => {
+                v.rsa_public_key(&format!("{}.rsa_pubkey_base64", field), key);
+            }
+            BatchRecipient::Ecies(key) => {
+                v.hex_exact(&format!("{}.ecies_pubkey_hex", field), key, ECIES_PUBKEY_LEN);
+            }
+        }
+    }
+}
+
+/// One wrapped copy of the AES key in a batch journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WrappedAesKey {
+    /// SHA-256 of the buyer key (canonical SPKI DER for RSA, raw bytes for X25519), hex-encoded
+    pub pubkey_sha256_hex: String,
+
+    /// Wrapped AES key (RSA-OAEP ciphertext or ECIES envelope), hex-encoded
+    pub enc_aes_key_hex: String,
+}
+
+/// Journal of the batch wrapping proof: a single commitment to the AES key and
+/// one wrapped key per buyer, in request order.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchEncryptAesKeyOutput {
+    pub is_valid: bool,
+    pub message: String,
+    /// SHA-256 of the AES key, hex-encoded
+    pub aes_key_sha256_hex: String,
+    pub wrapped: Vec<WrappedAesKey>,
+    /// OAEP parameters used for the RSA recipients
+    pub oaep: OaepParams,
+}
+
+/// Inputs for verifying ECIES (X25519) wrapping of an AES key
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EciesEncryptedAesKeyInput {
+    /// AES-256 key, hex-encoded (64 hex chars → 32 bytes)
+    pub aes_key_hex: SecretString,
+
+    /// Recipient X25519 public key, hex-encoded (64 hex chars → 32 bytes)
+    pub ecies_pubkey_hex: String,
+
+    /// ECIES envelope of the AES key (ephemeral pubkey || ciphertext || tag), hex-encoded
+    pub enc_aes_key_hex: String,
+}
+
+impl EciesEncryptedAesKeyInput {
+    /// Checks the AES key, X25519 key and envelope lengths before proving.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.hex_exact("aes_key_hex", self.aes_key_hex.expose_secret(), AES_KEY_LEN);
+        v.hex_exact("ecies_pubkey_hex", &self.ecies_pubkey_hex, ECIES_PUBKEY_LEN);
+        v.hex_exact(
+            "enc_aes_key_hex",
+            &self.enc_aes_key_hex,
+            ECIES_PUBKEY_LEN + AES_KEY_LEN + ECIES_TAG_LEN,
+        );
+        v.finish()
+    }
+}
+
+/// Journal of the ECIES verification proof
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EciesEncryptedAesKeyOutput {
+    pub is_valid: bool,
+    pub message: String,
+    /// SHA-256 of the raw X25519 recipient key, hex-encoded
+    pub ecies_pubkey_sha256_hex: String,
+    /// The verified envelope, hex-encoded
+    pub enc_aes_key_hex: String,
+    /// SHA-256 of the AES key, hex-encoded
+    pub aes_key_sha256_hex: String,
+}
+
+/// Inputs for wrapping an AES key to an X25519 public key with ECIES
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EciesEncryptAesKeyInput {
+    /// AES-256 key, hex-encoded (64 hex chars → 32 bytes)
+    pub aes_key_hex: SecretString,
+
+    /// Recipient X25519 public key, hex-encoded (64 hex chars → 32 bytes)
+    pub ecies_pubkey_hex: String,
+}
+
+impl EciesEncryptAesKeyInput {
+    /// Checks the AES key and X25519 key lengths before proving.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.hex_exact("aes_key_hex", self.aes_key_hex.expose_secret(), AES_KEY_LEN);
+        v.hex_exact("ecies_pubkey_hex", &self.ecies_pubkey_hex, ECIES_PUBKEY_LEN);
+        v.finish()
+    }
+}
+
+/// Journal of the ECIES wrapping proof
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EciesEncryptAesKeyOutput {
+    pub is_valid: bool,
+    pub message: String,
+    /// ECIES envelope of the AES key as hex string
+    pub enc_aes_key_hex: String,
+    /// SHA-256 of the raw X25519 recipient key, hex-encoded
+    pub ecies_pubkey_sha256_hex: String,
+    /// SHA-256 of the AES key, hex-encoded
+    pub aes_key_sha256_hex: String,
+}
+
+/// Encrypted message in the format of `@metamask/eth-sig-util` `encrypt`,
+/// accepted by `eth_decrypt` once JSON-serialized and hex-encoded.
+/// Field order matches `JSON.stringify` output of eth-sig-util.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EthEncryptedData {
+    /// Always `x25519-xsalsa20-poly1305`
+    pub version: String,
+
+    /// XSalsa20 nonce, base64 (24 bytes)
+    pub nonce: String,
+
+    /// Ephemeral X25519 public key, base64 (32 bytes)
+    #[serde(rename = "ephemPublicKey")]
+    pub ephem_public_key: String,
+
+    /// Poly1305 tag followed by the ciphertext, base64
+    pub ciphertext: String,
+}
+
+impl EthEncryptedData {
+    fn validate_into(&self, v: &mut Validator, field: &str) {
+        if self.version != ETH_ENCRYPTION_VERSION {
+            v.error(
+                &format!("{}.version", field),
+                format!("must be {}", ETH_ENCRYPTION_VERSION),
+            );
+        }
+        v.base64_exact(&format!("{}.nonce", field), &self.nonce, ETH_NONCE_LEN);
+        v.base64_exact(&format!("{}.ephemPublicKey", field), &self.ephem_public_key, ETH_PUBKEY_LEN);
+        // The sealed message is the 64-char hex encoding of the AES key
+        v.base64_exact(&format!("{}.ciphertext", field), &self.ciphertext, ETH_TAG_LEN + 2 * AES_KEY_LEN);
+    }
+}
+
+/// Inputs for verifying that an AES key was sealed to a wallet encryption key
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EthEncryptedAesKeyInput {
+    /// AES-256 key, hex-encoded (64 hex chars → 32 bytes)
+    pub aes_key_hex: SecretString,
+
+    /// Buyer's wallet encryption key from `eth_getEncryptionPublicKey` (base64 X25519)
+    pub eth_pubkey_base64: String,
+
+    /// Envelope to check
+    pub encrypted: EthEncryptedData,
+
+    /// Ephemeral X25519 secret the envelope was sealed with, hex-encoded. A
+    /// private witness for envelopes sealed elsewhere (e.g. with a random
+    /// sender key); without it only envelopes from `eth_encryption::seal` verify.
+    #[serde(default)]
+    pub ephemeral_secret_hex: Option<SecretString>,
+}
+
+impl EthEncryptedAesKeyInput {
+    /// Checks the AES key, wallet key and envelope field lengths before proving.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.hex_exact("aes_key_hex", self.aes_key_hex.expose_secret(), AES_KEY_LEN);
+        v.base64_exact("eth_pubkey_base64", &self.eth_pubkey_base64, ETH_PUBKEY_LEN);
+        self.encrypted.validate_into(&mut v, "encrypted");
+        if let Some(secret_hex) = &self.ephemeral_secret_hex {
+            v.hex_exact("ephemeral_secret_hex", secret_hex.expose_secret(), ETH_PUBKEY_LEN);
+        }
+        v.finish()
+    }
+}
+
+/// Journal of the wallet encryption verification proof
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EthEncryptedAesKeyOutput {
+    pub is_valid: bool,
+    pub message: String,
+    /// Buyer's wallet encryption key the envelope is sealed to
+    pub eth_pubkey_base64: String,
+    /// The verified envelope
+    pub encrypted: EthEncryptedData,
+    /// SHA-256 of the AES key, hex-encoded
+    pub aes_key_sha256_hex: String,
+}
+
+/// Inputs for sealing an AES key to a wallet encryption key
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EthEncryptAesKeyInput {
+    /// AES-256 key, hex-encoded (64 hex chars → 32 bytes)
+    pub aes_key_hex: SecretString,
+
+    /// Buyer's wallet encryption key from `eth_getEncryptionPublicKey` (base64 X25519)
+    pub eth_pubkey_base64: String,
 }
 
+impl EthEncryptAesKeyInput {
+    /// Checks the AES key and wallet key lengths before proving.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.hex_exact("aes_key_hex", self.aes_key_hex.expose_secret(), AES_KEY_LEN);
+        v.base64_exact("eth_pubkey_base64", &self.eth_pubkey_base64, ETH_PUBKEY_LEN);
+        v.finish()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EthEncryptAesKeyOutput {
+    pub is_valid: bool,
+    pub message: String,
+    /// Envelope of the AES key, ready for `eth_decrypt`
+    pub encrypted: EthEncryptedData,
+    /// Buyer's wallet encryption key the envelope is sealed to
+    pub eth_pubkey_base64: String,
+    /// SHA-256 of the AES key, hex-encoded
+    pub aes_key_sha256_hex: String,
+}
+
+/// Key commitment from a seller journal: `key_sha256_hex` of `/aes-gcm-verify`
+/// or `/rsa-encrypt-batch`, or `key_hash_hex` of `/hashlock-verify`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyCommitment {
+    #[serde(default)]
+    pub digest: HashLockDigest,
+
+    /// Committed `H(aes_key)`, hex-encoded (64 hex chars → 32 bytes)
+    pub key_hash_hex: String,
+}
+
+/// Cipher and public parameters of a committed dataset ciphertext
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DatasetCipher {
+    /// AES-256-CTR, IV hex-encoded (32 hex chars → 16 bytes)
+    AesCtr { iv_hex: String },
+
+    /// AES-256-GCM, nonce hex-encoded (24 hex chars → 12 bytes), optional AAD
+    AesGcm { nonce_hex: String, aad_hex: Option<String> },
+}
+
+/// Dataset ciphertext the seller proved, with the plaintext hash they committed to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommittedCiphertext {
+    pub cipher: DatasetCipher,
+
+    /// Ciphertext as published (ciphertext || tag for AES-GCM), hex-encoded
+    pub ciphertext_hex: String,
+
+    /// Committed SHA-256 of the plaintext, hex-encoded. Required for AES-CTR,
+    /// which decrypts under any key; for AES-GCM a failing tag is enough.
+    pub plaintext_sha256_hex: Option<String>,
+}
+
+/// Inputs for proving that the key a seller delivered breaks their commitments.
+/// Only the buyer can produce it: the RSA private key is a private witness.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DisputeProofInput {
+    /// Buyer RSA private key, base64-encoded PKCS#8 DER. The host normalises
+    /// PEM and PKCS#1 keys to this form before proving.
+    pub rsa_private_key_base64: SecretString,
+
+    /// `encryptedSecret` exactly as stored on the escrow
+    pub encrypted_secret_hex: String,
+
+    /// OAEP digests and label the seller wrapped with, copied from the `oaep`
+    /// of their wrapping journal (`/rsa-encrypt`, `/rsa-encrypt-batch` or
+    /// `/rewrap`). The watcher wraps with the defaults.
+    #[serde(default)]
+    pub oaep: OaepParams,
+
+    pub key_commitment: Option<KeyCommitment>,
+
+    pub ciphertext: Option<CommittedCiphertext>,
+}
+
+impl DisputeProofInput {
+    /// Checks the private key, that at least one commitment is given and the
+    /// commitment field lengths before proving. `encrypted_secret_hex` is only
+    /// bounded in size: a malformed on-chain value is itself misbehaviour.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        v.rsa_private_key("rsa_private_key_base64", self.rsa_private_key_base64.expose_secret());
+        v.max_len("encrypted_secret_hex", self.encrypted_secret_hex.len(), 2 * RSA_MAX_MODULUS_BITS / 8);
+        v.oaep("oaep", &self.oaep);
+
+        if self.key_commitment.is_none() && self.ciphertext.is_none() {
+            v.error(
+                "key_commitment",
+                String::from("at least one of key_commitment or ciphertext must be provided"),
+            );
+        }
+        if let Some(commitment) = &self.key_commitment {
+            v.hex_exact("key_commitment.key_hash_hex", &commitment.key_hash_hex, DIGEST_LEN);
+        }
+        if let Some(committed) = &self.ciphertext {
+            let max_len = match &committed.cipher {
+                DatasetCipher::AesCtr { iv_hex } => {
+                    v.hex_exact("ciphertext.cipher.aes_ctr.iv_hex", iv_hex, AES_CTR_IV_LEN);
+                    if committed.plaintext_sha256_hex.is_none() {
+                        v.error(
+                            "ciphertext.plaintext_sha256_hex",
+                            String::from("is required for AES-CTR ciphertexts"),
+                        );
+                    }
+                    MAX_PAYLOAD_LEN
+                }
+                DatasetCipher::AesGcm { nonce_hex, aad_hex } => {
+                    v.hex_exact("ciphertext.cipher.aes_gcm.nonce_hex", nonce_hex, AES_GCM_NONCE_LEN);
+                    if let Some(aad_hex) = aad_hex {
+                        v.hex_max("ciphertext.cipher.aes_gcm.aad_hex", aad_hex, MAX_PAYLOAD_LEN);
+                    }
+                    MAX_PAYLOAD_LEN + AES_GCM_TAG_LEN
+                }
+            };
+            v.hex_max("ciphertext.ciphertext_hex", &committed.ciphertext_hex, max_len);
+            if let Some(hash) = &committed.plaintext_sha256_hex {
+                v.hex_exact("ciphertext.plaintext_sha256_hex", hash, DIGEST_LEN);
+            }
+        }
+        v.finish()
+    }
+}
+
+/// Journal of the dispute proof. `is_misbehaviour` is only meaningful once the
+/// arbiter has checked the echoed values: `rsa_pubkey_sha256_hex` against the
+/// purchase's `buyerPublicKey`, `encrypted_secret_hex` against the escrow, and
+/// `oaep` and the commitments against the seller's journal. Any other `oaep`
+/// makes a correct secret fail to unwrap, so the proof must then be rejected.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DisputeProofOutput {
+    pub is_misbehaviour: bool,
+    pub message: String,
+    /// SHA-256 of the canonical SPKI DER of the buyer public key, hex-encoded
+    pub rsa_pubkey_sha256_hex: String,
+    pub encrypted_secret_hex: String,
+    pub oaep: OaepParams,
+    pub key_commitment: Option<KeyCommitment>,
+    pub cipher: Option<DatasetCipher>,
+    /// SHA-256 of the committed ciphertext, hex-encoded
+    pub ciphertext_sha256_hex: Option<String>,
+    pub plaintext_sha256_hex: Option<String>,
+}
+
+/// Holder of the AES key in a licence transfer
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RewrapSource {
+    /// Current owner: the key wrapped to them and the private key unwrapping it
+    Owner {
+        /// Owner RSA private key, base64-encoded PKCS#8 DER. The host normalises
+        /// PEM and PKCS#1 keys to this form before proving.
+        rsa_private_key_base64: SecretString,
+
+        /// AES key wrapped to the owner (`encryptedSecret`), hex-encoded
+        enc_aes_key_hex: String,
+
+        /// OAEP digests and label the key was wrapped to the owner with
+        oaep: OaepParams,
+    },
+
+    /// Seller re-issuing the licence with the AES key itself
+    Seller {
+        /// AES-256 key, hex-encoded (64 hex chars → 32 bytes)
+        aes_key_hex: SecretString,
+    },
+}
+
+/// Inputs for re-wrapping a dataset key to the new owner of a transferred licence
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RewrapInput {
+    pub source: RewrapSource,
+
+    /// New owner's public key
+    pub recipient: BatchRecipient,
+
+    /// OAEP digests and label to wrap with when the new owner has an RSA key
+    #[serde(default)]
+    pub oaep: OaepParams,
+
+    /// Hash under which the AES key is committed, matching the seller's journal
+    #[serde(default)]
+    pub key_digest: HashLockDigest,
+
+    /// Private seed the OAEP seeds are drawn from (32 bytes, hex-encoded). The
+    /// host draws a fresh one from its OS RNG for every proof; it is never committed.
+    pub wrap_seed_hex: SecretString,
+}
+
+impl RewrapInput {
+    /// Checks the owner's private key and wrapped key (or the seller's AES key),
+    /// the new owner's key and the OAEP labels before proving.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        match &self.source {
+            RewrapSource::Owner { rsa_private_key_base64, enc_aes_key_hex, oaep } => {
+                let private_key = rsa_private_key_base64.expose_secret();
+                let key = v.rsa_private_key("source.owner.rsa_private_key_base64", private_key);
+                let pubkey = key.map(|key| key.to_public_key());
+                v.rsa_ciphertext("source.owner.enc_aes_key_hex", enc_aes_key_hex, pubkey.as_ref());
+                v.oaep("source.owner.oaep", oaep);
+            }
+            RewrapSource::Seller { aes_key_hex } => {
+                v.hex_exact("source.seller.aes_key_hex", aes_key_hex.expose_secret(), AES_KEY_LEN);
+            }
+        }
+        self.recipient.check(&mut v, "recipient");
+        v.oaep("oaep", &self.oaep);
+        v.hex_exact("wrap_seed_hex", self.wrap_seed_hex.expose_secret(), WRAP_SEED_LEN);
+        v.finish()
+    }
+}
+
+/// Journal of the re-wrapping proof: the key commitment is the one of the
+/// listing, so a transfer proves the new owner receives the same key. The
+/// `old_*` fields are `None` when the seller re-wraps.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RewrapOutput {
+    pub is_valid: bool,
+    pub message: String,
+    /// `H(aes_key)` under `key_digest`, unchanged by the transfer
+    pub key_commitment: KeyCommitment,
+    /// SHA-256 of the canonical SPKI DER of the previous owner's RSA key, hex-encoded
+    pub old_pubkey_sha256_hex: Option<String>,
+    pub old_enc_aes_key_hex: Option<String>,
+    pub old_oaep: Option<OaepParams>,
+    /// SHA-256 of the new owner's key (SPKI DER for RSA, raw X25519 bytes), hex-encoded
+    pub new_pubkey_sha256_hex: String,
+    /// AES key wrapped to the new owner (RSA-OAEP ciphertext or ECIES envelope), hex-encoded
+    pub new_enc_aes_key_hex: String,
+    /// OAEP parameters used for an RSA new owner
+    pub oaep: OaepParams,
+}
+
+/// A receipt folded into an aggregation proof, as the claim the guest verifies:
+/// the image ID of the guest that produced it and its journal bytes. The
+/// receipt itself is passed to the prover as an assumption.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregatedReceipt {
+    /// Image ID of the proving guest
+    pub image_id: [u32; 8],
+
+    /// Journal bytes committed by the guest
+    pub journal: Vec<u8>,
+}
+
+/// Inputs for aggregating several purchase proofs into one receipt
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AggregationInput {
+    /// Sub-receipt claims in leaf order, at most [`MAX_AGGREGATED_RECEIPTS`]
+    pub receipts: Vec<AggregatedReceipt>,
+}
+
+impl AggregationInput {
+    /// Checks the receipt count and journal sizes before proving.
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::new();
+        if !(1..=MAX_AGGREGATED_RECEIPTS).contains(&self.receipts.len()) {
+            v.error(
+                "receipts",
+                format!(
+                    "must contain between 1 and {} receipts, got {}",
+                    MAX_AGGREGATED_RECEIPTS,
+                    self.receipts.len()
+                ),
+            );
+        }
+        for (i, receipt) in self.receipts.iter().enumerate() {
+            v.max_len(&format!("receipts[{}].journal", i), receipt.journal.len(), MAX_PAYLOAD_LEN);
+        }
+        v.finish()
+    }
+}
+
+/// Journal of the aggregation proof: the number of verified sub-receipts and
+/// the Merkle root over their `(image_id, journal digest)` leaves.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AggregationOutput {
+    pub is_valid: bool,
+    pub message: String,
+    pub receipt_count: u32,
+    /// Root of the binary SHA-256 tree over the sub-receipt leaves, hex-encoded
+    pub merkle_root_hex: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn fields(result: Result<(), Vec<FieldError>>) -> Vec<String> {
+        result.err().unwrap_or_default().into_iter().map(|e| e.field).collect()
+    }
+
+    #[test]
+    fn rewrap_errors_are_paths_into_the_input() {
+        let owner = RewrapInput {
+            source: RewrapSource::Owner {
+                rsa_private_key_base64: SecretString::from("not base64!"),
+                enc_aes_key_hex: String::from("zz"),
+                oaep: OaepParams { label_hex: Some(String::from("0")), ..OaepParams::default() },
+            },
+            recipient: BatchRecipient::Rsa(String::from("not base64!")),
+            oaep: OaepParams::default(),
+            key_digest: HashLockDigest::default(),
+            wrap_seed_hex: SecretString::from("00".repeat(WRAP_SEED_LEN)),
+        };
+        assert_eq!(
+            fields(owner.validate()),
+            [
+                "source.owner.rsa_private_key_base64",
+                "source.owner.enc_aes_key_hex",
+                "source.owner.oaep.label_hex",
+                "recipient.rsa_pubkey_base64",
+            ]
+        );
+
+        let seller = RewrapInput {
+            source: RewrapSource::Seller { aes_key_hex: SecretString::from("00") },
+            recipient: BatchRecipient::Ecies(String::from("00")),
+            oaep: OaepParams::default(),
+            key_digest: HashLockDigest::default(),
+            wrap_seed_hex: SecretString::from("00".repeat(WRAP_SEED_LEN)),
+        };
+        assert_eq!(fields(seller.validate()), ["source.seller.aes_key_hex", "recipient.ecies_pubkey_hex"]);
+    }
+
+    #[test]
+    fn batch_recipients_serialize_under_their_field_names() {
+        let recipients = vec![BatchRecipient::Rsa(String::from("MIIB")), BatchRecipient::Ecies(String::from("00"))];
+        assert_eq!(
+            serde_json::to_string(&recipients).unwrap(),
+            r#"[{"rsa_pubkey_base64":"MIIB"},{"ecies_pubkey_hex":"00"}]"#
+        );
+    }
+}
@@ -2,6 +2,7 @@ use risc0_zkvm::Receipt;
 use bincode::serialize;
 use base64::{engine::general_purpose, Engine as _};
 use alloc::string::String;
+use sha2::{Digest, Sha256};
 
 
 pub fn receipt_to_base64(receipt: &Receipt) -> String {
@@ -10,4 +11,10 @@ pub fn receipt_to_base64(receipt: &Receipt) -> String {
 
     // Encode the bytes as base64
     general_purpose::STANDARD.encode(receipt_bytes)
-}
\ No newline at end of file
+}
+
+/// Hex-encoded SHA-256 of `data`, the form in which journals commit to
+/// plaintexts, ciphertexts and keys.
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
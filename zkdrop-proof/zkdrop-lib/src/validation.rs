@@ -0,0 +1,410 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use base64::{engine::general_purpose, Engine as _};
+use rsa::{
+    pkcs8::{DecodePrivateKey, DecodePublicKey},
+    traits::PublicKeyParts,
+    RsaPrivateKey, RsaPublicKey,
+};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::types::OaepParams;
+
+/// AES-256 key length in bytes
+pub const AES_KEY_LEN: usize = 32;
+
+/// AES-CTR IV / counter block length in bytes
+pub const AES_CTR_IV_LEN: usize = 16;
+
+/// SHA-256 / Keccak-256 digest length in bytes
+pub const DIGEST_LEN: usize = 32;
+
+/// Smallest accepted RSA modulus
+pub const RSA_MIN_MODULUS_BITS: usize = 2048;
+
+/// Largest accepted RSA modulus
+pub const RSA_MAX_MODULUS_BITS: usize = 4096;
+
+/// Longest OAEP label accepted, in bytes
+pub const OAEP_MAX_LABEL_LEN: usize = 256;
+
+/// Most buyer keys wrapped in a single batch proof
+pub const MAX_BATCH_RECIPIENTS: usize = 64;
+
+/// Most receipts folded into a single aggregation proof
+pub const MAX_AGGREGATED_RECEIPTS: usize = 64;
+
+/// Largest plaintext / ciphertext accepted for a single proof (1 MiB)
+pub const MAX_PAYLOAD_LEN: usize = 1024 * 1024;
+
+/// Most columns a declared CSV schema may have
+pub const MAX_CSV_COLUMNS: usize = 256;
+
+/// Longest JSON Schema accepted, in bytes (16 KiB)
+pub const MAX_JSON_SCHEMA_LEN: usize = 16 * 1024;
+
+/// Most predicates evaluated in a single proof
+pub const MAX_PREDICATES: usize = 16;
+
+/// Longest predicate pattern accepted, in bytes
+pub const MAX_PATTERN_LEN: usize = 1024;
+
+/// Most bytes a single compressed entry or stream may inflate to (16 MiB)
+pub const MAX_INFLATED_LEN: usize = 16 * 1024 * 1024;
+
+/// Most plaintext bytes a sample preview may reveal (64 KiB)
+pub const MAX_PREVIEW_LEN: usize = 64 * 1024;
+
+/// A problem with one field of an input, reported before anything is sent to the zkVM.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Collects [`FieldError`]s while the fields of an input are checked.
+#[derive(Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn error(&mut self, field: &str, message: String) {
+        self.errors.push(FieldError { field: String::from(field), message });
+    }
+
+    /// Adds the errors of a nested input's `validate`, e.g. the decryption
+    /// proof input wrapped by the dataset property proofs.
+    pub fn extend_from(&mut self, result: Result<(), Vec<FieldError>>) {
+        self.errors.extend(result.err().unwrap_or_default());
+    }
+
+    /// Same as [`Validator::extend_from`], with each field prefixed by `prefix.`.
+    pub fn extend_prefixed(&mut self, prefix: &str, result: Result<(), Vec<FieldError>>) {
+        for e in result.err().unwrap_or_default() {
+            self.errors.push(FieldError { field: format!("{}.{}", prefix, e.field), message: e.message });
+        }
+    }
+
+    /// Checks that `value` is well-formed hex and returns the decoded length.
+    /// Nothing is decoded, so secret values are never copied.
+    pub fn hex(&mut self, field: &str, value: &str) -> Option<usize> {
+        if value.len() % 2 == 1 {
+            self.error(field, String::from("hex string must have an even length"));
+            return None;
+        }
+        if !value.bytes().all(|b| b.is_ascii_hexdigit()) {
+            self.error(field, String::from("must only contain hex characters"));
+            return None;
+        }
+        Some(value.len() / 2)
+    }
+
+    /// Checks that `value` is hex encoding exactly `expected` bytes.
+    pub fn hex_exact(&mut self, field: &str, value: &str, expected: usize) -> bool {
+        match self.hex(field, value) {
+            Some(len) if len == expected => true,
+            Some(len) => {
+                self.error(field, format!("must be {} bytes, got {}", expected, len));
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Checks that `value` is hex encoding at most `max` bytes and returns the decoded length.
+    pub fn hex_max(&mut self, field: &str, value: &str, max: usize) -> Option<usize> {
+        let len = self.hex(field, value)?;
+        if len > max {
+            self.error(field, format!("must be at most {} bytes, got {}", max, len));
+            return None;
+        }
+        Some(len)
+    }
+
+    /// Checks that `value` is base64 encoding exactly `expected` bytes.
+    pub fn base64_exact(&mut self, field: &str, value: &str, expected: usize) -> bool {
+        match general_purpose::STANDARD.decode(value) {
+            Ok(bytes) if bytes.len() == expected => true,
+            Ok(bytes) => {
+                self.error(field, format!("must be {} bytes, got {}", expected, bytes.len()));
+                false
+            }
+            Err(_) => {
+                self.error(field, String::from("must be valid base64"));
+                false
+            }
+        }
+    }
+
+    pub fn max_len(&mut self, field: &str, len: usize, max: usize) -> bool {
+        if len > max {
+            self.error(field, format!("must be at most {} bytes, got {}", max, len));
+            return false;
+        }
+        true
+    }
+
+    /// Parses a base64 DER (SPKI) RSA public key and checks its modulus size.
+    pub fn rsa_public_key(&mut self, field: &str, value: &str) -> Option<RsaPublicKey> {
+        let der = match general_purpose::STANDARD.decode(value) {
+            Ok(der) => der,
+            Err(_) => {
+                self.error(field, String::from("must be valid base64"));
+                return None;
+            }
+        };
+        let key = match RsaPublicKey::from_public_key_der(&der) {
+            Ok(key) => key,
+            Err(_) => {
+                self.error(field, String::from("must be a DER encoded SPKI RSA public key"));
+                return None;
+            }
+        };
+        self.rsa_modulus(field, &key).then_some(key)
+    }
+
+    /// Parses a base64 DER (PKCS#8) RSA private key and checks its modulus size.
+    /// The decoded DER is zeroized once parsed.
+    pub fn rsa_private_key(&mut self, field: &str, value: &str) -> Option<RsaPrivateKey> {
+        let der = match general_purpose::STANDARD.decode(value) {
+            Ok(der) => Zeroizing::new(der),
+            Err(_) => {
+                self.error(field, String::from("must be valid base64"));
+                return None;
+            }
+        };
+        let key = match RsaPrivateKey::from_pkcs8_der(&der) {
+            Ok(key) => key,
+            Err(_) => {
+                self.error(field, String::from("must be a DER encoded PKCS#8 RSA private key"));
+                return None;
+            }
+        };
+        self.rsa_modulus(field, &key).then_some(key)
+    }
+
+    fn rsa_modulus(&mut self, field: &str, key: &impl PublicKeyParts) -> bool {
+        let bits = key.n().bits();
+        if !(RSA_MIN_MODULUS_BITS..=RSA_MAX_MODULUS_BITS).contains(&bits) {
+            self.error(
+                field,
+                format!(
+                    "RSA modulus must be between {} and {} bits, got {}",
+                    RSA_MIN_MODULUS_BITS, RSA_MAX_MODULUS_BITS, bits
+                ),
+            );
+            return false;
+        }
+        true
+    }
+
+    /// Checks that an RSA ciphertext is exactly as long as the key's modulus.
+    pub fn rsa_ciphertext(&mut self, field: &str, value: &str, key: Option<&RsaPublicKey>) {
+        if let (Some(len), Some(key)) = (self.hex(field, value), key) {
+            if len != key.size() {
+                self.error(
+                    field,
+                    format!("must be {} bytes to match the RSA modulus, got {}", key.size(), len),
+                );
+            }
+        }
+    }
+
+    /// Checks that the OAEP label is hex of at most [`OAEP_MAX_LABEL_LEN`] bytes.
+    pub fn oaep(&mut self, field: &str, params: &OaepParams) {
+        if let Some(label_hex) = &params.label_hex {
+            self.hex_max(&format!("{}.label_hex", field), label_hex, OAEP_MAX_LABEL_LEN);
+        }
+    }
+
+    pub fn finish(self) -> Result<(), Vec<FieldError>> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use rand_chacha::rand_core::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use rsa::pkcs8::{EncodePrivateKey, EncodePublicKey};
+    use rsa::BigUint;
+
+    use crate::types::OaepDigest;
+
+    /// Runs `check` on a fresh validator and returns the reported errors.
+    fn errors(check: impl FnOnce(&mut Validator)) -> Vec<FieldError> {
+        let mut v = Validator::new();
+        check(&mut v);
+        v.finish().err().unwrap_or_default()
+    }
+
+    fn messages(check: impl FnOnce(&mut Validator)) -> Vec<String> {
+        errors(check).into_iter().map(|e| e.message).collect()
+    }
+
+    /// Base64 SPKI DER of a public key whose modulus has exactly `bits` bits.
+    /// The modulus is not a product of primes, which parsing does not check.
+    fn public_key_of_bits(bits: usize) -> String {
+        let n = (BigUint::from(1u8) << (bits - 1)) + BigUint::from(1u8);
+        let key = RsaPublicKey::new(n, BigUint::from(65537u32)).unwrap();
+        general_purpose::STANDARD.encode(key.to_public_key_der().unwrap().as_bytes())
+    }
+
+    fn private_key_of_bits(bits: usize) -> RsaPrivateKey {
+        RsaPrivateKey::new(&mut ChaCha20Rng::seed_from_u64(7), bits).unwrap()
+    }
+
+    #[test]
+    fn hex_checks_encoding_and_returns_the_decoded_length() {
+        let mut v = Validator::new();
+        assert_eq!(v.hex("iv_hex", "00ff0A"), Some(3));
+        assert_eq!(v.hex("iv_hex", ""), Some(0));
+        assert_eq!(v.finish(), Ok(()));
+
+        assert_eq!(messages(|v| assert_eq!(v.hex("iv_hex", "abc"), None)), ["hex string must have an even length"]);
+        assert_eq!(messages(|v| assert_eq!(v.hex("iv_hex", "0g"), None)), ["must only contain hex characters"]);
+        assert_eq!(errors(|v| {
+            v.hex("iv_hex", "0x00");
+        })[0].field, "iv_hex");
+    }
+
+    #[test]
+    fn hex_exact_requires_the_exact_length() {
+        assert!(errors(|v| assert!(v.hex_exact("aes_key_hex", &"ab".repeat(AES_KEY_LEN), AES_KEY_LEN))).is_empty());
+        assert_eq!(
+            messages(|v| assert!(!v.hex_exact("aes_key_hex", &"ab".repeat(16), AES_KEY_LEN))),
+            ["must be 32 bytes, got 16"]
+        );
+        // Malformed hex is reported once, not also as a length error
+        assert_eq!(messages(|v| assert!(!v.hex_exact("aes_key_hex", "zz", 1))).len(), 1);
+    }
+
+    #[test]
+    fn hex_max_caps_the_payload() {
+        let at_cap = "00".repeat(MAX_PAYLOAD_LEN);
+        assert!(errors(|v| assert_eq!(v.hex_max("ciphertext_hex", &at_cap, MAX_PAYLOAD_LEN), Some(MAX_PAYLOAD_LEN))).is_empty());
+
+        let over_cap = "00".repeat(MAX_PAYLOAD_LEN + 1);
+        assert_eq!(
+            messages(|v| assert_eq!(v.hex_max("ciphertext_hex", &over_cap, MAX_PAYLOAD_LEN), None)),
+            [format!("must be at most {} bytes, got {}", MAX_PAYLOAD_LEN, MAX_PAYLOAD_LEN + 1)]
+        );
+    }
+
+    #[test]
+    fn base64_exact_requires_valid_base64_of_the_exact_length() {
+        let key = general_purpose::STANDARD.encode([1u8; 32]);
+        assert!(errors(|v| assert!(v.base64_exact("eth_pubkey_base64", &key, 32))).is_empty());
+        assert_eq!(messages(|v| assert!(!v.base64_exact("eth_pubkey_base64", &key, 33))), ["must be 33 bytes, got 32"]);
+        assert_eq!(messages(|v| assert!(!v.base64_exact("eth_pubkey_base64", "not base64!", 32))), ["must be valid base64"]);
+    }
+
+    #[test]
+    fn max_len_caps_lengths() {
+        assert!(errors(|v| assert!(v.max_len("schema_json", MAX_JSON_SCHEMA_LEN, MAX_JSON_SCHEMA_LEN))).is_empty());
+        assert_eq!(
+            messages(|v| assert!(!v.max_len("schema_json", MAX_JSON_SCHEMA_LEN + 1, MAX_JSON_SCHEMA_LEN))),
+            ["must be at most 16384 bytes, got 16385"]
+        );
+    }
+
+    #[test]
+    fn rsa_public_key_bounds_the_modulus() {
+        for bits in [RSA_MIN_MODULUS_BITS, 3072, RSA_MAX_MODULUS_BITS] {
+            let key = public_key_of_bits(bits);
+            let mut v = Validator::new();
+            assert!(v.rsa_public_key("rsa_pubkey_base64", &key).is_some(), "{} bits", bits);
+            assert_eq!(v.finish(), Ok(()));
+        }
+
+        assert_eq!(
+            messages(|v| assert!(v.rsa_public_key("rsa_pubkey_base64", &public_key_of_bits(2047)).is_none())),
+            ["RSA modulus must be between 2048 and 4096 bits, got 2047"]
+        );
+        assert_eq!(messages(|v| {
+            v.rsa_public_key("rsa_pubkey_base64", "not base64!");
+        }), ["must be valid base64"]);
+        let not_a_key = general_purpose::STANDARD.encode(b"not a key");
+        assert_eq!(messages(|v| {
+            v.rsa_public_key("rsa_pubkey_base64", &not_a_key);
+        }), ["must be a DER encoded SPKI RSA public key"]);
+    }
+
+    #[test]
+    fn rsa_private_key_bounds_the_modulus() {
+        let encode = |key: &RsaPrivateKey| general_purpose::STANDARD.encode(key.to_pkcs8_der().unwrap().as_bytes());
+        let key = private_key_of_bits(2048);
+        let mut v = Validator::new();
+        assert_eq!(v.rsa_private_key("rsa_private_key_base64", &encode(&key)), Some(key));
+        assert_eq!(v.finish(), Ok(()));
+
+        let small = encode(&private_key_of_bits(1024));
+        assert_eq!(
+            messages(|v| assert!(v.rsa_private_key("rsa_private_key_base64", &small).is_none())),
+            ["RSA modulus must be between 2048 and 4096 bits, got 1024"]
+        );
+        let public_key = public_key_of_bits(2048);
+        assert_eq!(messages(|v| {
+            v.rsa_private_key("rsa_private_key_base64", &public_key);
+        }), ["must be a DER encoded PKCS#8 RSA private key"]);
+    }
+
+    #[test]
+    fn rsa_ciphertext_matches_the_modulus_length() {
+        let mut v = Validator::new();
+        let key = v.rsa_public_key("rsa_pubkey_base64", &public_key_of_bits(2048));
+        v.rsa_ciphertext("enc_aes_key_hex", &"00".repeat(256), key.as_ref());
+        assert_eq!(v.finish(), Ok(()));
+
+        let errors = errors(|v| {
+            let key = v.rsa_public_key("rsa_pubkey_base64", &public_key_of_bits(2048));
+            v.rsa_ciphertext("enc_aes_key_hex", &"00".repeat(128), key.as_ref());
+        });
+        assert_eq!(errors, [FieldError {
+            field: String::from("enc_aes_key_hex"),
+            message: String::from("must be 256 bytes to match the RSA modulus, got 128"),
+        }]);
+        // Without a parsed key only the encoding is checked
+        assert!(messages(|v| v.rsa_ciphertext("enc_aes_key_hex", "00", None)).is_empty());
+    }
+
+    #[test]
+    fn oaep_caps_the_label() {
+        let params = |label_hex: Option<String>| OaepParams {
+            digest: OaepDigest::Sha256,
+            mgf_digest: OaepDigest::Sha256,
+            label_hex,
+        };
+        assert!(errors(|v| v.oaep("oaep", &params(None))).is_empty());
+        assert!(errors(|v| v.oaep("oaep", &params(Some("00".repeat(OAEP_MAX_LABEL_LEN))))).is_empty());
+
+        let errors = errors(|v| v.oaep("old_oaep", &params(Some("00".repeat(OAEP_MAX_LABEL_LEN + 1)))));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "old_oaep.label_hex");
+    }
+
+    #[test]
+    fn nested_errors_are_prefixed() {
+        let nested = Err(vec![FieldError { field: String::from("iv_hex"), message: String::from("bad") }]);
+        let errors = errors(|v| {
+            v.extend_from(Ok(()));
+            v.extend_prefixed("previous", nested.clone());
+            v.extend_from(nested);
+        });
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["previous.iv_hex", "iv_hex"]);
+    }
+}